@@ -5,23 +5,23 @@ compile_error!("Cannot enable both 'dev' and 'prod' features");
 compile_error!("Either 'dev' or 'prod' feature must be enabled");
 
 use infra::{
-    external::mail::MailClientImpl,
+    external::{mail::MailClientImpl, problem_registry::CountedRegistryServer},
     provider::Provider,
     repository::{
         auth::AuthRepositoryImpl, dep_name::DepNameRepositoryImpl,
         editorial::EditorialRepositoryImpl, icon::IconRepositoryImpl,
         language::LanguageRepositoryImpl, problem::ProblemRepositoryImpl,
-        procedure::ProcedureRepositoryImpl, session::SessionRepositoryImpl,
-        submission::SubmissionRepositoryImpl, testcase::TestcaseRepositoryImpl,
-        user::UserRepositoryImpl,
+        procedure::ProcedureRepositoryImpl, resource_id_counter::ResourceIdCounterRepositoryImpl,
+        session::SessionRepositoryImpl, submission::SubmissionRepositoryImpl,
+        testcase::TestcaseRepositoryImpl, user::UserRepositoryImpl,
     },
 };
 use judge_core::logic::judge_service_impl::JudgeServiceImpl;
 use usecase::service::{
     auth::AuthenticationService, editorial::EditorialService, github_oauth2::GitHubOAuth2Service,
     google_oauth2::GoogleOAuth2Service, icon::IconService, language::LanguageService,
-    problem::ProblemService, submission::SubmissionService, testcase::TestcaseService,
-    traq_oauth2::TraqOAuth2Service, user::UserService,
+    problem::ProblemService, problem_package::ProblemPackageService, submission::SubmissionService,
+    testcase::TestcaseService, traq_oauth2::TraqOAuth2Service, user::UserService,
 };
 
 #[cfg(feature = "dev")]
@@ -38,12 +38,14 @@ use back_judge_grpc::client::RemoteJudgeServiceClient;
 use problem_registry::{client::ProblemRegistryClient, server::ProblemRegistryServer};
 
 #[cfg(feature = "dev")]
-type RegistryServerImpl = MockRegistryServer;
+type RegistryServerImpl =
+    CountedRegistryServer<MockRegistryServer, ResourceIdCounterRepositoryImpl>;
 #[cfg(feature = "dev")]
 type RegistryClientImpl = MockRegistryClient;
 
 #[cfg(feature = "prod")]
-type RegistryServerImpl = ProblemRegistryServer;
+type RegistryServerImpl =
+    CountedRegistryServer<ProblemRegistryServer, ResourceIdCounterRepositoryImpl>;
 #[cfg(feature = "prod")]
 type RegistryClientImpl = ProblemRegistryClient;
 
@@ -73,6 +75,17 @@ pub struct DiContainer {
         RegistryServerImpl,
         DepNameRepositoryImpl,
     >,
+    problem_package_service: ProblemPackageService<
+        ProblemRepositoryImpl,
+        UserRepositoryImpl,
+        SessionRepositoryImpl,
+        TestcaseRepositoryImpl,
+        ProcedureRepositoryImpl,
+        RegistryClientImpl,
+        RegistryServerImpl,
+        DepNameRepositoryImpl,
+        LanguageRepositoryImpl,
+    >,
     user_service: UserService<
         UserRepositoryImpl,
         SessionRepositoryImpl,
@@ -119,12 +132,18 @@ pub struct DiContainer {
 impl DiContainer {
     pub async fn new(provider: Provider) -> Self {
         #[cfg(feature = "dev")]
-        let pr_server: RegistryServerImpl = provider.provide_problem_registry_server();
+        let pr_server: RegistryServerImpl = CountedRegistryServer::new(
+            provider.provide_problem_registry_server(),
+            provider.provide_resource_id_counter_repository(),
+        );
         #[cfg(feature = "dev")]
         let pr_client: RegistryClientImpl = provider.provide_problem_registry_client();
 
         #[cfg(feature = "prod")]
-        let pr_server: RegistryServerImpl = ProblemRegistryServer::new().await;
+        let pr_server: RegistryServerImpl = CountedRegistryServer::new(
+            ProblemRegistryServer::new().await,
+            provider.provide_resource_id_counter_repository(),
+        );
         #[cfg(feature = "prod")]
         let pr_client: RegistryClientImpl = ProblemRegistryClient::new().await;
 
@@ -155,6 +174,17 @@ impl DiContainer {
                 pr_server.clone(),
                 provider.provide_dep_name_repository(),
             ),
+            problem_package_service: ProblemPackageService::new(
+                provider.provide_problem_repository(),
+                provider.provide_user_repository(),
+                provider.provide_session_repository(),
+                provider.provide_testcase_repository(),
+                provider.provide_procedure_repository(),
+                pr_client.clone(),
+                pr_server.clone(),
+                provider.provide_dep_name_repository(),
+                provider.provide_language_repository(),
+            ),
             user_service: UserService::new(
                 provider.provide_user_repository(),
                 provider.provide_session_repository(),
@@ -270,6 +300,22 @@ impl DiContainer {
         &self.problem_service
     }
 
+    pub fn problem_package_service(
+        &self,
+    ) -> &ProblemPackageService<
+        ProblemRepositoryImpl,
+        UserRepositoryImpl,
+        SessionRepositoryImpl,
+        TestcaseRepositoryImpl,
+        ProcedureRepositoryImpl,
+        RegistryClientImpl,
+        RegistryServerImpl,
+        DepNameRepositoryImpl,
+        LanguageRepositoryImpl,
+    > {
+        &self.problem_package_service
+    }
+
     pub fn editorial_service(
         &self,
     ) -> &EditorialService<SessionRepositoryImpl, EditorialRepositoryImpl, ProblemRepositoryImpl>
@@ -42,6 +42,14 @@ pub fn make_router(di_container: DiContainer) -> Router {
         .route(
             "/:submissionId/rejudge",
             post(submissions::post_rejudge_submission),
+        )
+        .route(
+            "/:submissionId/trace",
+            get(submissions::get_submission_trace),
+        )
+        .route(
+            "/:submissionId/timeline",
+            get(submissions::get_submission_timeline),
         );
 
     let problem_router = Router::new()
@@ -49,12 +57,18 @@ pub fn make_router(di_container: DiContainer) -> Router {
             "/",
             post(problems::post_problem).get(problems::get_problems),
         )
+        .route("/import", post(problems::post_import_problem))
+        .route(
+            "/import/polygon",
+            post(problems::post_import_polygon_problem),
+        )
         .route(
             "/:problemId",
             get(problems::get_problem)
                 .put(problems::put_problem)
                 .delete(problems::delete_problem),
         )
+        .route("/:problemId/export", get(problems::get_export_problem))
         .route(
             "/:problemId/submissions",
             post(submissions::post_submission),
@@ -66,6 +80,10 @@ pub fn make_router(di_container: DiContainer) -> Router {
         .route(
             "/:problemId/testcases",
             get(testcase::get_testcases).post(testcase::post_testcase),
+        )
+        .route(
+            "/:problemId/testcase-groups",
+            get(testcase::get_testcase_groups).put(testcase::put_testcase_groups),
         );
 
     let editorials_router = Router::new().route(
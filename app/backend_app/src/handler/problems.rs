@@ -1,13 +1,14 @@
 use crate::di::DiContainer;
 use crate::model::error::AppError;
 use crate::model::problems::{
-    CreateNormalProblem, ProblemGetQuery, ProblemOrderBy, ProblemResponse,
+    CreateNormalProblem, PolygonImportResponse, ProblemGetQuery, ProblemOrderBy, ProblemResponse,
     ProblemSummariesResponses, UpdateNormalProblem,
 };
 use axum::{
     Json,
+    body::Bytes,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
 use axum_extra::{TypedHeader, headers::Cookie};
@@ -148,3 +149,70 @@ pub async fn delete_problem(
         Err(e) => Err(AppError(e).into()),
     }
 }
+
+pub async fn get_export_problem(
+    State(di_container): State<DiContainer>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+    Path(problem_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .problem_package_service()
+        .export_problem(session_id, problem_id.clone())
+        .await
+    {
+        Ok(archive) => {
+            let mut headers = HeaderMap::new();
+            headers.insert("Content-Type", "application/x-tar".parse().unwrap());
+            headers.insert(
+                "Content-Disposition",
+                format!("attachment; filename=\"problem_{}.tar\"", problem_id)
+                    .parse()
+                    .unwrap(),
+            );
+            Ok((headers, archive))
+        }
+        Err(e) => Err(AppError(e).into()),
+    }
+}
+
+pub async fn post_import_problem(
+    State(di_container): State<DiContainer>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+    body: Bytes,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .problem_package_service()
+        .import_problem(session_id, body.to_vec())
+        .await
+    {
+        Ok(problem) => {
+            let resp = ProblemResponse::from(problem);
+            Ok((StatusCode::OK, Json(resp)))
+        }
+        Err(e) => Err(AppError(e).into()),
+    }
+}
+
+pub async fn post_import_polygon_problem(
+    State(di_container): State<DiContainer>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+    body: Bytes,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .problem_package_service()
+        .import_polygon_problem(session_id, body.to_vec())
+        .await
+    {
+        Ok(import) => {
+            let resp = PolygonImportResponse::from(import);
+            Ok((StatusCode::OK, Json(resp)))
+        }
+        Err(e) => Err(AppError(e).into()),
+    }
+}
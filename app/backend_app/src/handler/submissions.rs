@@ -1,13 +1,14 @@
 use crate::model::error::AppError;
 use crate::model::submissions::{
-    CreateSubmission, SubmissionOrderBy, SubmissionResponse, SubmissionSummariesResponse,
+    CreateSubmission, ExecutionTimelineEntryResponse, SubmissionOrderBy, SubmissionResponse,
+    SubmissionSummariesResponse,
 };
 use crate::{di::DiContainer, model::submissions::SubmissionGetQuery};
 use axum::extract::Query;
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
 use axum_extra::{TypedHeader, headers::Cookie};
@@ -130,3 +131,51 @@ pub async fn post_rejudge_submission(
         Err(e) => Err(AppError(e).into()),
     }
 }
+
+pub async fn get_submission_trace(
+    State(di_container): State<DiContainer>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+    Path(submission_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .submission_service()
+        .get_judge_trace(session_id, submission_id.clone())
+        .await
+    {
+        Ok(bundle) => {
+            let mut headers = HeaderMap::new();
+            headers.insert("Content-Type", "application/x-tar".parse().unwrap());
+            headers.insert(
+                "Content-Disposition",
+                format!("attachment; filename=\"trace_{}.tar\"", submission_id)
+                    .parse()
+                    .unwrap(),
+            );
+            Ok((headers, bundle))
+        }
+        Err(e) => Err(AppError(e).into()),
+    }
+}
+
+pub async fn get_submission_timeline(
+    State(di_container): State<DiContainer>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+    Path(submission_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .submission_service()
+        .get_execution_timeline(session_id, submission_id)
+        .await
+    {
+        Ok(timeline) => {
+            let resp: Vec<ExecutionTimelineEntryResponse> =
+                timeline.into_iter().map(|entry| entry.into()).collect();
+            Ok((StatusCode::OK, Json(resp)))
+        }
+        Err(e) => Err(AppError(e).into()),
+    }
+}
@@ -11,7 +11,8 @@ use crate::{
     model::{
         error::AppError,
         testcase::{
-            CreateTestcaseRequest, TestcaseResponse, TestcaseSummaryResponse, UpdateTestcaseRequest,
+            CreateTestcaseRequest, TestcaseGroupResponse, TestcaseResponse,
+            TestcaseSummaryResponse, UpdateTestcaseGroupRequest, UpdateTestcaseRequest,
         },
     },
 };
@@ -115,3 +116,48 @@ pub async fn delete_testcase(
         Err(e) => Err(AppError(e).into()),
     }
 }
+
+pub async fn get_testcase_groups(
+    State(di_container): State<DiContainer>,
+    Path(problem_id): Path<String>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .testcase_service()
+        .get_testcase_groups(session_id, problem_id)
+        .await
+    {
+        Ok(groups) => {
+            let resp = groups
+                .into_iter()
+                .map(TestcaseGroupResponse::from)
+                .collect::<Vec<_>>();
+            Ok((StatusCode::OK, Json(resp)))
+        }
+        Err(e) => Err(AppError(e).into()),
+    }
+}
+
+pub async fn put_testcase_groups(
+    State(di_container): State<DiContainer>,
+    Path(problem_id): Path<String>,
+    TypedHeader(cookie): TypedHeader<Cookie>,
+    Json(groups): Json<Vec<UpdateTestcaseGroupRequest>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let session_id = cookie.get("session_id");
+
+    match di_container
+        .testcase_service()
+        .put_testcase_groups(
+            session_id,
+            problem_id,
+            groups.into_iter().map(|x| x.into()).collect(),
+        )
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(AppError(e).into()),
+    }
+}
@@ -1,7 +1,10 @@
 use crate::model::testcase::TestcaseSummaryResponse;
 use async_session::chrono;
 use serde::{Deserialize, Serialize};
-use usecase::model::problem::{NormalProblemDto, NormalProblemSummaryDto, NormalProblemsDto};
+use usecase::model::{
+    polygon_package::PolygonImportDto,
+    problem::{NormalProblemDto, NormalProblemSummaryDto, NormalProblemsDto},
+};
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,6 +42,23 @@ impl From<NormalProblemDto> for ProblemResponse {
     }
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolygonImportResponse {
+    pub problem: ProblemResponse,
+    /// Parts of the Polygon package that were not imported
+    pub unsupported: Vec<String>,
+}
+
+impl From<PolygonImportDto> for PolygonImportResponse {
+    fn from(import: PolygonImportDto) -> Self {
+        PolygonImportResponse {
+            problem: import.problem.into(),
+            unsupported: import.unsupported,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProblemSummaryResponse {
@@ -1,7 +1,8 @@
 use async_session::chrono;
 use serde::{Deserialize, Serialize};
 use usecase::model::submission::{
-    JudgeResultDto, SubmissionDto, SubmissionSummaryDto, SubmissionsDto,
+    ExecutionTimelineEntryDto, GroupJudgeResultDto, JudgeResultDto, SubmissionDto,
+    SubmissionSummaryDto, SubmissionsDto,
 };
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,7 +21,9 @@ pub struct SubmissionResponse {
     pub source_code: String,
     pub code_length: i32,
     pub overall_judge_status: String,
+    pub compile_log: Option<String>,
     pub judge_results: Vec<JudgeResultResponse>,
+    pub group_results: Vec<GroupJudgeResultResponse>,
 }
 
 impl From<SubmissionDto> for SubmissionResponse {
@@ -39,7 +42,9 @@ impl From<SubmissionDto> for SubmissionResponse {
             source_code: val.source_code,
             code_length: val.code_length,
             overall_judge_status: val.overall_judge_status,
+            compile_log: val.compile_log,
             judge_results: val.judge_results.into_iter().map(|x| x.into()).collect(),
+            group_results: val.group_results.into_iter().map(|x| x.into()).collect(),
         }
     }
 }
@@ -53,6 +58,11 @@ pub struct JudgeResultResponse {
     pub score: i64,
     pub time: i32,
     pub memory: i32,
+    pub message: Option<String>,
+    pub stderr: Option<String>,
+    pub signal: Option<i32>,
+    pub cpu_time: Option<i32>,
+    pub wall_time: Option<i32>,
 }
 
 impl From<JudgeResultDto> for JudgeResultResponse {
@@ -64,6 +74,61 @@ impl From<JudgeResultDto> for JudgeResultResponse {
             score: val.score,
             time: val.time_ms,
             memory: val.memory_kib,
+            message: val.message,
+            stderr: val.stderr,
+            signal: val.signal,
+            cpu_time: val.cpu_time_ms,
+            wall_time: val.wall_time_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupJudgeResultResponse {
+    pub group_name: String,
+    pub judge_status: String,
+    pub score: i64,
+}
+
+impl From<GroupJudgeResultDto> for GroupJudgeResultResponse {
+    fn from(val: GroupJudgeResultDto) -> Self {
+        GroupJudgeResultResponse {
+            group_name: val.group_name,
+            judge_status: val.judge_status,
+            score: val.score,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionTimelineEntryResponse {
+    pub dep_id: String,
+    pub name: String,
+    pub judge_status: String,
+    pub ready_at_ms: i64,
+    pub started_at_ms: Option<i64>,
+    pub finished_at_ms: i64,
+    pub host: Option<String>,
+    pub retry_count: i32,
+    pub outcome_size_bytes: Option<i64>,
+    pub cached: bool,
+}
+
+impl From<ExecutionTimelineEntryDto> for ExecutionTimelineEntryResponse {
+    fn from(val: ExecutionTimelineEntryDto) -> Self {
+        ExecutionTimelineEntryResponse {
+            dep_id: val.dep_id,
+            name: val.name,
+            judge_status: val.judge_status,
+            ready_at_ms: val.ready_at_ms,
+            started_at_ms: val.started_at_ms,
+            finished_at_ms: val.finished_at_ms,
+            host: val.host,
+            retry_count: val.retry_count,
+            outcome_size_bytes: val.outcome_size_bytes,
+            cached: val.cached,
         }
     }
 }
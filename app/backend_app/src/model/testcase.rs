@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::types::chrono;
 use usecase::model::testcase::{
-    CreateTestcaseData, TestcaseDto, TestcaseSummaryDto, UpdateTestcaseData,
+    CreateTestcaseData, TestcaseDto, TestcaseGroupDto, TestcaseSummaryDto, UpdateTestcaseData,
+    UpdateTestcaseGroupData,
 };
 use uuid::Uuid;
 
@@ -10,6 +11,7 @@ use uuid::Uuid;
 pub struct TestcaseSummaryResponse {
     pub id: Uuid,
     pub name: String,
+    pub group: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -19,6 +21,7 @@ impl From<TestcaseSummaryDto> for TestcaseSummaryResponse {
         TestcaseSummaryResponse {
             id: testcase.id,
             name: testcase.name,
+            group: testcase.group,
             created_at: testcase.created_at,
             updated_at: testcase.updated_at,
         }
@@ -30,6 +33,7 @@ impl From<TestcaseSummaryDto> for TestcaseSummaryResponse {
 pub struct TestcaseResponse {
     pub id: Uuid,
     pub name: String,
+    pub group: Option<String>,
     pub test_input: String,
     pub test_output: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -41,6 +45,7 @@ impl From<TestcaseDto> for TestcaseResponse {
         TestcaseResponse {
             id: testcase.id,
             name: testcase.name,
+            group: testcase.group,
             test_input: testcase.input,
             test_output: testcase.output,
             created_at: testcase.created_at,
@@ -55,6 +60,7 @@ pub struct CreateTestcaseRequest {
     pub name: String,
     pub test_input: String,
     pub test_output: String,
+    pub group: Option<String>,
 }
 
 impl From<CreateTestcaseRequest> for CreateTestcaseData {
@@ -63,6 +69,7 @@ impl From<CreateTestcaseRequest> for CreateTestcaseData {
             name: request.name,
             input: request.test_input,
             output: request.test_output,
+            group: request.group,
         }
     }
 }
@@ -73,6 +80,7 @@ pub struct UpdateTestcaseRequest {
     pub name: String,
     pub test_input: String,
     pub test_output: String,
+    pub group: Option<String>,
 }
 
 impl From<UpdateTestcaseRequest> for UpdateTestcaseData {
@@ -81,6 +89,43 @@ impl From<UpdateTestcaseRequest> for UpdateTestcaseData {
             name: request.name,
             input: request.test_input,
             output: request.test_output,
+            group: request.group,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestcaseGroupResponse {
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
+}
+
+impl From<TestcaseGroupDto> for TestcaseGroupResponse {
+    fn from(group: TestcaseGroupDto) -> Self {
+        TestcaseGroupResponse {
+            name: group.name,
+            point: group.point,
+            policy: group.policy,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTestcaseGroupRequest {
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
+}
+
+impl From<UpdateTestcaseGroupRequest> for UpdateTestcaseGroupData {
+    fn from(request: UpdateTestcaseGroupRequest) -> Self {
+        UpdateTestcaseGroupData {
+            name: request.name,
+            point: request.point,
+            policy: request.policy,
         }
     }
 }
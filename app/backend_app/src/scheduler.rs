@@ -24,8 +24,9 @@ pub async fn init_scheduler(provider: &Provider) -> anyhow::Result<JobScheduler>
         let problem_registry_server = Arc::clone(&problem_registry_server);
 
         Box::pin(async move {
+            // 登録に再利用されないよう、消す前に行を押さえる
             let deletable_ids = resource_id_counter_repo
-                .get_deletable_resource_ids(10)
+                .claim_deletable_resource_ids(10)
                 .await
                 .unwrap_or_else(|e| {
                     tracing::error!("Failed to get deletable resource IDs: {}", e);
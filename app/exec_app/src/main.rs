@@ -24,7 +24,7 @@ use std::sync::Arc;
 use std::{env, fs};
 use tar::Archive;
 use tokio::signal;
-use tokio::time::timeout;
+use tokio::time::{Duration, error::Elapsed, timeout};
 use tonic::async_trait;
 use tonic::codegen::tokio_stream::StreamExt;
 use tonic::{Request, Response, Status, transport::Server};
@@ -84,10 +84,13 @@ impl ExecApp {
             .unwrap();
     }
 
+    /// Only the script itself runs against `exec_time`, so that unpacking the dependencies
+    /// and starting the container do not eat into it
     async fn execute_container(
         &self,
         dependency: Vec<Dependency>,
-    ) -> Result<ExecuteResponse, anyhow::Error> {
+        exec_time: Duration,
+    ) -> Result<Result<ExecuteResponse, Elapsed>, anyhow::Error> {
         println!("{:?}", dependency);
         tracing::info!("writing outcomes");
         // write outcomes to /outcomes (dir in host)
@@ -185,9 +188,52 @@ impl ExecApp {
         tracing::info!("output: {:?}", message);
 
         // get exec result
+        let (stdout, stderr) = match timeout(exec_time, self.attach_exec(&message.id)).await {
+            Ok(result) => result?,
+            Err(elapsed) => return Ok(Err(elapsed)),
+        };
+
+        // get exec info
+        let info = self.docker_api.inspect_exec(&message.id).await?;
+
+        let mut ouput = self.docker_api.download_from_container(
+            ExecApp::DOCKER_CONTAINER_NAME,
+            Some(DownloadFromContainerOptions::<String> {
+                // TODO OUTPUT_PATHを実行時に渡す
+                path: dependency
+                    .iter()
+                    .filter(|dep| dep.envvar == OUTPUT_PATH)
+                    .map(|dep| format!("/outcomes/{}", dep.outcome_uuid))
+                    .next()
+                    .expect(format!("outcome \"{}\" not found", OUTPUT_PATH).as_str()),
+            }),
+        );
+
+        let mut tar_bytes: Vec<u8> = vec![];
+        while let Some(Ok(chunk)) = ouput.next().await {
+            tar_bytes.extend_from_slice(&chunk);
+        }
+
+        let mut gz_bytes = vec![];
+        let mut encoder = GzEncoder::new(&mut gz_bytes, Compression::default());
+        std::io::Write::write_all(&mut encoder, &tar_bytes)?;
+        encoder.finish()?;
+
+        Ok(Ok(ExecuteResponse {
+            output: Some(Output {
+                exit_code: info.exit_code.context("failed to parse exit code")? as i32,
+                stdout,
+                stderr,
+            }),
+            outcome: gz_bytes,
+        }))
+    }
+
+    /// Starts the exec and collects its stdout and stderr once it has finished
+    async fn attach_exec(&self, exec_id: &str) -> Result<(String, String), anyhow::Error> {
         let result = self
             .docker_api
-            .start_exec(&message.id, None::<StartExecOptions>)
+            .start_exec(exec_id, None::<StartExecOptions>)
             .await?;
         let mut stdout = String::new();
         let mut stderr = String::new();
@@ -207,27 +253,12 @@ impl ExecApp {
                     let current_secs = total_usage as f64 / 1_000_000_000.0;
                     tracing::info!("Current CPU usage sum: {:.2}s", current_secs);
 
-                    if let Ok(exec_info) = self.docker_api.inspect_exec(&message.id).await {
+                    if let Ok(exec_info) = self.docker_api.inspect_exec(exec_id).await {
                         if !exec_info.running.unwrap_or(false) {
                             tracing::info!("Exec process has finished");
                             break;
                         }
                     }
-
-                    if current_secs > 60.0 {
-                        tracing::info!(
-                            "Container [{}] exceeded CPU limit: {:.2}s",
-                            ExecApp::DOCKER_CONTAINER_NAME,
-                            current_secs
-                        );
-
-                        self.terminate_container().await;
-
-                        return Err(anyhow::anyhow!(
-                            "Execution time limit exceeded: {:.2}s",
-                            current_secs
-                        ));
-                    }
                 }
                 while let Some(Ok(msg)) = output.next().await {
                     match msg {
@@ -250,40 +281,7 @@ impl ExecApp {
             }
         }
 
-        // get exec info
-        let info = self.docker_api.inspect_exec(&message.id).await?;
-
-        let mut ouput = self.docker_api.download_from_container(
-            ExecApp::DOCKER_CONTAINER_NAME,
-            Some(DownloadFromContainerOptions::<String> {
-                // TODO OUTPUT_PATHを実行時に渡す
-                path: dependency
-                    .iter()
-                    .filter(|dep| dep.envvar == OUTPUT_PATH)
-                    .map(|dep| format!("/outcomes/{}", dep.outcome_uuid))
-                    .next()
-                    .expect(format!("outcome \"{}\" not found", OUTPUT_PATH).as_str()),
-            }),
-        );
-
-        let mut tar_bytes: Vec<u8> = vec![];
-        while let Some(Ok(chunk)) = ouput.next().await {
-            tar_bytes.extend_from_slice(&chunk);
-        }
-
-        let mut gz_bytes = vec![];
-        let mut encoder = GzEncoder::new(&mut gz_bytes, Compression::default());
-        std::io::Write::write_all(&mut encoder, &tar_bytes)?;
-        encoder.finish()?;
-
-        Ok(ExecuteResponse {
-            output: Some(Output {
-                exit_code: info.exit_code.context("failed to parse exit code")? as i32,
-                stdout,
-                stderr,
-            }),
-            outcome: gz_bytes,
-        })
+        Ok((stdout, stderr))
     }
 }
 
@@ -294,14 +292,15 @@ impl ExecuteService for ExecApp {
         request: Request<ExecuteRequest>,
     ) -> Result<Response<ExecuteResponse>, Status> {
         let request = request.into_inner();
-        let exec_result = timeout(
-            std::time::Duration::from_millis(request.exec_time_ms as u64),
-            self.execute_container(request.dependency),
-        );
+        // exec_time_ms は壁時計での制限時間
+        let exec_time_ms = request.exec_time_ms.max(0) as u64;
+        let exec_result = self
+            .execute_container(request.dependency, Duration::from_millis(exec_time_ms))
+            .await;
         self.terminate_container().await;
-        Ok(Response::new(match exec_result.await {
+        Ok(Response::new(match exec_result {
             Ok(Ok(response)) => response,
-            Ok(Err(e)) => ExecuteResponse {
+            Err(e) => ExecuteResponse {
                 // todo
                 output: Some(Output {
                     exit_code: 1,
@@ -310,15 +309,13 @@ impl ExecuteService for ExecApp {
                 }),
                 outcome: vec![],
             },
-            Err(elapsed) => ExecuteResponse {
-                // todo
-                output: Some(Output {
-                    exit_code: 1,
-                    stdout: "timeout".to_string(),
-                    stderr: elapsed.to_string(),
-                }),
-                outcome: vec![],
-            },
+            Ok(Err(_)) => {
+                tracing::info!("Execution exceeded {} ms", exec_time_ms);
+                return Err(Status::deadline_exceeded(format!(
+                    "Execution exceeded {} ms",
+                    exec_time_ms
+                )));
+            }
         }))
     }
 }
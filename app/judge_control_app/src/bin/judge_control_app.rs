@@ -23,7 +23,11 @@ async fn main() {
     let problem_registry_client = ProblemRegistryClient::new().await;
     let job_service = JobService::new(aws_client, grpc_client_factory, problem_registry_client);
     tracing::info!("JobService created");
-    let inner_judge_service = JudgeServiceImpl::new(job_service);
+    let mut inner_judge_service = JudgeServiceImpl::new(job_service);
+    if let Ok(trace_dir) = std::env::var("TRAOJUDGE_TRACE_DIR") {
+        tracing::info!("Recording judge traces in {}", trace_dir);
+        inner_judge_service = inner_judge_service.with_trace_dir(trace_dir.into());
+    }
     tracing::info!("JudgeServiceImpl created");
     let wrapped_judge_service = WrappedJudgeService::new(inner_judge_service);
     let grpc_service = JudgeServiceServer::new(wrapped_judge_service);
@@ -0,0 +1,47 @@
+use judge_core::logic::trace_replayer;
+use judge_core::model::judge_trace::JudgeTrace;
+use judge_infra_mock::job_service::job_service::JobService;
+use judge_infra_mock::multi_proc_problem_registry::registry_client::RegistryClient;
+
+/// Re-runs one execution of a trace bundle on the local docker job service.
+///
+/// Usage: `replay <trace bundle> <dep id>`
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let mut args = std::env::args().skip(1);
+    let (Some(bundle_path), Some(dep_id)) = (args.next(), args.next()) else {
+        anyhow::bail!("Usage: replay <trace bundle> <dep id>");
+    };
+    let dep_id = uuid::Uuid::parse_str(&dep_id)?.into();
+    let host_temp_dir = std::env::var("TRAOJUDGE_JOB_SERVICE_CACHE_DIR")
+        .unwrap_or_else(|_| "/tmp/traojudge-replay".to_string());
+    let image =
+        std::env::var("TRAO_EXEC_IMAGE").unwrap_or_else(|_| "traojudge/exec:latest".to_string());
+
+    let trace = JudgeTrace::from_bundle(&std::fs::read(&bundle_path)?)?;
+    // 依存はすべてトレースから置くので、レジストリは使われない
+    let problem_registry_client = RegistryClient::new(host_temp_dir.clone().into());
+    let job_service = JobService::new(
+        host_temp_dir.into(),
+        "/tmp/trao".into(),
+        problem_registry_client,
+        image,
+    )?;
+    let replayed = trace_replayer::replay(&job_service, &trace, dep_id).await?;
+
+    let recorded = trace
+        .execution(dep_id)
+        .and_then(|execution| execution.result.as_ref());
+    println!("recorded: {:?}", recorded);
+    println!("replayed: {:?}", replayed.result);
+    if let Some(output) = replayed.output {
+        println!("exit status: {}", output.status);
+        println!("stdout:\n{}", String::from_utf8_lossy(&output.stdout));
+        println!("stderr:\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(())
+}
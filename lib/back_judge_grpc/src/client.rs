@@ -1,6 +1,7 @@
 use crate::*;
 use anyhow::Result;
-use judge_core::model::judge;
+use futures::StreamExt;
+use judge_core::model::{identifiers, judge, judge_output};
 
 #[derive(Debug, Clone)]
 pub struct RemoteJudgeServiceClient {
@@ -30,4 +31,53 @@ impl judge::JudgeService for RemoteJudgeServiceClient {
         let response: judge::JudgeResponse = grpc_response.into();
         response
     }
+
+    fn judge_stream(&self, request: judge::JudgeRequest) -> judge::JudgeResponseStream {
+        let grpc_request: generated::JudgeRequest = request.into();
+        let mut grpc_client = self.grpc_client.clone();
+        futures::stream::once(async move {
+            grpc_client
+                .judge_stream(tonic::Request::new(grpc_request))
+                .await
+        })
+        .flat_map(|grpc_response| match grpc_response {
+            Ok(grpc_response) => grpc_response
+                .into_inner()
+                .map(|item| {
+                    let item = item.map_err(|e| {
+                        anyhow::anyhow!("Failed to receive remote judge result: {}", e)
+                    })?;
+                    let result: (identifiers::DepId, judge_output::ExecutionJobReport) =
+                        item.try_into()?;
+                    Ok(result)
+                })
+                .boxed(),
+            Err(e) => futures::stream::iter([Err(anyhow::anyhow!(
+                "Failed to execute remote judge: {}",
+                e
+            ))])
+            .boxed(),
+        })
+        .boxed()
+    }
+
+    async fn cancel(&self, judge_id: identifiers::JudgeId) -> anyhow::Result<()> {
+        let grpc_request: generated::CancelRequest = judge_id.into();
+        let mut grpc_client = self.grpc_client.clone();
+        grpc_client
+            .cancel(tonic::Request::new(grpc_request))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to cancel remote judge: {}", e))?;
+        Ok(())
+    }
+    async fn trace(&self, judge_id: identifiers::JudgeId) -> anyhow::Result<Option<Vec<u8>>> {
+        let grpc_request: generated::GetTraceRequest = judge_id.into();
+        let mut grpc_client = self.grpc_client.clone();
+        let grpc_response = grpc_client
+            .get_trace(tonic::Request::new(grpc_request))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch remote judge trace: {}", e))?
+            .into_inner();
+        Ok(grpc_response.bundle)
+    }
 }
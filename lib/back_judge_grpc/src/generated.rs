@@ -1,7 +1,7 @@
 include!("generated/_.rs");
 
 use anyhow::{Error, Result};
-use judge_core::model::procedure::registered;
+use judge_core::model::procedure::{condition, registered};
 use judge_core::model::*;
 use std::collections::HashMap;
 
@@ -75,6 +75,70 @@ impl TryFrom<Text> for registered::Text {
     }
 }
 
+impl From<registered::Binary> for Binary {
+    fn from(binary: registered::Binary) -> Self {
+        let dep_id: uuid::Uuid = binary.dep_id.into();
+        let dep_id: Uuid = dep_id.into();
+        let resource_id: uuid::Uuid = binary.resource_id.into();
+        let resource_id: Uuid = resource_id.into();
+        Self {
+            resource_id: Some(resource_id),
+            dep_id: Some(dep_id),
+        }
+    }
+}
+
+impl TryFrom<Binary> for registered::Binary {
+    type Error = Error;
+    fn try_from(binary: Binary) -> Result<Self> {
+        let resource_id = binary
+            .resource_id
+            .ok_or(anyhow::anyhow!("resource_id is missing"))?;
+        let resource_id: uuid::Uuid = resource_id.into();
+        let resource_id: identifiers::ResourceId = resource_id.into();
+        let dep_id = binary.dep_id.ok_or(anyhow::anyhow!("dep_id is missing"))?;
+        let dep_id: uuid::Uuid = dep_id.into();
+        let dep_id: identifiers::DepId = dep_id.into();
+        Ok(registered::Binary {
+            resource_id,
+            dep_id,
+        })
+    }
+}
+
+impl From<registered::Directory> for Directory {
+    fn from(directory: registered::Directory) -> Self {
+        let dep_id: uuid::Uuid = directory.dep_id.into();
+        let dep_id: Uuid = dep_id.into();
+        let resource_id: uuid::Uuid = directory.resource_id.into();
+        let resource_id: Uuid = resource_id.into();
+        Self {
+            resource_id: Some(resource_id),
+            dep_id: Some(dep_id),
+        }
+    }
+}
+
+impl TryFrom<Directory> for registered::Directory {
+    type Error = Error;
+    fn try_from(directory: Directory) -> Result<Self> {
+        let resource_id = directory
+            .resource_id
+            .ok_or(anyhow::anyhow!("resource_id is missing"))?;
+        let resource_id: uuid::Uuid = resource_id.into();
+        let resource_id: identifiers::ResourceId = resource_id.into();
+        let dep_id = directory
+            .dep_id
+            .ok_or(anyhow::anyhow!("dep_id is missing"))?;
+        let dep_id: uuid::Uuid = dep_id.into();
+        let dep_id: identifiers::DepId = dep_id.into();
+        Ok(registered::Directory {
+            resource_id,
+            dep_id,
+        })
+    }
+}
+
 impl From<registered::EmptyDirectory> for EmptyDirectory {
     fn from(empty_directory: registered::EmptyDirectory) -> Self {
         let uuid: uuid::Uuid = empty_directory.dep_id.into();
@@ -95,6 +159,42 @@ impl TryFrom<EmptyDirectory> for registered::EmptyDirectory {
     }
 }
 
+impl From<condition::Condition> for Condition {
+    fn from(condition: condition::Condition) -> Self {
+        let (statuses, negated) = match condition {
+            condition::Condition::StatusIn(statuses) => (statuses, false),
+            condition::Condition::StatusNotIn(statuses) => (statuses, true),
+        };
+        let statuses = statuses
+            .into_iter()
+            .map(|status| {
+                let status: JudgeStatus = status.into();
+                status.into()
+            })
+            .collect();
+        Self { statuses, negated }
+    }
+}
+
+impl TryFrom<Condition> for condition::Condition {
+    type Error = Error;
+    fn try_from(condition: Condition) -> Result<Self> {
+        let statuses = condition
+            .statuses
+            .into_iter()
+            .map(|status| {
+                let status = JudgeStatus::try_from(status)?;
+                Ok(status.into())
+            })
+            .collect::<Result<Vec<judge_output::JudgeStatus>>>()?;
+        if condition.negated {
+            Ok(condition::Condition::StatusNotIn(statuses))
+        } else {
+            Ok(condition::Condition::StatusIn(statuses))
+        }
+    }
+}
+
 impl From<registered::Dependency> for Dependency {
     fn from(dependency: registered::Dependency) -> Self {
         let uuid: uuid::Uuid = dependency.dep_id.into();
@@ -102,6 +202,7 @@ impl From<registered::Dependency> for Dependency {
         Self {
             dep_id: Some(uuid),
             envvar_name: dependency.envvar_name,
+            condition: dependency.condition.map(|condition| condition.into()),
         }
     }
 }
@@ -114,9 +215,14 @@ impl TryFrom<Dependency> for registered::Dependency {
             .ok_or(anyhow::anyhow!("uuid is missing"))?;
         let uuid: uuid::Uuid = uuid.into();
         let uuid: identifiers::DepId = uuid.into();
+        let condition = dependency
+            .condition
+            .map(|condition| condition.try_into())
+            .transpose()?;
         Ok(registered::Dependency {
             dep_id: uuid,
             envvar_name: dependency.envvar_name,
+            condition,
         })
     }
 }
@@ -134,6 +240,7 @@ impl From<registered::Execution> for Execution {
             dependencies,
             dep_id: Some(dep_id),
             time_reserved_ms: execution.time_reserved_ms,
+            no_cache: execution.no_cache,
         }
     }
 }
@@ -155,6 +262,7 @@ impl TryFrom<Execution> for registered::Execution {
             dependencies,
             dep_id,
             time_reserved_ms: execution.time_reserved_ms,
+            no_cache: execution.no_cache,
         })
     }
 }
@@ -171,6 +279,16 @@ impl From<registered::Procedure> for Procedure {
             .into_iter()
             .map(|text| text.into())
             .collect::<prost::alloc::vec::Vec<_>>();
+        let binaries = procedure
+            .binaries
+            .into_iter()
+            .map(|binary| binary.into())
+            .collect::<prost::alloc::vec::Vec<_>>();
+        let directories = procedure
+            .directories
+            .into_iter()
+            .map(|directory| directory.into())
+            .collect::<prost::alloc::vec::Vec<_>>();
         let empty_directories = procedure
             .empty_directories
             .into_iter()
@@ -186,6 +304,8 @@ impl From<registered::Procedure> for Procedure {
             texts,
             empty_directories,
             executions,
+            binaries,
+            directories,
         }
     }
 }
@@ -203,6 +323,16 @@ impl TryFrom<Procedure> for registered::Procedure {
             .into_iter()
             .map(|text| text.try_into())
             .collect::<Result<prost::alloc::vec::Vec<_>>>()?;
+        let binaries = procedure
+            .binaries
+            .into_iter()
+            .map(|binary| binary.try_into())
+            .collect::<Result<prost::alloc::vec::Vec<_>>>()?;
+        let directories = procedure
+            .directories
+            .into_iter()
+            .map(|directory| directory.try_into())
+            .collect::<Result<prost::alloc::vec::Vec<_>>>()?;
         let empty_directories = procedure
             .empty_directories
             .into_iter()
@@ -216,6 +346,8 @@ impl TryFrom<Procedure> for registered::Procedure {
         Ok(registered::Procedure {
             runtime_texts,
             texts,
+            binaries,
+            directories,
             empty_directories,
             executions,
         })
@@ -234,9 +366,12 @@ impl From<judge::JudgeRequest> for JudgeRequest {
                 content: v,
             })
             .collect::<prost::alloc::vec::Vec<_>>();
+        let judge_id: uuid::Uuid = judge_request.judge_id.into();
+        let judge_id: Uuid = judge_id.into();
         Self {
             procedure: Some(procedure),
             runtime_text_contents,
+            judge_id: Some(judge_id),
         }
     }
 }
@@ -254,13 +389,61 @@ impl TryFrom<JudgeRequest> for judge::JudgeRequest {
             .into_iter()
             .map(|content| (content.label, content.content))
             .collect::<HashMap<String, String>>();
+        let judge_id = judge_request
+            .judge_id
+            .ok_or(anyhow::anyhow!("judge_id is missing"))?;
+        let judge_id: uuid::Uuid = judge_id.into();
+        let judge_id: identifiers::JudgeId = judge_id.into();
         Ok(judge::JudgeRequest {
+            judge_id,
             procedure,
             runtime_texts,
         })
     }
 }
 
+impl From<identifiers::JudgeId> for CancelRequest {
+    fn from(judge_id: identifiers::JudgeId) -> Self {
+        let judge_id: uuid::Uuid = judge_id.into();
+        let judge_id: Uuid = judge_id.into();
+        Self {
+            judge_id: Some(judge_id),
+        }
+    }
+}
+
+impl TryFrom<CancelRequest> for identifiers::JudgeId {
+    type Error = Error;
+    fn try_from(cancel_request: CancelRequest) -> Result<Self> {
+        let judge_id = cancel_request
+            .judge_id
+            .ok_or(anyhow::anyhow!("judge_id is missing"))?;
+        let judge_id: uuid::Uuid = judge_id.into();
+        Ok(judge_id.into())
+    }
+}
+
+impl From<identifiers::JudgeId> for GetTraceRequest {
+    fn from(judge_id: identifiers::JudgeId) -> Self {
+        let judge_id: uuid::Uuid = judge_id.into();
+        let judge_id: Uuid = judge_id.into();
+        Self {
+            judge_id: Some(judge_id),
+        }
+    }
+}
+
+impl TryFrom<GetTraceRequest> for identifiers::JudgeId {
+    type Error = Error;
+    fn try_from(get_trace_request: GetTraceRequest) -> Result<Self> {
+        let judge_id = get_trace_request
+            .judge_id
+            .ok_or(anyhow::anyhow!("judge_id is missing"))?;
+        let judge_id: uuid::Uuid = judge_id.into();
+        Ok(judge_id.into())
+    }
+}
+
 impl From<judge_output::JudgeStatus> for JudgeStatus {
     fn from(judge_status: judge_output::JudgeStatus) -> Self {
         match judge_status {
@@ -272,6 +455,10 @@ impl From<judge_output::JudgeStatus> for JudgeStatus {
             judge_output::JudgeStatus::MLE => Self::Mle,
             judge_output::JudgeStatus::OLE => Self::Ole,
             judge_output::JudgeStatus::WE => Self::We,
+            judge_output::JudgeStatus::IE => Self::Ie,
+            judge_output::JudgeStatus::SKIP => Self::Skip,
+            judge_output::JudgeStatus::PE => Self::Pe,
+            judge_output::JudgeStatus::PA => Self::Pa,
         }
     }
 }
@@ -287,6 +474,10 @@ impl From<JudgeStatus> for judge_output::JudgeStatus {
             JudgeStatus::Mle => Self::MLE,
             JudgeStatus::Ole => Self::OLE,
             JudgeStatus::We => Self::WE,
+            JudgeStatus::Ie => Self::IE,
+            JudgeStatus::Skip => Self::SKIP,
+            JudgeStatus::Pe => Self::PE,
+            JudgeStatus::Pa => Self::PA,
         }
     }
 }
@@ -322,6 +513,11 @@ impl From<judge_output::DisplayableExecutionResult> for DisplayableExecutionResu
             score: displayable_execution_result.score,
             message: displayable_execution_result.message,
             continue_status,
+            compile_log: displayable_execution_result.compile_log,
+            stderr: displayable_execution_result.stderr,
+            signal: displayable_execution_result.signal,
+            cpu_time: displayable_execution_result.cpu_time,
+            wall_time: displayable_execution_result.wall_time,
         }
     }
 }
@@ -341,6 +537,11 @@ impl TryFrom<DisplayableExecutionResult> for judge_output::DisplayableExecutionR
             score: displayable_execution_result.score,
             message: displayable_execution_result.message,
             continue_status,
+            compile_log: displayable_execution_result.compile_log,
+            stderr: displayable_execution_result.stderr,
+            signal: displayable_execution_result.signal,
+            cpu_time: displayable_execution_result.cpu_time,
+            wall_time: displayable_execution_result.wall_time,
         })
     }
 }
@@ -362,6 +563,51 @@ impl TryFrom<HiddenExecutionResult> for judge_output::HiddenExecutionResult {
     }
 }
 
+impl From<judge_output::BatchedExecutionResult> for BatchedExecutionResult {
+    fn from(batched_execution_result: judge_output::BatchedExecutionResult) -> Self {
+        let results = batched_execution_result
+            .results
+            .into_iter()
+            .map(|sub| SubExecutionResult {
+                sub_name: sub.sub_name,
+                result: Some(sub.result.into()),
+            })
+            .collect();
+        let continue_status: ContinueStatus = batched_execution_result.continue_status.into();
+        let continue_status: i32 = continue_status.into();
+        Self {
+            results,
+            continue_status,
+        }
+    }
+}
+
+impl TryFrom<BatchedExecutionResult> for judge_output::BatchedExecutionResult {
+    type Error = Error;
+    fn try_from(batched_execution_result: BatchedExecutionResult) -> Result<Self> {
+        let results = batched_execution_result
+            .results
+            .into_iter()
+            .map(|sub| {
+                let result = sub
+                    .result
+                    .ok_or(anyhow::anyhow!("result is missing"))?
+                    .try_into()?;
+                Ok(judge_output::SubExecutionResult {
+                    sub_name: sub.sub_name,
+                    result,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let continue_status = ContinueStatus::try_from(batched_execution_result.continue_status)?;
+        let continue_status: judge_output::ContinueStatus = continue_status.into();
+        Ok(judge_output::BatchedExecutionResult {
+            results,
+            continue_status,
+        })
+    }
+}
+
 impl From<judge_output::ExecutionJobResult> for ExecutionJobResult {
     fn from(execution_job_result: judge_output::ExecutionJobResult) -> Self {
         let result = match execution_job_result {
@@ -375,11 +621,29 @@ impl From<judge_output::ExecutionJobResult> for ExecutionJobResult {
                         let hidden: HiddenExecutionResult = hidden.into();
                         execution_job_result::Result::HiddenExecutionResult(hidden)
                     }
+                    judge_output::ExecutionResult::Batched(batched) => {
+                        let batched: BatchedExecutionResult = batched.into();
+                        execution_job_result::Result::BatchedExecutionResult(batched)
+                    }
                 }
             }
             judge_output::ExecutionJobResult::EarlyExit => {
                 execution_job_result::Result::EarlyReturn(Unit {})
             }
+            judge_output::ExecutionJobResult::Cancelled => {
+                execution_job_result::Result::Cancelled(Unit {})
+            }
+            judge_output::ExecutionJobResult::Failed { reason } => {
+                execution_job_result::Result::FailedExecutionResult(FailedExecutionResult {
+                    reason,
+                })
+            }
+            judge_output::ExecutionJobResult::Skipped => {
+                execution_job_result::Result::Skipped(Unit {})
+            }
+            judge_output::ExecutionJobResult::ConditionUnmet => {
+                execution_job_result::Result::ConditionUnmet(Unit {})
+            }
         };
         Self {
             result: Some(result),
@@ -407,29 +671,103 @@ impl TryFrom<ExecutionJobResult> for judge_output::ExecutionJobResult {
                     judge_output::ExecutionResult::Hidden(hidden),
                 ))
             }
+            execution_job_result::Result::BatchedExecutionResult(batched) => {
+                let batched: judge_output::BatchedExecutionResult = batched.try_into()?;
+                Ok(judge_output::ExecutionJobResult::ExecutionResult(
+                    judge_output::ExecutionResult::Batched(batched),
+                ))
+            }
             execution_job_result::Result::EarlyReturn(_) => {
                 Ok(judge_output::ExecutionJobResult::EarlyExit)
             }
+            execution_job_result::Result::Cancelled(_) => {
+                Ok(judge_output::ExecutionJobResult::Cancelled)
+            }
+            execution_job_result::Result::FailedExecutionResult(failed) => {
+                Ok(judge_output::ExecutionJobResult::Failed {
+                    reason: failed.reason,
+                })
+            }
+            execution_job_result::Result::Skipped(_) => {
+                Ok(judge_output::ExecutionJobResult::Skipped)
+            }
+            execution_job_result::Result::ConditionUnmet(_) => {
+                Ok(judge_output::ExecutionJobResult::ConditionUnmet)
+            }
+        }
+    }
+}
+
+impl From<judge_output::ExecutionMetadata> for ExecutionMetadata {
+    fn from(metadata: judge_output::ExecutionMetadata) -> Self {
+        Self {
+            ready_at_ms: metadata.ready_at_ms,
+            started_at_ms: metadata.started_at_ms,
+            finished_at_ms: metadata.finished_at_ms,
+            host: metadata.host,
+            retry_count: metadata.retry_count,
+            outcome_size_bytes: metadata.outcome_size_bytes,
+            cached: metadata.cached,
+        }
+    }
+}
+
+impl From<ExecutionMetadata> for judge_output::ExecutionMetadata {
+    fn from(metadata: ExecutionMetadata) -> Self {
+        Self {
+            ready_at_ms: metadata.ready_at_ms,
+            started_at_ms: metadata.started_at_ms,
+            finished_at_ms: metadata.finished_at_ms,
+            host: metadata.host,
+            retry_count: metadata.retry_count,
+            outcome_size_bytes: metadata.outcome_size_bytes,
+            cached: metadata.cached,
         }
     }
 }
 
+impl From<(identifiers::DepId, judge_output::ExecutionJobReport)> for ExecutionJobResultWithDepId {
+    fn from((dep_id, report): (identifiers::DepId, judge_output::ExecutionJobReport)) -> Self {
+        let dep_id: uuid::Uuid = dep_id.into();
+        let dep_id: Uuid = dep_id.into();
+        let execution_job_result: ExecutionJobResult = report.result.into();
+        Self {
+            execution_job_result: Some(execution_job_result),
+            dep_id: Some(dep_id),
+            metadata: report.metadata.map(|metadata| metadata.into()),
+        }
+    }
+}
+
+impl TryFrom<ExecutionJobResultWithDepId>
+    for (identifiers::DepId, judge_output::ExecutionJobReport)
+{
+    type Error = Error;
+    fn try_from(result_with_id: ExecutionJobResultWithDepId) -> Result<Self> {
+        let dep_id = result_with_id
+            .dep_id
+            .ok_or(anyhow::anyhow!("dep_id is missing"))?;
+        let dep_id: uuid::Uuid = dep_id.into();
+        let dep_id: identifiers::DepId = dep_id.into();
+        let result = result_with_id
+            .execution_job_result
+            .ok_or(anyhow::anyhow!("execution_job_result is missing"))?;
+        let result: judge_output::ExecutionJobResult = result.try_into()?;
+        let report = judge_output::ExecutionJobReport {
+            result,
+            metadata: result_with_id.metadata.map(|metadata| metadata.into()),
+        };
+        Ok((dep_id, report))
+    }
+}
+
 impl From<judge::JudgeResponse> for JudgeResponse {
     fn from(judge_response: judge::JudgeResponse) -> Self {
         match judge_response {
             Ok(results) => {
                 let results = results
                     .into_iter()
-                    .map(|(k, v)| {
-                        let uuid: uuid::Uuid = k.into();
-                        let uuid: Uuid = uuid.into();
-                        let result: ExecutionJobResult = v.into();
-                        let result_with_id = ExecutionJobResultWithDepId {
-                            execution_job_result: Some(result),
-                            dep_id: Some(uuid),
-                        };
-                        result_with_id
-                    })
+                    .map(|(dep_id, report)| (dep_id, report).into())
                     .collect::<prost::alloc::vec::Vec<_>>();
                 let results = ExecutionJobResults {
                     execution_job_results: results,
@@ -455,18 +793,117 @@ impl From<JudgeResponse> for judge::JudgeResponse {
             judge_response::Result::ExecutionJobResults(results) => {
                 let results = results.execution_job_results
                     .into_iter()
-                    .map(|result| {
-                        let dep_id = result.dep_id.ok_or(anyhow::anyhow!("dep_id is missing"))?;
-                        let dep_id: uuid::Uuid = dep_id.into();
-                        let dep_id: identifiers::DepId = dep_id.into();
-                        let result = result.execution_job_result.ok_or(anyhow::anyhow!("execution_job_result is missing"))?;
-                        let result: judge_output::ExecutionJobResult = result.try_into()?;
-                        Ok((dep_id, result))
-                    })
-                    .collect::<Result<HashMap<identifiers::DepId, judge_output::ExecutionJobResult>>>()?;
+                    .map(|result| result.try_into())
+                    .collect::<Result<HashMap<identifiers::DepId, judge_output::ExecutionJobReport>>>()?;
                 Ok(results)
             }
             judge_response::Result::ErrorMessage(err) => Err(anyhow::anyhow!(err)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    fn round_trip(result: judge_output::ExecutionJobResult) -> judge_output::ExecutionJobResult {
+        let encoded = ExecutionJobResult::from(result).encode_to_vec();
+        ExecutionJobResult::decode(encoded.as_slice())
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    fn displayable(status: judge_output::JudgeStatus) -> judge_output::DisplayableExecutionResult {
+        judge_output::DisplayableExecutionResult {
+            status,
+            time: 12.0,
+            memory: 3456.0,
+            score: 100,
+            message: Some("ok".to_string()),
+            continue_status: judge_output::ContinueStatus::Continue,
+            compile_log: None,
+            stderr: Some("warning".to_string()),
+            signal: Some(9),
+            cpu_time: Some(11.0),
+            wall_time: Some(15.0),
+        }
+    }
+
+    #[test]
+    fn execution_job_results_survive_encoding() {
+        use judge_output::{ExecutionJobResult as R, ExecutionResult};
+
+        let result = round_trip(R::ExecutionResult(ExecutionResult::Displayable(
+            displayable(judge_output::JudgeStatus::PA),
+        )));
+        let R::ExecutionResult(ExecutionResult::Displayable(result)) = result else {
+            panic!("unexpected {:?}", result);
+        };
+        assert_eq!(result.status, judge_output::JudgeStatus::PA);
+        assert_eq!(result.score, 100);
+        assert_eq!(result.stderr.as_deref(), Some("warning"));
+        assert_eq!(result.signal, Some(9));
+        assert_eq!(result.wall_time, Some(15.0));
+
+        let result = round_trip(R::ExecutionResult(ExecutionResult::Hidden(
+            judge_output::HiddenExecutionResult {
+                continue_status: judge_output::ContinueStatus::Stop,
+            },
+        )));
+        assert!(matches!(
+            result,
+            R::ExecutionResult(ExecutionResult::Hidden(
+                judge_output::HiddenExecutionResult {
+                    continue_status: judge_output::ContinueStatus::Stop
+                }
+            ))
+        ));
+
+        let result = round_trip(R::Failed {
+            reason: "container crashed".to_string(),
+        });
+        assert!(matches!(result, R::Failed { reason } if reason == "container crashed"));
+
+        assert!(matches!(round_trip(R::EarlyExit), R::EarlyExit));
+        assert!(matches!(round_trip(R::Cancelled), R::Cancelled));
+        assert!(matches!(round_trip(R::Skipped), R::Skipped));
+        assert!(matches!(round_trip(R::ConditionUnmet), R::ConditionUnmet));
+    }
+
+    #[test]
+    fn batched_results_survive_encoding() {
+        use judge_output::{ExecutionJobResult as R, ExecutionResult};
+
+        let sub_result = |sub_name: &str, status| judge_output::SubExecutionResult {
+            sub_name: sub_name.to_string(),
+            result: displayable(status),
+        };
+        let result = round_trip(R::ExecutionResult(ExecutionResult::Batched(
+            judge_output::BatchedExecutionResult {
+                results: vec![
+                    sub_result("1", judge_output::JudgeStatus::AC),
+                    sub_result("2", judge_output::JudgeStatus::WA),
+                ],
+                continue_status: judge_output::ContinueStatus::Stop,
+            },
+        )));
+        let R::ExecutionResult(ExecutionResult::Batched(result)) = result else {
+            panic!("unexpected {:?}", result);
+        };
+        let subs = result
+            .results
+            .iter()
+            .map(|sub| (sub.sub_name.as_str(), sub.result.status.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            subs,
+            vec![
+                ("1", judge_output::JudgeStatus::AC),
+                ("2", judge_output::JudgeStatus::WA)
+            ]
+        );
+        assert_eq!(result.continue_status, judge_output::ContinueStatus::Stop);
+    }
+}
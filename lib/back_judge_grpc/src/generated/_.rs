@@ -22,6 +22,16 @@ pub struct DisplayableExecutionResult {
     pub used_memory: f64,
     #[prost(enumeration = "ContinueStatus", tag = "6")]
     pub continue_status: i32,
+    #[prost(string, optional, tag = "7")]
+    pub compile_log: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "8")]
+    pub stderr: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int32, optional, tag = "9")]
+    pub signal: ::core::option::Option<i32>,
+    #[prost(double, optional, tag = "10")]
+    pub cpu_time: ::core::option::Option<f64>,
+    #[prost(double, optional, tag = "11")]
+    pub wall_time: ::core::option::Option<f64>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct HiddenExecutionResult {
@@ -29,8 +39,27 @@ pub struct HiddenExecutionResult {
     pub continue_status: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubExecutionResult {
+    #[prost(string, tag = "1")]
+    pub sub_name: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub result: ::core::option::Option<DisplayableExecutionResult>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchedExecutionResult {
+    #[prost(message, repeated, tag = "1")]
+    pub results: ::prost::alloc::vec::Vec<SubExecutionResult>,
+    #[prost(enumeration = "ContinueStatus", tag = "2")]
+    pub continue_status: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FailedExecutionResult {
+    #[prost(string, tag = "1")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ExecutionJobResult {
-    #[prost(oneof = "execution_job_result::Result", tags = "1, 2, 3")]
+    #[prost(oneof = "execution_job_result::Result", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
     pub result: ::core::option::Option<execution_job_result::Result>,
 }
 /// Nested message and enum types in `ExecutionJobResult`.
@@ -43,14 +72,45 @@ pub mod execution_job_result {
         HiddenExecutionResult(super::HiddenExecutionResult),
         #[prost(message, tag = "3")]
         EarlyReturn(super::Unit),
+        #[prost(message, tag = "4")]
+        Cancelled(super::Unit),
+        #[prost(message, tag = "5")]
+        FailedExecutionResult(super::FailedExecutionResult),
+        #[prost(message, tag = "6")]
+        Skipped(super::Unit),
+        #[prost(message, tag = "7")]
+        ConditionUnmet(super::Unit),
+        #[prost(message, tag = "8")]
+        BatchedExecutionResult(super::BatchedExecutionResult),
     }
 }
+/// times are milliseconds since the UNIX epoch
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecutionMetadata {
+    #[prost(uint64, tag = "1")]
+    pub ready_at_ms: u64,
+    #[prost(uint64, optional, tag = "2")]
+    pub started_at_ms: ::core::option::Option<u64>,
+    #[prost(uint64, tag = "3")]
+    pub finished_at_ms: u64,
+    #[prost(string, optional, tag = "4")]
+    pub host: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, tag = "5")]
+    pub retry_count: u32,
+    #[prost(uint64, optional, tag = "6")]
+    pub outcome_size_bytes: ::core::option::Option<u64>,
+    #[prost(bool, tag = "7")]
+    pub cached: bool,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ExecutionJobResultWithDepId {
     #[prost(message, optional, tag = "1")]
     pub execution_job_result: ::core::option::Option<ExecutionJobResult>,
     #[prost(message, optional, tag = "2")]
     pub dep_id: ::core::option::Option<Uuid>,
+    /// absent if the execution was never scheduled
+    #[prost(message, optional, tag = "3")]
+    pub metadata: ::core::option::Option<ExecutionMetadata>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ExecutionJobResults {
@@ -72,6 +132,12 @@ pub mod judge_response {
         ErrorMessage(::prost::alloc::string::String),
     }
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTraceResponse {
+    /// absent if no trace was recorded for the judge
+    #[prost(bytes = "vec", optional, tag = "1")]
+    pub bundle: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum JudgeStatus {
@@ -83,6 +149,10 @@ pub enum JudgeStatus {
     Re = 5,
     Ce = 6,
     We = 7,
+    Ie = 8,
+    Skip = 9,
+    Pe = 10,
+    Pa = 11,
 }
 impl JudgeStatus {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -99,6 +169,10 @@ impl JudgeStatus {
             Self::Re => "RE",
             Self::Ce => "CE",
             Self::We => "WE",
+            Self::Ie => "IE",
+            Self::Skip => "SKIP",
+            Self::Pe => "PE",
+            Self::Pa => "PA",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -112,6 +186,10 @@ impl JudgeStatus {
             "RE" => Some(Self::Re),
             "CE" => Some(Self::Ce),
             "WE" => Some(Self::We),
+            "IE" => Some(Self::Ie),
+            "SKIP" => Some(Self::Skip),
+            "PE" => Some(Self::Pe),
+            "PA" => Some(Self::Pa),
             _ => None,
         }
     }
@@ -157,16 +235,41 @@ pub struct Text {
     pub dep_id: ::core::option::Option<Uuid>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Binary {
+    #[prost(message, optional, tag = "1")]
+    pub resource_id: ::core::option::Option<Uuid>,
+    #[prost(message, optional, tag = "2")]
+    pub dep_id: ::core::option::Option<Uuid>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Directory {
+    #[prost(message, optional, tag = "1")]
+    pub resource_id: ::core::option::Option<Uuid>,
+    #[prost(message, optional, tag = "2")]
+    pub dep_id: ::core::option::Option<Uuid>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct EmptyDirectory {
     #[prost(message, optional, tag = "1")]
     pub dep_id: ::core::option::Option<Uuid>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Condition {
+    #[prost(enumeration = "JudgeStatus", repeated, tag = "1")]
+    pub statuses: ::prost::alloc::vec::Vec<i32>,
+    /// holds when the status is not in statuses
+    #[prost(bool, tag = "2")]
+    pub negated: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Dependency {
     #[prost(message, optional, tag = "1")]
     pub dep_id: ::core::option::Option<Uuid>,
     #[prost(string, tag = "2")]
     pub envvar_name: ::prost::alloc::string::String,
+    /// absent if the dependency is unconditional
+    #[prost(message, optional, tag = "3")]
+    pub condition: ::core::option::Option<Condition>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Execution {
@@ -176,6 +279,8 @@ pub struct Execution {
     pub dependencies: ::prost::alloc::vec::Vec<Dependency>,
     #[prost(uint64, tag = "3")]
     pub time_reserved_ms: u64,
+    #[prost(bool, tag = "4")]
+    pub no_cache: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Procedure {
@@ -187,6 +292,10 @@ pub struct Procedure {
     pub empty_directories: ::prost::alloc::vec::Vec<EmptyDirectory>,
     #[prost(message, repeated, tag = "4")]
     pub executions: ::prost::alloc::vec::Vec<Execution>,
+    #[prost(message, repeated, tag = "5")]
+    pub binaries: ::prost::alloc::vec::Vec<Binary>,
+    #[prost(message, repeated, tag = "6")]
+    pub directories: ::prost::alloc::vec::Vec<Directory>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RuntimeTextContent {
@@ -201,6 +310,18 @@ pub struct JudgeRequest {
     pub procedure: ::core::option::Option<Procedure>,
     #[prost(message, repeated, tag = "2")]
     pub runtime_text_contents: ::prost::alloc::vec::Vec<RuntimeTextContent>,
+    #[prost(message, optional, tag = "3")]
+    pub judge_id: ::core::option::Option<Uuid>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CancelRequest {
+    #[prost(message, optional, tag = "1")]
+    pub judge_id: ::core::option::Option<Uuid>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetTraceRequest {
+    #[prost(message, optional, tag = "1")]
+    pub judge_id: ::core::option::Option<Uuid>,
 }
 /// Generated client implementations.
 pub mod judge_service_client {
@@ -311,6 +432,71 @@ pub mod judge_service_client {
             req.extensions_mut().insert(GrpcMethod::new("JudgeService", "Judge"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn judge_stream(
+            &mut self,
+            request: impl tonic::IntoRequest<super::JudgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ExecutionJobResultWithDepId>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/JudgeService/JudgeStream",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("JudgeService", "JudgeStream"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn cancel(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelRequest>,
+        ) -> std::result::Result<tonic::Response<super::Unit>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/JudgeService/Cancel");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("JudgeService", "Cancel"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_trace(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTraceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTraceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/JudgeService/GetTrace",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("JudgeService", "GetTrace"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -330,6 +516,33 @@ pub mod judge_service_server {
             &self,
             request: tonic::Request<super::JudgeRequest>,
         ) -> std::result::Result<tonic::Response<super::JudgeResponse>, tonic::Status>;
+        /// Server streaming response type for the JudgeStream method.
+        type JudgeStreamStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::ExecutionJobResultWithDepId,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn judge_stream(
+            &self,
+            request: tonic::Request<super::JudgeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::JudgeStreamStream>,
+            tonic::Status,
+        >;
+        async fn cancel(
+            &self,
+            request: tonic::Request<super::CancelRequest>,
+        ) -> std::result::Result<tonic::Response<super::Unit>, tonic::Status>;
+        async fn get_trace(
+            &self,
+            request: tonic::Request<super::GetTraceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTraceResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct JudgeServiceServer<T> {
@@ -451,6 +664,140 @@ pub mod judge_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/JudgeService/JudgeStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct JudgeStreamSvc<T: JudgeService>(pub Arc<T>);
+                    impl<
+                        T: JudgeService,
+                    > tonic::server::ServerStreamingService<super::JudgeRequest>
+                    for JudgeStreamSvc<T> {
+                        type Response = super::ExecutionJobResultWithDepId;
+                        type ResponseStream = T::JudgeStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::JudgeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as JudgeService>::judge_stream(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = JudgeStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/JudgeService/Cancel" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelSvc<T: JudgeService>(pub Arc<T>);
+                    impl<
+                        T: JudgeService,
+                    > tonic::server::UnaryService<super::CancelRequest> for CancelSvc<T> {
+                        type Response = super::Unit;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as JudgeService>::cancel(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CancelSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/JudgeService/GetTrace" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTraceSvc<T: JudgeService>(pub Arc<T>);
+                    impl<
+                        T: JudgeService,
+                    > tonic::server::UnaryService<super::GetTraceRequest> for GetTraceSvc<T> {
+                        type Response = super::GetTraceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTraceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as JudgeService>::get_trace(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTraceSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -1,7 +1,8 @@
 use crate::*;
 use anyhow::Result;
 use axum::async_trait;
-use judge_core::model::judge;
+use futures::StreamExt;
+use judge_core::model::{identifiers, judge};
 use tracing;
 
 #[derive(Debug, Clone)]
@@ -40,4 +41,62 @@ impl<Inner: judge::JudgeService> generated::judge_service_server::JudgeService
         let response = response.into();
         Ok(tonic::Response::new(response))
     }
+
+    type JudgeStreamStream = futures::stream::BoxStream<
+        'static,
+        Result<generated::ExecutionJobResultWithDepId, tonic::Status>,
+    >;
+
+    async fn judge_stream(
+        &self,
+        request: tonic::Request<generated::JudgeRequest>,
+    ) -> Result<tonic::Response<Self::JudgeStreamStream>, tonic::Status> {
+        let request = request.into_inner();
+        tracing::info!("Received stream request: {:?}", request);
+        let request: judge::JudgeRequest = request
+            .try_into()
+            .map_err(|e| tonic::Status::invalid_argument(format!("Invalid request: {}", e)))?;
+        let stream = self
+            .inner_api
+            .judge_stream(request)
+            .map(|item| {
+                tracing::info!("Sending stream item: {:?}", item);
+                item.map(|result| result.into())
+                    .map_err(|e| tonic::Status::internal(format!("Internal error: {}", e)))
+            })
+            .boxed();
+        Ok(tonic::Response::new(stream))
+    }
+
+    async fn cancel(
+        &self,
+        request: tonic::Request<generated::CancelRequest>,
+    ) -> Result<tonic::Response<generated::Unit>, tonic::Status> {
+        let request = request.into_inner();
+        tracing::info!("Received cancel request: {:?}", request);
+        let judge_id: identifiers::JudgeId = request
+            .try_into()
+            .map_err(|e| tonic::Status::invalid_argument(format!("Invalid request: {}", e)))?;
+        self.inner_api
+            .cancel(judge_id)
+            .await
+            .map_err(|e| tonic::Status::internal(format!("Internal error: {}", e)))?;
+        Ok(tonic::Response::new(generated::Unit {}))
+    }
+    async fn get_trace(
+        &self,
+        request: tonic::Request<generated::GetTraceRequest>,
+    ) -> Result<tonic::Response<generated::GetTraceResponse>, tonic::Status> {
+        let request = request.into_inner();
+        tracing::info!("Received trace request: {:?}", request);
+        let judge_id: identifiers::JudgeId = request
+            .try_into()
+            .map_err(|e| tonic::Status::invalid_argument(format!("Invalid request: {}", e)))?;
+        let bundle = self
+            .inner_api
+            .trace(judge_id)
+            .await
+            .map_err(|e| tonic::Status::internal(format!("Internal error: {}", e)))?;
+        Ok(tonic::Response::new(generated::GetTraceResponse { bundle }))
+    }
 }
@@ -11,3 +11,5 @@ pub mod user;
 
 #[cfg(test)]
 mod rules_test;
+#[cfg(test)]
+mod submission_test;
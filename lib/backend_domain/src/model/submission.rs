@@ -1,6 +1,73 @@
 use async_session::chrono;
+use judge_core::model::judge_output;
+use std::fmt;
 use uuid::Uuid;
 
+/// Verdict of a submission, a testcase or a group, stored as its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JudgeStatus {
+    /// Waiting for judge
+    WJ,
+    AC,
+    WA,
+    TLE,
+    MLE,
+    OLE,
+    RE,
+    CE,
+    WE,
+    IE,
+    SKIP,
+    PE,
+    PA,
+}
+
+impl JudgeStatus {
+    pub fn new(status: &str) -> anyhow::Result<Self> {
+        match status {
+            "WJ" => Ok(JudgeStatus::WJ),
+            "AC" => Ok(JudgeStatus::AC),
+            "WA" => Ok(JudgeStatus::WA),
+            "TLE" => Ok(JudgeStatus::TLE),
+            "MLE" => Ok(JudgeStatus::MLE),
+            "OLE" => Ok(JudgeStatus::OLE),
+            "RE" => Ok(JudgeStatus::RE),
+            "CE" => Ok(JudgeStatus::CE),
+            "WE" => Ok(JudgeStatus::WE),
+            "IE" => Ok(JudgeStatus::IE),
+            "SKIP" => Ok(JudgeStatus::SKIP),
+            "PE" => Ok(JudgeStatus::PE),
+            "PA" => Ok(JudgeStatus::PA),
+            _ => anyhow::bail!("invalid judge status"),
+        }
+    }
+}
+
+impl fmt::Display for JudgeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<judge_output::JudgeStatus> for JudgeStatus {
+    fn from(status: judge_output::JudgeStatus) -> Self {
+        match status {
+            judge_output::JudgeStatus::AC => JudgeStatus::AC,
+            judge_output::JudgeStatus::WA => JudgeStatus::WA,
+            judge_output::JudgeStatus::TLE => JudgeStatus::TLE,
+            judge_output::JudgeStatus::MLE => JudgeStatus::MLE,
+            judge_output::JudgeStatus::OLE => JudgeStatus::OLE,
+            judge_output::JudgeStatus::RE => JudgeStatus::RE,
+            judge_output::JudgeStatus::CE => JudgeStatus::CE,
+            judge_output::JudgeStatus::WE => JudgeStatus::WE,
+            judge_output::JudgeStatus::IE => JudgeStatus::IE,
+            judge_output::JudgeStatus::SKIP => JudgeStatus::SKIP,
+            judge_output::JudgeStatus::PE => JudgeStatus::PE,
+            judge_output::JudgeStatus::PA => JudgeStatus::PA,
+        }
+    }
+}
+
 pub struct Submission {
     pub id: Uuid,
     pub user_id: i64,
@@ -13,16 +80,42 @@ pub struct Submission {
     pub max_time_ms: i32,
     pub max_memory_kib: i32,
     pub source: String,
-    pub overall_judge_status: String,
+    pub overall_judge_status: JudgeStatus,
+    pub compile_log: Option<String>,
 }
 
 pub struct JudgeResult {
     pub testcase_id: Uuid,
     pub testcase_name: String,
-    pub judge_status: String,
+    pub judge_status: JudgeStatus,
     pub score: i64,
     pub time_ms: i32,
     pub memory_kib: i32,
+    pub message: Option<String>,
+    pub stderr: Option<String>,
+    pub signal: Option<i32>,
+    pub cpu_time_ms: Option<i32>,
+    pub wall_time_ms: Option<i32>,
+}
+
+pub struct GroupJudgeResult {
+    pub group_name: String,
+    pub judge_status: JudgeStatus,
+    pub score: i64,
+}
+
+/// How one execution of a judge ran, times are milliseconds since the UNIX epoch
+pub struct ExecutionTimelineEntry {
+    pub dep_id: Uuid,
+    pub name: String,
+    pub judge_status: String,
+    pub ready_at_ms: i64,
+    pub started_at_ms: Option<i64>,
+    pub finished_at_ms: i64,
+    pub host: Option<String>,
+    pub retry_count: i32,
+    pub outcome_size_bytes: Option<i64>,
+    pub cached: bool,
 }
 
 pub struct CreateSubmission {
@@ -30,27 +123,55 @@ pub struct CreateSubmission {
     pub user_id: i64,
     pub language_id: String,
     pub source: String,
-    pub judge_status: String,
+    pub judge_status: JudgeStatus,
     pub total_score: i64,
     pub max_time_ms: i32,
     pub max_memory_kib: i32,
 }
 
 pub struct UpdateSubmission {
-    pub judge_status: String,
+    pub judge_status: JudgeStatus,
     pub total_score: i64,
     pub max_time_ms: i32,
     pub max_memory_kib: i32,
+    pub compile_log: Option<String>,
 }
 
 pub struct CreateJudgeResult {
     pub submission_id: Uuid,
     pub testcase_id: Uuid,
     pub testcase_name: String,
-    pub judge_status: String,
+    pub judge_status: JudgeStatus,
     pub score: i64,
     pub time_ms: i32,
     pub memory_kib: i32,
+    pub message: Option<String>,
+    pub stderr: Option<String>,
+    pub signal: Option<i32>,
+    pub cpu_time_ms: Option<i32>,
+    pub wall_time_ms: Option<i32>,
+    pub display_order: Option<i32>,
+}
+
+pub struct CreateGroupJudgeResult {
+    pub submission_id: Uuid,
+    pub group_name: String,
+    pub judge_status: JudgeStatus,
+    pub score: i64,
+}
+
+pub struct CreateExecutionTimelineEntry {
+    pub submission_id: Uuid,
+    pub dep_id: Uuid,
+    pub name: String,
+    pub judge_status: String,
+    pub ready_at_ms: i64,
+    pub started_at_ms: Option<i64>,
+    pub finished_at_ms: i64,
+    pub host: Option<String>,
+    pub retry_count: i32,
+    pub outcome_size_bytes: Option<i64>,
+    pub cached: bool,
 }
 
 #[derive(Clone)]
@@ -72,7 +193,7 @@ pub struct SubmissionGetQuery {
     pub user_id: Option<i64>,
     pub limit: i64,
     pub offset: i64,
-    pub judge_status: Option<String>,
+    pub judge_status: Option<JudgeStatus>,
     pub language_id: Option<String>,
     pub user_name: Option<String>,
     pub user_query: Option<i64>,
@@ -0,0 +1,49 @@
+use crate::model::submission::JudgeStatus;
+use judge_core::model::judge_output;
+
+#[test]
+fn test_judge_status_round_trip() {
+    let statuses = [
+        JudgeStatus::WJ,
+        JudgeStatus::AC,
+        JudgeStatus::WA,
+        JudgeStatus::TLE,
+        JudgeStatus::MLE,
+        JudgeStatus::OLE,
+        JudgeStatus::RE,
+        JudgeStatus::CE,
+        JudgeStatus::WE,
+        JudgeStatus::IE,
+        JudgeStatus::SKIP,
+        JudgeStatus::PE,
+        JudgeStatus::PA,
+    ];
+    for status in statuses {
+        assert_eq!(JudgeStatus::new(&status.to_string()).unwrap(), status);
+    }
+    assert_eq!(JudgeStatus::PA.to_string(), "PA");
+    assert!(JudgeStatus::new("ac").is_err());
+    assert!(JudgeStatus::new("").is_err());
+}
+
+#[test]
+fn test_judge_status_from_judge_output() {
+    let statuses = [
+        judge_output::JudgeStatus::AC,
+        judge_output::JudgeStatus::WA,
+        judge_output::JudgeStatus::TLE,
+        judge_output::JudgeStatus::MLE,
+        judge_output::JudgeStatus::OLE,
+        judge_output::JudgeStatus::RE,
+        judge_output::JudgeStatus::CE,
+        judge_output::JudgeStatus::WE,
+        judge_output::JudgeStatus::IE,
+        judge_output::JudgeStatus::SKIP,
+        judge_output::JudgeStatus::PE,
+        judge_output::JudgeStatus::PA,
+    ];
+    for status in statuses {
+        let name = format!("{:?}", status);
+        assert_eq!(JudgeStatus::from(status).to_string(), name);
+    }
+}
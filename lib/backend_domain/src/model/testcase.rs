@@ -7,6 +7,7 @@ pub struct TestcaseSummary {
     pub problem_id: i64,
     pub input_id: Uuid,
     pub output_id: Uuid,
+    pub group_name: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -17,4 +18,12 @@ pub struct CreateTestcase {
     pub problem_id: i64,
     pub input_id: Uuid,
     pub output_id: Uuid,
+    pub group_name: Option<String>,
+}
+
+pub struct TestcaseGroup {
+    pub problem_id: i64,
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
 }
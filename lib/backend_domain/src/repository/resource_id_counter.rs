@@ -1,10 +1,26 @@
 use axum::async_trait;
 use uuid::Uuid;
 
+/// State of a resource id seen by a registration that wants to reuse its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceIdState {
+    /// Counted and kept from garbage collection for a while
+    Kept,
+    /// Claimed by garbage collection, its content may be removed at any moment
+    BeingDeleted,
+    /// Not counted yet
+    Unknown,
+}
+
 #[cfg_attr(feature = "mockall", mockall::automock)]
 #[async_trait]
 pub trait ResourceIdCounterRepository {
-    async fn get_deletable_resource_ids(&self, limit: usize) -> anyhow::Result<Vec<Uuid>>;
+    /// Marks up to `limit` unreferenced ids as being deleted and returns them
+    async fn claim_deletable_resource_ids(&self, limit: usize) -> anyhow::Result<Vec<Uuid>>;
+    /// Drops claimed ids whose content is removed, releasing the ones referenced again meanwhile
     async fn delete_resource_ids(&self, ids: Vec<Uuid>) -> anyhow::Result<()>;
+    /// Releases claimed ids whose content could not be removed
     async fn update_timestamp_ids(&self, ids: Vec<Uuid>) -> anyhow::Result<()>;
+    /// Postpones the garbage collection of `id` unless it is already claimed
+    async fn keep_resource_id(&self, id: Uuid) -> anyhow::Result<ResourceIdState>;
 }
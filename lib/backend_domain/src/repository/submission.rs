@@ -2,7 +2,8 @@ use axum::async_trait;
 use uuid::Uuid;
 
 use crate::model::submission::{
-    CreateJudgeResult, CreateSubmission, JudgeResult, Submission, SubmissionGetQuery,
+    CreateExecutionTimelineEntry, CreateGroupJudgeResult, CreateJudgeResult, CreateSubmission,
+    ExecutionTimelineEntry, GroupJudgeResult, JudgeResult, Submission, SubmissionGetQuery,
     UpdateSubmission,
 };
 
@@ -11,6 +12,10 @@ use crate::model::submission::{
 pub trait SubmissionRepository {
     async fn get_submission(&self, id: Uuid) -> anyhow::Result<Option<Submission>>;
     async fn get_submission_results(&self, id: Uuid) -> anyhow::Result<Vec<JudgeResult>>;
+    async fn get_submission_group_results(&self, id: Uuid)
+    -> anyhow::Result<Vec<GroupJudgeResult>>;
+    async fn get_execution_timeline(&self, id: Uuid)
+    -> anyhow::Result<Vec<ExecutionTimelineEntry>>;
     async fn get_submissions_by_query(
         &self,
         query: SubmissionGetQuery,
@@ -26,6 +31,14 @@ pub trait SubmissionRepository {
         submission: UpdateSubmission,
     ) -> anyhow::Result<()>;
     async fn create_judge_results(&self, results: Vec<CreateJudgeResult>) -> anyhow::Result<()>;
+    async fn create_group_judge_results(
+        &self,
+        results: Vec<CreateGroupJudgeResult>,
+    ) -> anyhow::Result<()>;
+    async fn create_execution_timeline(
+        &self,
+        entries: Vec<CreateExecutionTimelineEntry>,
+    ) -> anyhow::Result<()>;
     async fn delete_judge_results_by_submission_id(
         &self,
         submission_id: Uuid,
@@ -1,7 +1,7 @@
 use axum::async_trait;
 use uuid::Uuid;
 
-use crate::model::testcase::{CreateTestcase, TestcaseSummary};
+use crate::model::testcase::{CreateTestcase, TestcaseGroup, TestcaseSummary};
 
 #[cfg_attr(feature = "mockall", mockall::automock)]
 #[async_trait]
@@ -9,5 +9,13 @@ pub trait TestcaseRepository {
     async fn get_testcases(&self, problem_id: i64) -> anyhow::Result<Vec<TestcaseSummary>>;
     async fn get_testcase(&self, id: Uuid) -> anyhow::Result<Option<TestcaseSummary>>;
     async fn create_testcases(&self, testcases: Vec<CreateTestcase>) -> anyhow::Result<()>;
+    async fn update_testcase(&self, testcase: CreateTestcase) -> anyhow::Result<()>;
+    async fn delete_testcase(&self, id: Uuid) -> anyhow::Result<()>;
     async fn delete_testcases(&self, problem_id: i64) -> anyhow::Result<()>;
+    async fn get_testcase_groups(&self, problem_id: i64) -> anyhow::Result<Vec<TestcaseGroup>>;
+    async fn replace_testcase_groups(
+        &self,
+        problem_id: i64,
+        groups: Vec<TestcaseGroup>,
+    ) -> anyhow::Result<()>;
 }
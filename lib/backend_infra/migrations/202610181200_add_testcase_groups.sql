@@ -0,0 +1,18 @@
+ALTER TABLE `testcases`
+    ADD COLUMN `group_name` VARCHAR(255) NULL;
+
+CREATE TABLE IF NOT EXISTS `testcase_groups` (
+    `problem_id` INT NOT NULL,
+    `name` VARCHAR(255) NOT NULL,
+    `point` BIGINT NOT NULL,
+    `policy` VARCHAR(32) NOT NULL,
+    PRIMARY KEY (`problem_id`, `name`)
+);
+
+CREATE TABLE IF NOT EXISTS `submission_groups` (
+    `submission_id` BINARY(16) NOT NULL,
+    `group_name` VARCHAR(255) NOT NULL,
+    `judge_status` VARCHAR(4) NOT NULL,
+    `score` BIGINT NOT NULL,
+    PRIMARY KEY (`submission_id`, `group_name`)
+);
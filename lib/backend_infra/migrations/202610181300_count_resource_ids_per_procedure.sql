@@ -0,0 +1,139 @@
+-- ResourceId is now a content hash, so one procedure may refer to the same id several times
+-- and binaries / directories hold resources too. Count each id at most once per procedure.
+
+UPDATE resource_id_counter SET ref_count = 0;
+
+INSERT INTO resource_id_counter (resource_id, ref_count)
+SELECT
+    resource_id,
+    new_ref_count
+FROM (
+    SELECT
+        ids.resource_id AS resource_id,
+        COUNT(*)        AS new_ref_count
+    FROM (
+        SELECT p.problem_id, jt.resource_id
+        FROM procedures AS p
+        CROSS JOIN JSON_TABLE(
+            p.`procedure`,
+            '$.texts[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS jt
+        UNION
+        SELECT p.problem_id, jt.resource_id
+        FROM procedures AS p
+        CROSS JOIN JSON_TABLE(
+            p.`procedure`,
+            '$.binaries[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS jt
+        UNION
+        SELECT p.problem_id, jt.resource_id
+        FROM procedures AS p
+        CROSS JOIN JSON_TABLE(
+            p.`procedure`,
+            '$.directories[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS jt
+    ) AS ids
+    WHERE ids.resource_id IS NOT NULL
+    GROUP BY ids.resource_id
+) AS src
+ON DUPLICATE KEY UPDATE
+    ref_count = new_ref_count;
+
+DROP TRIGGER IF EXISTS trigger_procedures_insert;
+DROP TRIGGER IF EXISTS trigger_procedures_delete;
+DROP TRIGGER IF EXISTS trigger_procedures_update;
+
+CREATE TRIGGER trigger_procedures_insert
+AFTER INSERT ON procedures
+FOR EACH ROW
+BEGIN
+    INSERT INTO resource_id_counter (resource_id, ref_count)
+    SELECT resource_id, 1
+    FROM (
+        SELECT resource_id FROM JSON_TABLE(
+            NEW.`procedure`,
+            '$.texts[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS t
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            NEW.`procedure`,
+            '$.binaries[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS b
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            NEW.`procedure`,
+            '$.directories[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS d
+    ) AS ids
+    WHERE resource_id IS NOT NULL
+    ON DUPLICATE KEY UPDATE ref_count = ref_count + 1;
+END;
+
+CREATE TRIGGER trigger_procedures_delete
+AFTER DELETE ON procedures
+FOR EACH ROW
+BEGIN
+    UPDATE resource_id_counter rt
+    JOIN (
+        SELECT resource_id FROM JSON_TABLE(
+            OLD.`procedure`,
+            '$.texts[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS t
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            OLD.`procedure`,
+            '$.binaries[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS b
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            OLD.`procedure`,
+            '$.directories[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS d
+    ) AS ids ON rt.resource_id = ids.resource_id
+    SET rt.ref_count = rt.ref_count - 1;
+END;
+
+CREATE TRIGGER trigger_procedures_update
+AFTER UPDATE ON procedures
+FOR EACH ROW
+BEGIN
+    UPDATE resource_id_counter rt
+    JOIN (
+        SELECT resource_id FROM JSON_TABLE(
+            OLD.`procedure`,
+            '$.texts[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS t
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            OLD.`procedure`,
+            '$.binaries[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS b
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            OLD.`procedure`,
+            '$.directories[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS d
+    ) AS ids_old ON rt.resource_id = ids_old.resource_id
+    SET rt.ref_count = rt.ref_count - 1;
+
+    INSERT INTO resource_id_counter (resource_id, ref_count)
+    SELECT resource_id, 1
+    FROM (
+        SELECT resource_id FROM JSON_TABLE(
+            NEW.`procedure`,
+            '$.texts[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS t
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            NEW.`procedure`,
+            '$.binaries[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS b
+        UNION
+        SELECT resource_id FROM JSON_TABLE(
+            NEW.`procedure`,
+            '$.directories[*]' COLUMNS (resource_id VARCHAR(36) COLLATE utf8mb4_uca1400_ai_ci PATH '$.resource_id')
+        ) AS d
+    ) AS ids_new
+    WHERE resource_id IS NOT NULL
+    ON DUPLICATE KEY UPDATE ref_count = ref_count + 1;
+END;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS `submission_executions` (
+    `submission_id` BINARY(16) NOT NULL,
+    `dep_id` BINARY(16) NOT NULL,
+    `name` VARCHAR(255) NOT NULL,
+    `judge_status` VARCHAR(16) NOT NULL,
+    `ready_at_ms` BIGINT NOT NULL,
+    `started_at_ms` BIGINT NULL,
+    `finished_at_ms` BIGINT NOT NULL,
+    `host` VARCHAR(255) NULL,
+    `retry_count` INT NOT NULL,
+    `outcome_size_bytes` BIGINT NULL,
+    `cached` BOOLEAN NOT NULL,
+    PRIMARY KEY (`submission_id`, `dep_id`)
+);
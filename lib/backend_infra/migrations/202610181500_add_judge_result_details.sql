@@ -0,0 +1,9 @@
+ALTER TABLE `submissions`
+    ADD COLUMN `compile_log` TEXT NULL;
+
+ALTER TABLE `submission_testcases`
+    ADD COLUMN `message` TEXT NULL,
+    ADD COLUMN `stderr` TEXT NULL,
+    ADD COLUMN `exit_signal` INT NULL,
+    ADD COLUMN `cpu_time_ms` INT NULL,
+    ADD COLUMN `wall_time_ms` INT NULL;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS `dep_metadata` (
+    `dep_id` BINARY(16) NOT NULL PRIMARY KEY,
+    `problem_id` INT NOT NULL,
+    `metadata` JSON NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE `submission_testcases`
+    ADD COLUMN `display_order` INT NULL;
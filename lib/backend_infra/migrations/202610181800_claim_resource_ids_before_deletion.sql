@@ -0,0 +1,4 @@
+-- Garbage collection claims a row before removing its content from the registry,
+-- so that a registration never reuses content which is about to disappear.
+ALTER TABLE `resource_id_counter`
+    ADD COLUMN `deleting_since` TIMESTAMP NULL;
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use domain::repository::resource_id_counter::{ResourceIdCounterRepository, ResourceIdState};
+use judge_core::model::{
+    identifiers::ResourceId,
+    problem_registry::{ProblemRegistryServer, RegistrationError, RemovalError},
+};
+
+const KEEP_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+const KEEP_RETRIES: usize = 30;
+
+/// Registry server that reuses uploaded content only while `resource_id_counter` keeps it from garbage collection
+#[derive(Clone)]
+pub struct CountedRegistryServer<S, C> {
+    inner: S,
+    counter: C,
+}
+
+impl<S, C> CountedRegistryServer<S, C> {
+    pub fn new(inner: S, counter: C) -> Self {
+        Self { inner, counter }
+    }
+}
+
+#[axum::async_trait]
+impl<S, C> ProblemRegistryServer for CountedRegistryServer<S, C>
+where
+    S: ProblemRegistryServer,
+    C: ResourceIdCounterRepository + Clone + Send + Sync,
+{
+    async fn register(
+        &self,
+        resource_id: ResourceId,
+        content: Vec<u8>,
+    ) -> Result<(), RegistrationError> {
+        self.inner.register(resource_id, content).await
+    }
+
+    async fn exists(&self, resource_id: ResourceId) -> Result<bool, RegistrationError> {
+        for _ in 0..KEEP_RETRIES {
+            let state = self
+                .counter
+                .keep_resource_id(resource_id.into())
+                .await
+                .map_err(|e| RegistrationError::InternalError(e.to_string()))?;
+            match state {
+                ResourceIdState::Kept | ResourceIdState::Unknown => {
+                    return self.inner.exists(resource_id).await;
+                }
+                // GC が内容を消し終えて行が無くなってから上げ直す
+                ResourceIdState::BeingDeleted => tokio::time::sleep(KEEP_RETRY_INTERVAL).await,
+            }
+        }
+        Err(RegistrationError::InternalError(format!(
+            "resource {} is being removed, try again later",
+            resource_id
+        )))
+    }
+
+    async fn remove(&self, resource_id: ResourceId) -> Result<(), RemovalError> {
+        self.inner.remove(resource_id).await
+    }
+}
@@ -1,7 +1,14 @@
 use super::uuid::UuidRow;
+use judge_core::model::procedure::job_metadata::JobMetadata;
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct DepNameRow {
     pub dep_id: UuidRow,
     pub name: String,
 }
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DepMetadataRow {
+    pub dep_id: UuidRow,
+    pub metadata: sqlx::types::Json<JobMetadata>,
+}
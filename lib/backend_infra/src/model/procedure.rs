@@ -1,18 +1,53 @@
+use judge_core::logic::schema_migration::{Migration, MigrationError, migrate};
+use judge_core::model::procedure::condition::Condition;
 use judge_core::model::procedure::registered::{
-    Dependency, EmptyDirectory, Execution, Procedure, RuntimeText, Text,
+    Binary, Dependency, Directory, EmptyDirectory, Execution, Procedure, RuntimeText, Text,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Version of `ProcedureJson` written to the `procedures` table
+pub const PROCEDURE_JSON_VERSION: u32 = 3;
+
+/// Index i upgrades a stored procedure from version i to i + 1
+const PROCEDURE_JSON_MIGRATIONS: [Migration; PROCEDURE_JSON_VERSION as usize] = [
+    // v0 には version がないだけで、構造は v1 と同じ
+    |_| Ok(()),
+    // v2 で binaries が追加された
+    |document| insert_empty_list(document, "binaries"),
+    // v3 で directories が追加された
+    |document| insert_empty_list(document, "directories"),
+];
+
+fn insert_empty_list(document: &mut serde_json::Value, key: &str) -> Result<(), MigrationError> {
+    let object = document
+        .as_object_mut()
+        .ok_or_else(|| MigrationError::Malformed("Procedure is not a JSON object".to_string()))?;
+    object.insert(key.to_string(), serde_json::Value::Array(vec![]));
+    Ok(())
+}
+
 #[derive(sqlx::FromRow)]
 pub struct ProcedureRow {
-    pub procedure: sqlx::types::Json<ProcedureJson>,
+    pub procedure: sqlx::types::Json<serde_json::Value>,
+}
+
+impl TryFrom<ProcedureRow> for ProcedureJson {
+    type Error = MigrationError;
+
+    fn try_from(val: ProcedureRow) -> Result<Self, Self::Error> {
+        let document = migrate(val.procedure.0, &PROCEDURE_JSON_MIGRATIONS)?;
+        serde_json::from_value(document).map_err(|e| MigrationError::Malformed(e.to_string()))
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ProcedureJson {
+    pub version: u32,
     pub runtime_texts: Vec<RuntimeTextJson>,
     pub texts: Vec<TextJson>,
+    pub binaries: Vec<BinaryJson>,
+    pub directories: Vec<DirectoryJson>,
     pub empty_directories: Vec<EmptyDirectoryJson>,
     pub executions: Vec<ExecutionJson>,
 }
@@ -29,6 +64,18 @@ pub struct TextJson {
     pub dep_id: Uuid,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BinaryJson {
+    pub resource_id: Uuid,
+    pub dep_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DirectoryJson {
+    pub resource_id: Uuid,
+    pub dep_id: Uuid,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct EmptyDirectoryJson {
     pub dep_id: Uuid,
@@ -39,12 +86,16 @@ pub struct ExecutionJson {
     pub dependencies: Vec<DependencyJson>,
     pub dep_id: Uuid,
     pub time_reserved_ms: u64,
+    #[serde(default)]
+    pub no_cache: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DependencyJson {
     pub dep_id: Uuid,
     pub envvar_name: String,
+    #[serde(default)]
+    pub condition: Option<Condition>,
 }
 
 impl From<ProcedureJson> for Procedure {
@@ -52,6 +103,8 @@ impl From<ProcedureJson> for Procedure {
         Procedure {
             runtime_texts: val.runtime_texts.into_iter().map(Into::into).collect(),
             texts: val.texts.into_iter().map(Into::into).collect(),
+            binaries: val.binaries.into_iter().map(Into::into).collect(),
+            directories: val.directories.into_iter().map(Into::into).collect(),
             empty_directories: val.empty_directories.into_iter().map(Into::into).collect(),
             executions: val.executions.into_iter().map(Into::into).collect(),
         }
@@ -61,8 +114,11 @@ impl From<ProcedureJson> for Procedure {
 impl From<Procedure> for ProcedureJson {
     fn from(val: Procedure) -> Self {
         ProcedureJson {
+            version: PROCEDURE_JSON_VERSION,
             runtime_texts: val.runtime_texts.into_iter().map(Into::into).collect(),
             texts: val.texts.into_iter().map(Into::into).collect(),
+            binaries: val.binaries.into_iter().map(Into::into).collect(),
+            directories: val.directories.into_iter().map(Into::into).collect(),
             empty_directories: val.empty_directories.into_iter().map(Into::into).collect(),
             executions: val.executions.into_iter().map(Into::into).collect(),
         }
@@ -105,6 +161,42 @@ impl From<Text> for TextJson {
     }
 }
 
+impl From<BinaryJson> for Binary {
+    fn from(val: BinaryJson) -> Self {
+        Binary {
+            resource_id: val.resource_id.into(),
+            dep_id: val.dep_id.into(),
+        }
+    }
+}
+
+impl From<Binary> for BinaryJson {
+    fn from(val: Binary) -> Self {
+        BinaryJson {
+            resource_id: val.resource_id.into(),
+            dep_id: val.dep_id.into(),
+        }
+    }
+}
+
+impl From<DirectoryJson> for Directory {
+    fn from(val: DirectoryJson) -> Self {
+        Directory {
+            resource_id: val.resource_id.into(),
+            dep_id: val.dep_id.into(),
+        }
+    }
+}
+
+impl From<Directory> for DirectoryJson {
+    fn from(val: Directory) -> Self {
+        DirectoryJson {
+            resource_id: val.resource_id.into(),
+            dep_id: val.dep_id.into(),
+        }
+    }
+}
+
 impl From<EmptyDirectoryJson> for EmptyDirectory {
     fn from(val: EmptyDirectoryJson) -> Self {
         EmptyDirectory {
@@ -127,6 +219,7 @@ impl From<ExecutionJson> for Execution {
             dependencies: val.dependencies.into_iter().map(Into::into).collect(),
             dep_id: val.dep_id.into(),
             time_reserved_ms: val.time_reserved_ms,
+            no_cache: val.no_cache,
         }
     }
 }
@@ -137,6 +230,7 @@ impl From<Execution> for ExecutionJson {
             dependencies: val.dependencies.into_iter().map(Into::into).collect(),
             dep_id: val.dep_id.into(),
             time_reserved_ms: val.time_reserved_ms,
+            no_cache: val.no_cache,
         }
     }
 }
@@ -146,6 +240,7 @@ impl From<DependencyJson> for Dependency {
         Dependency {
             dep_id: val.dep_id.into(),
             envvar_name: val.envvar_name,
+            condition: val.condition,
         }
     }
 }
@@ -155,6 +250,7 @@ impl From<Dependency> for DependencyJson {
         DependencyJson {
             dep_id: val.dep_id.into(),
             envvar_name: val.envvar_name,
+            condition: val.condition,
         }
     }
 }
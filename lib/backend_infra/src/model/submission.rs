@@ -1,6 +1,8 @@
 use sqlx::types::chrono;
 
-use domain::model::submission::{JudgeResult, Submission};
+use domain::model::submission::{
+    ExecutionTimelineEntry, GroupJudgeResult, JudgeResult, JudgeStatus, Submission,
+};
 
 use crate::model::uuid::UuidRow;
 
@@ -18,6 +20,7 @@ pub struct SubmissionRow {
     pub max_time_ms: i32,
     pub max_memory_kib: i32,
     pub submitted_at: chrono::DateTime<chrono::Utc>,
+    pub compile_log: Option<String>,
 }
 
 impl From<SubmissionRow> for Submission {
@@ -30,11 +33,12 @@ impl From<SubmissionRow> for Submission {
             user_name: val.user_name,
             language_id: val.language_id,
             source: val.source,
-            overall_judge_status: val.judge_status,
+            overall_judge_status: JudgeStatus::new(&val.judge_status).unwrap(),
             total_score: val.total_score,
             max_time_ms: val.max_time_ms,
             max_memory_kib: val.max_memory_kib,
             submitted_at: val.submitted_at,
+            compile_log: val.compile_log,
         }
     }
 }
@@ -48,6 +52,11 @@ pub struct JudgeResultRow {
     pub score: i64,
     pub time_ms: i32,
     pub memory_kib: i32,
+    pub message: Option<String>,
+    pub stderr: Option<String>,
+    pub exit_signal: Option<i32>,
+    pub cpu_time_ms: Option<i32>,
+    pub wall_time_ms: Option<i32>,
 }
 
 impl From<JudgeResultRow> for JudgeResult {
@@ -55,10 +64,65 @@ impl From<JudgeResultRow> for JudgeResult {
         JudgeResult {
             testcase_id: val.testcase_id.0,
             testcase_name: val.testcase_name,
-            judge_status: val.judge_status,
+            judge_status: JudgeStatus::new(&val.judge_status).unwrap(),
             score: val.score,
             time_ms: val.time_ms,
             memory_kib: val.memory_kib,
+            message: val.message,
+            stderr: val.stderr,
+            signal: val.exit_signal,
+            cpu_time_ms: val.cpu_time_ms,
+            wall_time_ms: val.wall_time_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct GroupJudgeResultRow {
+    pub submission_id: UuidRow,
+    pub group_name: String,
+    pub judge_status: String,
+    pub score: i64,
+}
+
+impl From<GroupJudgeResultRow> for GroupJudgeResult {
+    fn from(val: GroupJudgeResultRow) -> Self {
+        GroupJudgeResult {
+            group_name: val.group_name,
+            judge_status: JudgeStatus::new(&val.judge_status).unwrap(),
+            score: val.score,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExecutionTimelineEntryRow {
+    pub submission_id: UuidRow,
+    pub dep_id: UuidRow,
+    pub name: String,
+    pub judge_status: String,
+    pub ready_at_ms: i64,
+    pub started_at_ms: Option<i64>,
+    pub finished_at_ms: i64,
+    pub host: Option<String>,
+    pub retry_count: i32,
+    pub outcome_size_bytes: Option<i64>,
+    pub cached: bool,
+}
+
+impl From<ExecutionTimelineEntryRow> for ExecutionTimelineEntry {
+    fn from(val: ExecutionTimelineEntryRow) -> Self {
+        ExecutionTimelineEntry {
+            dep_id: val.dep_id.0,
+            name: val.name,
+            judge_status: val.judge_status,
+            ready_at_ms: val.ready_at_ms,
+            started_at_ms: val.started_at_ms,
+            finished_at_ms: val.finished_at_ms,
+            host: val.host,
+            retry_count: val.retry_count,
+            outcome_size_bytes: val.outcome_size_bytes,
+            cached: val.cached,
         }
     }
 }
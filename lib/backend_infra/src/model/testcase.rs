@@ -1,4 +1,4 @@
-use domain::model::testcase::TestcaseSummary;
+use domain::model::testcase::{TestcaseGroup, TestcaseSummary};
 use sqlx::types::chrono;
 
 use crate::model::uuid::UuidRow;
@@ -10,6 +10,7 @@ pub struct TestcaseRow {
     pub problem_id: i64,
     pub input_id: UuidRow,
     pub output_id: UuidRow,
+    pub group_name: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -22,8 +23,28 @@ impl From<TestcaseRow> for TestcaseSummary {
             problem_id: val.problem_id,
             input_id: val.input_id.0,
             output_id: val.output_id.0,
+            group_name: val.group_name,
             created_at: val.created_at,
             updated_at: val.updated_at,
         }
     }
 }
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TestcaseGroupRow {
+    pub problem_id: i64,
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
+}
+
+impl From<TestcaseGroupRow> for TestcaseGroup {
+    fn from(val: TestcaseGroupRow) -> Self {
+        TestcaseGroup {
+            problem_id: val.problem_id,
+            name: val.name,
+            point: val.point,
+            policy: val.policy,
+        }
+    }
+}
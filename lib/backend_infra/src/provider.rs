@@ -160,7 +160,7 @@ impl Provider {
         let job =
             mock_job_service::JobService::new(host_temp_dir, container_temp_dir, pr_client, image)
                 .expect("Failed to init mock JobService");
-        JudgeServiceImpl::new(job)
+        JudgeServiceImpl::new(job).with_trace_dir(self.temp_dir.join("traces"))
     }
 
     pub fn provide_language_repository(&self) -> LanguageRepositoryImpl {
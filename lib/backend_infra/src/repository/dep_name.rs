@@ -2,10 +2,16 @@ use std::collections::HashMap;
 
 use anyhow::Ok;
 use axum::async_trait;
-use judge_core::model::{dep_name_repository::DepNameRepository, identifiers::DepId};
+use judge_core::model::{
+    dep_name_repository::DepNameRepository, identifiers::DepId,
+    procedure::job_metadata::JobMetadata,
+};
 use sqlx::{MySqlPool, QueryBuilder};
 
-use crate::model::{dep_name::DepNameRow, uuid::UuidRow};
+use crate::model::{
+    dep_name::{DepMetadataRow, DepNameRow},
+    uuid::UuidRow,
+};
 
 #[derive(Clone)]
 pub struct DepNameRepositoryImpl {
@@ -88,6 +94,32 @@ impl DepNameRepository<i64> for DepNameRepositoryImpl {
             .bind(problem_id)
             .execute(&self.pool)
             .await?;
+        sqlx::query("DELETE FROM dep_metadata WHERE problem_id = ?")
+            .bind(problem_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_by_dep_ids(&self, problem_id: i64, dep_ids: Vec<DepId>) -> anyhow::Result<()> {
+        if dep_ids.is_empty() {
+            return Ok(());
+        }
+
+        for table in ["dep_name", "dep_metadata"] {
+            let mut query_builder =
+                QueryBuilder::new(format!("DELETE FROM {} WHERE problem_id = ", table));
+            query_builder.push_bind(problem_id);
+            query_builder.push(" AND dep_id IN (");
+
+            let mut separated = query_builder.separated(", ");
+            for dep_id in dep_ids.iter() {
+                separated.push_bind(UuidRow((*dep_id).into()));
+            }
+            query_builder.push(")");
+
+            query_builder.build().execute(&self.pool).await?;
+        }
         Ok(())
     }
 
@@ -109,4 +141,52 @@ impl DepNameRepository<i64> for DepNameRepositoryImpl {
 
         Ok(dep_id_to_name)
     }
+
+    async fn insert_metadata_many(
+        &self,
+        problem_id: i64,
+        dep_id_to_metadata: HashMap<DepId, JobMetadata>,
+    ) -> anyhow::Result<()> {
+        if dep_id_to_metadata.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder =
+            QueryBuilder::new("INSERT INTO dep_metadata (problem_id, dep_id, metadata) VALUES ");
+
+        let mut separated = query_builder.separated(", ");
+        for (dep_id, metadata) in dep_id_to_metadata {
+            separated.push("(");
+            separated.push_bind_unseparated(problem_id);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(UuidRow(dep_id.into()));
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(sqlx::types::Json(metadata));
+            separated.push_unseparated(")");
+        }
+        // testcase の差し替えでまとめ実行の testcase が変わったときは上書きする
+        query_builder.push(" ON DUPLICATE KEY UPDATE metadata = VALUES(metadata)");
+
+        query_builder.build().execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_metadata_by_problem_id(
+        &self,
+        problem_id: i64,
+    ) -> anyhow::Result<HashMap<DepId, JobMetadata>> {
+        let rows: Vec<DepMetadataRow> = sqlx::query_as::<_, DepMetadataRow>(
+            "SELECT dep_id, metadata FROM dep_metadata WHERE problem_id = ?",
+        )
+        .bind(problem_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut dep_id_to_metadata = HashMap::new();
+        for row in rows {
+            dep_id_to_metadata.insert(row.dep_id.0.into(), row.metadata.0);
+        }
+
+        Ok(dep_id_to_metadata)
+    }
 }
@@ -49,7 +49,11 @@ impl ProcedureRepository for ProcedureRepositoryImpl {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(procedure_row.map(|row| row.procedure.0.into()))
+        let Some(procedure_row) = procedure_row else {
+            return Ok(None);
+        };
+        let procedure = ProcedureJson::try_from(procedure_row)?;
+        Ok(Some(procedure.into()))
     }
 
     async fn delete_procedure(&self, problem_id: i64) -> anyhow::Result<()> {
@@ -1,6 +1,6 @@
 use axum::async_trait;
-use domain::repository::resource_id_counter::ResourceIdCounterRepository;
-use sqlx::{FromRow, MySqlPool};
+use domain::repository::resource_id_counter::{ResourceIdCounterRepository, ResourceIdState};
+use sqlx::{FromRow, MySqlPool, types::chrono};
 use uuid::Uuid;
 
 #[derive(Debug, FromRow)]
@@ -8,6 +8,11 @@ struct ResourceIdRow {
     resource_id: String,
 }
 
+#[derive(Debug, FromRow)]
+struct DeletingSinceRow {
+    deleting_since: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 #[derive(Clone)]
 pub struct ResourceIdCounterRepositoryImpl {
     pool: MySqlPool,
@@ -21,7 +26,11 @@ impl ResourceIdCounterRepositoryImpl {
 
 #[async_trait]
 impl ResourceIdCounterRepository for ResourceIdCounterRepositoryImpl {
-    async fn get_deletable_resource_ids(&self, limit: usize) -> anyhow::Result<Vec<Uuid>> {
+    async fn claim_deletable_resource_ids(&self, limit: usize) -> anyhow::Result<Vec<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+
+        // 登録が同じ行を keep_resource_id で触っている間は待つ
+        // 削除中のまま残った行も、1 時間経てば取り直す
         let rows = sqlx::query_as::<_, ResourceIdRow>(
             r#"
             SELECT resource_id
@@ -29,12 +38,27 @@ impl ResourceIdCounterRepository for ResourceIdCounterRepositoryImpl {
             WHERE ref_count = 0 AND updated_at < NOW() - INTERVAL 1 HOUR
             ORDER BY updated_at ASC
             LIMIT ?
+            FOR UPDATE
             "#,
         )
         .bind(limit as u32)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *tx)
         .await?;
 
+        if !rows.is_empty() {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "UPDATE resource_id_counter SET deleting_since = NOW() WHERE resource_id IN (",
+            );
+            let mut separated = query_builder.separated(", ");
+            for row in rows.iter() {
+                separated.push_bind(row.resource_id.as_str());
+            }
+            query_builder.push(")");
+            query_builder.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
         let uuids = rows
             .into_iter()
             .filter_map(|row| Uuid::parse_str(&row.resource_id).ok())
@@ -48,8 +72,10 @@ impl ResourceIdCounterRepository for ResourceIdCounterRepositoryImpl {
             return Ok(());
         }
 
-        let mut query_builder =
-            sqlx::QueryBuilder::new("DELETE FROM resource_id_counter WHERE resource_id IN (");
+        // 削除までの間に再び参照された ID は残す
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "DELETE FROM resource_id_counter WHERE ref_count = 0 AND resource_id IN (",
+        );
 
         let mut separated = query_builder.separated(", ");
         for id in ids.iter() {
@@ -59,7 +85,7 @@ impl ResourceIdCounterRepository for ResourceIdCounterRepositoryImpl {
 
         query_builder.build().execute(&self.pool).await?;
 
-        Ok(())
+        self.update_timestamp_ids(ids).await
     }
 
     async fn update_timestamp_ids(&self, ids: Vec<Uuid>) -> anyhow::Result<()> {
@@ -68,7 +94,7 @@ impl ResourceIdCounterRepository for ResourceIdCounterRepositoryImpl {
         }
 
         let mut query_builder = sqlx::QueryBuilder::new(
-            "UPDATE resource_id_counter SET updated_at = NOW() WHERE resource_id IN (",
+            "UPDATE resource_id_counter SET updated_at = NOW(), deleting_since = NULL WHERE resource_id IN (",
         );
 
         let mut separated = query_builder.separated(", ");
@@ -81,4 +107,29 @@ impl ResourceIdCounterRepository for ResourceIdCounterRepositoryImpl {
 
         Ok(())
     }
+
+    async fn keep_resource_id(&self, id: Uuid) -> anyhow::Result<ResourceIdState> {
+        // 削除中でなければ updated_at を進め、しばらく GC の対象から外す
+        sqlx::query(
+            "UPDATE resource_id_counter SET updated_at = NOW() WHERE resource_id = ? AND deleting_since IS NULL",
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        let row = sqlx::query_as::<_, DeletingSinceRow>(
+            "SELECT deleting_since FROM resource_id_counter WHERE resource_id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match row {
+            None => ResourceIdState::Unknown,
+            Some(DeletingSinceRow {
+                deleting_since: None,
+            }) => ResourceIdState::Kept,
+            Some(_) => ResourceIdState::BeingDeleted,
+        })
+    }
 }
@@ -1,11 +1,12 @@
 use crate::model::{
-    submission::{JudgeResultRow, SubmissionRow},
+    submission::{ExecutionTimelineEntryRow, GroupJudgeResultRow, JudgeResultRow, SubmissionRow},
     uuid::UuidRow,
 };
 use axum::async_trait;
 use domain::{
     model::submission::{
-        CreateJudgeResult, CreateSubmission, JudgeResult, Submission, SubmissionGetQuery,
+        CreateExecutionTimelineEntry, CreateGroupJudgeResult, CreateJudgeResult, CreateSubmission,
+        ExecutionTimelineEntry, GroupJudgeResult, JudgeResult, Submission, SubmissionGetQuery,
         SubmissionOrderBy, UpdateSubmission,
     },
     repository::submission::SubmissionRepository,
@@ -39,7 +40,7 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
 
     async fn get_submission_results(&self, id: Uuid) -> anyhow::Result<Vec<JudgeResult>> {
         let results = sqlx::query_as::<_, JudgeResultRow>(
-            "SELECT * FROM submission_testcases WHERE submission_id = ?",
+            "SELECT * FROM submission_testcases WHERE submission_id = ? ORDER BY display_order IS NULL, display_order, testcase_name",
         )
         .bind(UuidRow(id))
         .fetch_all(&self.pool)
@@ -48,6 +49,34 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
         Ok(results.into_iter().map(|result| result.into()).collect())
     }
 
+    async fn get_submission_group_results(
+        &self,
+        id: Uuid,
+    ) -> anyhow::Result<Vec<GroupJudgeResult>> {
+        let results = sqlx::query_as::<_, GroupJudgeResultRow>(
+            "SELECT * FROM submission_groups WHERE submission_id = ? ORDER BY group_name",
+        )
+        .bind(UuidRow(id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results.into_iter().map(|result| result.into()).collect())
+    }
+
+    async fn get_execution_timeline(
+        &self,
+        id: Uuid,
+    ) -> anyhow::Result<Vec<ExecutionTimelineEntry>> {
+        let entries = sqlx::query_as::<_, ExecutionTimelineEntryRow>(
+            "SELECT * FROM submission_executions WHERE submission_id = ? ORDER BY ready_at_ms",
+        )
+        .bind(UuidRow(id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries.into_iter().map(|entry| entry.into()).collect())
+    }
+
     async fn get_submissions_by_query(
         &self,
         query: SubmissionGetQuery,
@@ -82,7 +111,7 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
         if let Some(judge_status) = query.judge_status {
             query_builder
                 .push(" AND submissions.judge_status = ")
-                .push_bind(judge_status);
+                .push_bind(judge_status.to_string());
         }
         if let Some(problem_id) = query.problem_id {
             query_builder
@@ -173,7 +202,7 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
         if let Some(judge_status) = query.judge_status {
             query_builder
                 .push(" AND submissions.judge_status = ")
-                .push_bind(judge_status);
+                .push_bind(judge_status.to_string());
         }
         if let Some(problem_id) = query.problem_id {
             query_builder
@@ -200,7 +229,7 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
         .bind(submission.user_id)
         .bind(submission.language_id)
         .bind(submission.source)
-        .bind(submission.judge_status)
+        .bind(submission.judge_status.to_string())
         .bind(submission.total_score)
         .bind(submission.max_time_ms)
         .bind(submission.max_memory_kib)
@@ -216,12 +245,13 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
         submission: UpdateSubmission,
     ) -> anyhow::Result<()> {
         sqlx::query(
-            "UPDATE submissions SET judge_status = ?, total_score = ?, max_time_ms = ?, max_memory_kib = ? WHERE id = ?",
+            "UPDATE submissions SET judge_status = ?, total_score = ?, max_time_ms = ?, max_memory_kib = ?, compile_log = ? WHERE id = ?",
         )
-        .bind(submission.judge_status)
+        .bind(submission.judge_status.to_string())
         .bind(submission.total_score)
         .bind(submission.max_time_ms)
         .bind(submission.max_memory_kib)
+        .bind(submission.compile_log)
         .bind(UuidRow(submission_id))
         .execute(&self.pool)
         .await?;
@@ -235,7 +265,7 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
         }
 
         let mut query_builder = QueryBuilder::new(
-            "INSERT INTO submission_testcases (submission_id, testcase_id, testcase_name, judge_status, score, time_ms, memory_kib) VALUES ",
+            "INSERT INTO submission_testcases (submission_id, testcase_id, testcase_name, judge_status, score, time_ms, memory_kib, message, stderr, exit_signal, cpu_time_ms, wall_time_ms, display_order) VALUES ",
         );
         let mut separated = query_builder.separated(", ");
         for r in results.into_iter() {
@@ -246,13 +276,93 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
             separated.push_unseparated(", ");
             separated.push_bind_unseparated(r.testcase_name);
             separated.push_unseparated(", ");
-            separated.push_bind_unseparated(r.judge_status);
+            separated.push_bind_unseparated(r.judge_status.to_string());
             separated.push_unseparated(", ");
             separated.push_bind_unseparated(r.score);
             separated.push_unseparated(", ");
             separated.push_bind_unseparated(r.time_ms);
             separated.push_unseparated(", ");
             separated.push_bind_unseparated(r.memory_kib);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.message);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.stderr);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.signal);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.cpu_time_ms);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.wall_time_ms);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.display_order);
+            separated.push_unseparated(")");
+        }
+        query_builder.build().execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn create_group_judge_results(
+        &self,
+        results: Vec<CreateGroupJudgeResult>,
+    ) -> anyhow::Result<()> {
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = QueryBuilder::new(
+            "INSERT INTO submission_groups (submission_id, group_name, judge_status, score) VALUES ",
+        );
+        let mut separated = query_builder.separated(", ");
+        for r in results.into_iter() {
+            separated.push("(");
+            separated.push_bind_unseparated(UuidRow(r.submission_id));
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.group_name);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.judge_status.to_string());
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(r.score);
+            separated.push_unseparated(")");
+        }
+        query_builder.build().execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn create_execution_timeline(
+        &self,
+        entries: Vec<CreateExecutionTimelineEntry>,
+    ) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = QueryBuilder::new(
+            "INSERT INTO submission_executions (submission_id, dep_id, name, judge_status, ready_at_ms, started_at_ms, finished_at_ms, host, retry_count, outcome_size_bytes, cached) VALUES ",
+        );
+        let mut separated = query_builder.separated(", ");
+        for e in entries.into_iter() {
+            separated.push("(");
+            separated.push_bind_unseparated(UuidRow(e.submission_id));
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(UuidRow(e.dep_id));
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.name);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.judge_status);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.ready_at_ms);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.started_at_ms);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.finished_at_ms);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.host);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.retry_count);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.outcome_size_bytes);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(e.cached);
             separated.push_unseparated(")");
         }
         query_builder.build().execute(&self.pool).await?;
@@ -267,6 +377,14 @@ impl SubmissionRepository for SubmissionRepositoryImpl {
             .bind(UuidRow(submission_id))
             .execute(&self.pool)
             .await?;
+        sqlx::query("DELETE FROM submission_groups WHERE submission_id = ?")
+            .bind(UuidRow(submission_id))
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM submission_executions WHERE submission_id = ?")
+            .bind(UuidRow(submission_id))
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 }
@@ -1,12 +1,15 @@
 use axum::async_trait;
 use domain::{
-    model::testcase::{CreateTestcase, TestcaseSummary},
+    model::testcase::{CreateTestcase, TestcaseGroup, TestcaseSummary},
     repository::testcase::TestcaseRepository,
 };
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
-use crate::model::{testcase::TestcaseRow, uuid::UuidRow};
+use crate::model::{
+    testcase::{TestcaseGroupRow, TestcaseRow},
+    uuid::UuidRow,
+};
 
 #[derive(Clone)]
 pub struct TestcaseRepositoryImpl {
@@ -46,7 +49,7 @@ impl TestcaseRepository for TestcaseRepositoryImpl {
         }
 
         let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO `testcases` (`id`, `problem_id`, `name`, `input_id`, `output_id`) VALUES ",
+            "INSERT INTO `testcases` (`id`, `problem_id`, `name`, `input_id`, `output_id`, `group_name`) VALUES ",
         );
 
         let mut separated = query_builder.separated(", ");
@@ -61,6 +64,8 @@ impl TestcaseRepository for TestcaseRepositoryImpl {
             separated.push_bind_unseparated(UuidRow(testcase.input_id));
             separated.push_unseparated(", ");
             separated.push_bind_unseparated(UuidRow(testcase.output_id));
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(testcase.group_name);
             separated.push_unseparated(")");
         }
 
@@ -69,6 +74,31 @@ impl TestcaseRepository for TestcaseRepositoryImpl {
         Ok(())
     }
 
+    async fn update_testcase(&self, testcase: CreateTestcase) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE `testcases` SET `name` = ?, `input_id` = ?, `output_id` = ?, `group_name` = ? WHERE `id` = ? AND `problem_id` = ?",
+        )
+        .bind(testcase.name)
+        .bind(UuidRow(testcase.input_id))
+        .bind(UuidRow(testcase.output_id))
+        .bind(testcase.group_name)
+        .bind(UuidRow(testcase.id))
+        .bind(testcase.problem_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_testcase(&self, id: Uuid) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM `testcases` WHERE `id` = ?")
+            .bind(UuidRow(id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     async fn delete_testcases(&self, problem_id: i64) -> anyhow::Result<()> {
         sqlx::query("DELETE FROM `testcases` WHERE `problem_id` = ?")
             .bind(problem_id)
@@ -77,4 +107,49 @@ impl TestcaseRepository for TestcaseRepositoryImpl {
 
         Ok(())
     }
+
+    async fn get_testcase_groups(&self, problem_id: i64) -> anyhow::Result<Vec<TestcaseGroup>> {
+        let groups = sqlx::query_as::<_, TestcaseGroupRow>(
+            "SELECT * FROM `testcase_groups` WHERE `problem_id` = ? ORDER BY `name`",
+        )
+        .bind(problem_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups.into_iter().map(|row| row.into()).collect())
+    }
+
+    async fn replace_testcase_groups(
+        &self,
+        problem_id: i64,
+        groups: Vec<TestcaseGroup>,
+    ) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM `testcase_groups` WHERE `problem_id` = ?")
+            .bind(problem_id)
+            .execute(&self.pool)
+            .await?;
+
+        if !groups.is_empty() {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "INSERT INTO `testcase_groups` (`problem_id`, `name`, `point`, `policy`) VALUES ",
+            );
+
+            let mut separated = query_builder.separated(", ");
+            for group in groups {
+                separated.push("(");
+                separated.push_bind_unseparated(group.problem_id);
+                separated.push_unseparated(", ");
+                separated.push_bind_unseparated(group.name);
+                separated.push_unseparated(", ");
+                separated.push_bind_unseparated(group.point);
+                separated.push_unseparated(", ");
+                separated.push_bind_unseparated(group.policy);
+                separated.push_unseparated(")");
+            }
+
+            query_builder.build().execute(&self.pool).await?;
+        }
+
+        Ok(())
+    }
 }
@@ -4,7 +4,9 @@ pub mod error;
 pub mod github_oauth2;
 pub mod google_oauth2;
 pub mod language;
+pub mod polygon_package;
 pub mod problem;
+pub mod problem_package;
 pub mod submission;
 pub mod testcase;
 pub mod traq_oauth2;
@@ -0,0 +1,630 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use domain::model::problem::CreateNormalProblem;
+use judge_core::{
+    logic::problem_presets::{
+        grouped_judge::{
+            GroupScoringPolicy, TestcaseGroup, create_grouped_judge_procedure_with_checker,
+        },
+        interactive_judge::{InteractiveJudgeInteractor, create_interactive_judge_procedure},
+        normal_judge::{NormalJudgeTestcase, create_normal_judge_procedure_with_checker},
+        special_judge::{SpecialJudgeChecker, create_special_judge_procedure},
+    },
+    model::{builtin_checker::BuiltinChecker, procedure::writer_schema::Procedure},
+};
+use roxmltree::Node;
+
+use crate::model::{
+    problem::NormalProblemDto,
+    problem_package::{
+        PACKAGE_VERSION, PackageManifest, PackageTestcase, PackageTestcaseGroup, ProblemPackage,
+    },
+};
+
+const PROBLEM_XML_PATH: &str = "problem.xml";
+
+/// Polygon has no notion of difficulty
+const DEFAULT_DIFFICULTY: i32 = 1;
+
+/// A Polygon (Codeforces) package mapped onto our model
+pub struct PolygonImport {
+    pub problem: CreateNormalProblem,
+    pub procedure: Procedure,
+    pub testcases: Vec<PackageTestcase>,
+    pub groups: Vec<PackageTestcaseGroup>,
+    /// Parts of the package that were left out of the import
+    pub unsupported: Vec<UnsupportedPart>,
+}
+
+pub struct PolygonImportDto {
+    pub problem: NormalProblemDto,
+    pub unsupported: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsupportedPart {
+    Statement {
+        path: String,
+        kind: String,
+    },
+    Generator(String),
+    Validator(String),
+    /// A test whose files are not in the package, typically because it is generated on Polygon
+    MissingTest(String),
+    FileIo,
+    TestPoints,
+    GroupDependencies(String),
+    /// Groups are only honored with a built-in checker
+    GroupsIgnored,
+}
+
+impl std::fmt::Display for UnsupportedPart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsupportedPart::Statement { path, kind } => {
+                write!(f, "Statement {} ({}) was not imported", path, kind)
+            }
+            UnsupportedPart::Generator(path) => write!(f, "Generator {} was not imported", path),
+            UnsupportedPart::Validator(path) => write!(f, "Validator {} was not imported", path),
+            UnsupportedPart::MissingTest(name) => {
+                write!(f, "Test {} has no input or answer in the package", name)
+            }
+            UnsupportedPart::FileIo => write!(f, "File input/output is replaced by stdin/stdout"),
+            UnsupportedPart::TestPoints => write!(f, "Points of individual tests were ignored"),
+            UnsupportedPart::GroupDependencies(name) => {
+                write!(f, "Dependencies of group {} were ignored", name)
+            }
+            UnsupportedPart::GroupsIgnored => {
+                write!(
+                    f,
+                    "Test groups were ignored because of the custom checker or interactor"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolygonError {
+    #[error("{0} is missing from the package")]
+    MissingFile(String),
+    #[error("Malformed package: {0}")]
+    Malformed(String),
+    #[error("No language matches Polygon source type {0}")]
+    UnsupportedLanguage(String),
+    #[error("The package has no importable tests")]
+    NoTestcases,
+}
+
+impl PolygonImport {
+    pub fn into_package(self) -> (ProblemPackage, Vec<UnsupportedPart>) {
+        let package = ProblemPackage {
+            manifest: PackageManifest {
+                version: PACKAGE_VERSION,
+                title: self.problem.title,
+                difficulty: self.problem.difficulty,
+                time_limit_ms: self.problem.time_limit_ms,
+                memory_limit_kib: self.problem.memory_limit_kib,
+                testcases: self.testcases,
+                groups: self.groups,
+            },
+            statement: self.problem.statement,
+            procedure: self.procedure,
+        };
+        (package, self.unsupported)
+    }
+}
+
+/// Reads every file in a zip archive, keyed by its path in the archive
+pub fn unpack_zip(archive: &[u8]) -> Result<HashMap<String, Vec<u8>>, PolygonError> {
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive))
+        .map_err(|e| PolygonError::Malformed(e.to_string()))?;
+    let mut files = HashMap::new();
+    for i in 0..zip.len() {
+        let mut file = zip
+            .by_index(i)
+            .map_err(|e| PolygonError::Malformed(e.to_string()))?;
+        if !file.is_file() {
+            continue;
+        }
+        let path = file.name().to_string();
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|e| PolygonError::Malformed(e.to_string()))?;
+        files.insert(path, content);
+    }
+    Ok(files)
+}
+
+/// Maps a Polygon package onto a problem and a procedure built from the presets.
+///
+/// `files` are the files of the package keyed by path; `languages` are the language tags
+/// checkers and interactors may be compiled with.
+pub fn import_polygon_package(
+    mut files: HashMap<String, Vec<u8>>,
+    author_id: i64,
+    languages: &[String],
+) -> Result<PolygonImport, PolygonError> {
+    // zip によっては全体が1つのディレクトリに入っているので、problem.xml の場所を根とする
+    let root = files
+        .keys()
+        .filter(|path| path.rsplit('/').next() == Some(PROBLEM_XML_PATH))
+        .min_by_key(|path| path.len())
+        .map(|path| path[..path.len() - PROBLEM_XML_PATH.len()].to_string())
+        .ok_or_else(|| PolygonError::MissingFile(PROBLEM_XML_PATH.to_string()))?;
+    if !root.is_empty() {
+        files = files
+            .into_iter()
+            .filter_map(|(path, content)| {
+                path.strip_prefix(&root)
+                    .map(|path| (path.to_string(), content))
+            })
+            .collect();
+    }
+
+    let xml = text_file(&files, PROBLEM_XML_PATH)?;
+    let document =
+        roxmltree::Document::parse(&xml).map_err(|e| PolygonError::Malformed(e.to_string()))?;
+    let problem = document.root_element();
+    let mut unsupported = Vec::new();
+
+    let title = element(problem, "names")
+        .and_then(|names| {
+            let mut names = elements(names, "name");
+            names
+                .clone()
+                .find(|name| name.attribute("language") == Some("english"))
+                .or_else(|| names.next())
+        })
+        .and_then(|name| name.attribute("value"))
+        .or_else(|| problem.attribute("short-name"))
+        .unwrap_or_default()
+        .to_string();
+
+    // 問題文は TeX なので取り込まない
+    if let Some(statements) = element(problem, "statements") {
+        for statement in elements(statements, "statement") {
+            unsupported.push(UnsupportedPart::Statement {
+                path: statement.attribute("path").unwrap_or_default().to_string(),
+                kind: statement.attribute("type").unwrap_or_default().to_string(),
+            });
+        }
+    }
+
+    let judging = element(problem, "judging")
+        .ok_or_else(|| PolygonError::Malformed("judging is missing".to_string()))?;
+    if [
+        judging.attribute("input-file"),
+        judging.attribute("output-file"),
+    ]
+    .iter()
+    .any(|file| file.is_some_and(|file| !file.is_empty()))
+    {
+        unsupported.push(UnsupportedPart::FileIo);
+    }
+    let testset = elements(judging, "testset")
+        .find(|testset| testset.attribute("name") == Some("tests"))
+        .or_else(|| element(judging, "testset"))
+        .ok_or_else(|| PolygonError::Malformed("testset is missing".to_string()))?;
+
+    let time_limit_ms: i32 = element_text(testset, "time-limit")?
+        .parse()
+        .map_err(|_| PolygonError::Malformed("time-limit is not a number".to_string()))?;
+    let memory_limit_bytes: i64 = element_text(testset, "memory-limit")?
+        .parse()
+        .map_err(|_| PolygonError::Malformed("memory-limit is not a number".to_string()))?;
+    let memory_limit_kib = i32::try_from(memory_limit_bytes / 1024)
+        .map_err(|_| PolygonError::Malformed("memory-limit is too large".to_string()))?;
+    let input_pattern = element_text(testset, "input-path-pattern")?;
+    let answer_pattern = element_text(testset, "answer-path-pattern")?;
+
+    let mut testcases = Vec::new();
+    let mut test_points: HashMap<String, f64> = HashMap::new();
+    let mut has_test_points = false;
+    for (i, test) in element(testset, "tests")
+        .into_iter()
+        .flat_map(|tests| elements(tests, "test"))
+        .enumerate()
+    {
+        let index = i + 1;
+        let name = if test.attribute("sample") == Some("true") {
+            format!("sample_{:02}", index)
+        } else {
+            format!("test_{:02}", index)
+        };
+        let group = test.attribute("group").map(|group| group.to_string());
+        if let Some(points) = test.attribute("points") {
+            let points: f64 = points
+                .parse()
+                .map_err(|_| PolygonError::Malformed(format!("points of test {}", index)))?;
+            has_test_points = true;
+            if let Some(group) = group.as_ref() {
+                *test_points.entry(group.clone()).or_default() += points;
+            }
+        }
+        let input = files.get(&format_index(&input_pattern, index));
+        let expected_output = files.get(&format_index(&answer_pattern, index));
+        let (Some(input), Some(expected_output)) = (input, expected_output) else {
+            unsupported.push(UnsupportedPart::MissingTest(name));
+            continue;
+        };
+        let as_text = |content: &Vec<u8>| {
+            String::from_utf8(content.clone())
+                .map_err(|_| PolygonError::Malformed(format!("test {} is not UTF-8 text", index)))
+        };
+        testcases.push(NormalJudgeTestcase {
+            input: as_text(input)?,
+            expected_output: as_text(expected_output)?,
+            name,
+            group,
+        });
+    }
+    if testcases.is_empty() {
+        return Err(PolygonError::NoTestcases);
+    }
+
+    // 宣言されていないグループは、所属するテストの配点の合計を配点とする
+    let mut groups: Vec<TestcaseGroup> = Vec::new();
+    for group in element(testset, "groups")
+        .into_iter()
+        .flat_map(|groups| elements(groups, "group"))
+    {
+        let name = group
+            .attribute("name")
+            .ok_or_else(|| PolygonError::Malformed("group without a name".to_string()))?
+            .to_string();
+        if element(group, "dependencies").is_some() {
+            unsupported.push(UnsupportedPart::GroupDependencies(name.clone()));
+        }
+        let point = match group.attribute("points") {
+            Some(points) => points
+                .parse::<f64>()
+                .map_err(|_| PolygonError::Malformed(format!("points of group {}", name)))?,
+            None => test_points.get(&name).copied().unwrap_or_default(),
+        };
+        let policy = match group.attribute("points-policy") {
+            Some("each-test") => GroupScoringPolicy::Proportional,
+            _ => GroupScoringPolicy::AllOrNothing,
+        };
+        groups.push(TestcaseGroup {
+            name,
+            point: point.round() as i64,
+            policy,
+        });
+    }
+    for testcase in testcases.iter() {
+        let Some(group) = testcase.group.as_ref() else {
+            continue;
+        };
+        if groups.iter().all(|declared| &declared.name != group) {
+            groups.push(TestcaseGroup {
+                name: group.clone(),
+                point: test_points.get(group).copied().unwrap_or_default().round() as i64,
+                policy: GroupScoringPolicy::Proportional,
+            });
+        }
+    }
+    if groups.is_empty() && has_test_points {
+        unsupported.push(UnsupportedPart::TestPoints);
+    }
+    // グループに属さないテストがあると grouped judge にできない
+    if !groups.is_empty() && testcases.iter().any(|testcase| testcase.group.is_none()) {
+        return Err(PolygonError::Malformed(
+            "some tests do not belong to any group".to_string(),
+        ));
+    }
+
+    let assets = element(problem, "assets");
+    let checker = assets.and_then(|assets| element(assets, "checker"));
+    let interactor = assets.and_then(|assets| element(assets, "interactor"));
+
+    let mut asset_sources = Vec::new();
+    if let Some(validators) = assets.and_then(|assets| element(assets, "validators")) {
+        for validator in elements(validators, "validator") {
+            if let Some(path) = element(validator, "source").and_then(|s| s.attribute("path")) {
+                unsupported.push(UnsupportedPart::Validator(path.to_string()));
+                asset_sources.push(path.to_string());
+            }
+        }
+    }
+    for asset in [checker, interactor].into_iter().flatten() {
+        if let Some(path) = element(asset, "source").and_then(|s| s.attribute("path")) {
+            asset_sources.push(path.to_string());
+        }
+    }
+    // 残りの実行ファイルはジェネレーターとみなす
+    if let Some(executables) =
+        element(problem, "files").and_then(|files| element(files, "executables"))
+    {
+        for executable in elements(executables, "executable") {
+            if let Some(path) = element(executable, "source").and_then(|s| s.attribute("path"))
+                && !asset_sources.iter().any(|source| source == path)
+            {
+                unsupported.push(UnsupportedPart::Generator(path.to_string()));
+            }
+        }
+    }
+
+    let builtin = match (interactor, checker) {
+        (Some(_), _) => None,
+        (None, Some(checker)) => checker.attribute("name").and_then(standard_checker),
+        (None, None) => Some(BuiltinChecker::Exact),
+    };
+    // グループ採点のプリセットは組み込みのチェッカーでしか比較できない
+    if !groups.is_empty() && builtin.is_none() {
+        unsupported.push(UnsupportedPart::GroupsIgnored);
+        groups.clear();
+        for testcase in testcases.iter_mut() {
+            testcase.group = None;
+        }
+    }
+    let package_groups = groups
+        .iter()
+        .map(|group| PackageTestcaseGroup {
+            name: group.name.clone(),
+            point: group.point,
+            policy: group.policy.as_str().to_string(),
+        })
+        .collect();
+    let package_testcases = testcases
+        .iter()
+        .map(|testcase| PackageTestcase {
+            name: testcase.name.clone(),
+            group: testcase.group.clone(),
+        })
+        .collect();
+
+    let procedure = match (interactor, checker, builtin) {
+        (Some(interactor), _, _) => {
+            let (source, language_tag) = asset_source(&files, interactor, languages)?;
+            create_interactive_judge_procedure(
+                testcases,
+                InteractiveJudgeInteractor {
+                    source,
+                    language_tag,
+                },
+            )
+        }
+        (None, _, Some(builtin)) if !groups.is_empty() => {
+            create_grouped_judge_procedure_with_checker(testcases, groups, builtin)
+        }
+        (None, _, Some(builtin)) => create_normal_judge_procedure_with_checker(testcases, builtin),
+        (None, Some(checker), None) => {
+            let (source, language_tag) = asset_source(&files, checker, languages)?;
+            create_special_judge_procedure(
+                testcases,
+                SpecialJudgeChecker {
+                    source,
+                    language_tag,
+                },
+            )
+        }
+        (None, None, None) => unreachable!("a package without a checker compares exactly"),
+    }
+    .map_err(|e| PolygonError::Malformed(e.to_string()))?;
+
+    Ok(PolygonImport {
+        problem: CreateNormalProblem {
+            author_id,
+            title,
+            statement: String::new(),
+            time_limit_ms,
+            memory_limit_kib,
+            difficulty: DEFAULT_DIFFICULTY,
+        },
+        procedure,
+        testcases: package_testcases,
+        groups: package_groups,
+        unsupported,
+    })
+}
+
+fn element<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn elements<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &str,
+) -> impl Iterator<Item = Node<'a, 'input>> + Clone {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn element_text(node: Node, name: &str) -> Result<String, PolygonError> {
+    element(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .ok_or_else(|| PolygonError::Malformed(format!("{} is missing", name)))
+}
+
+fn text_file(files: &HashMap<String, Vec<u8>>, path: &str) -> Result<String, PolygonError> {
+    let content = files
+        .get(path)
+        .ok_or_else(|| PolygonError::MissingFile(path.to_string()))?;
+    String::from_utf8(content.clone())
+        .map_err(|_| PolygonError::Malformed(format!("{} is not UTF-8 text", path)))
+}
+
+/// Expands the `%d`-style placeholder of Polygon path patterns such as `tests/%02d`
+fn format_index(pattern: &str, index: usize) -> String {
+    let Some(start) = pattern.find('%') else {
+        return pattern.to_string();
+    };
+    let Some(end) = pattern[start..].find('d').map(|end| start + end) else {
+        return pattern.to_string();
+    };
+    let width: usize = pattern[start + 1..end].parse().unwrap_or(0);
+    format!(
+        "{}{:0width$}{}",
+        &pattern[..start],
+        index,
+        &pattern[end + 1..],
+        width = width
+    )
+}
+
+/// testlib の標準チェッカーのうち、組み込みチェッカーで置き換えられるもの
+fn standard_checker(name: &str) -> Option<BuiltinChecker> {
+    let name = name.strip_prefix("std::")?;
+    let name = name.strip_suffix(".cpp").unwrap_or(name);
+    let float = |eps: f64| BuiltinChecker::Float {
+        abs_eps: eps,
+        rel_eps: eps,
+    };
+    match name {
+        "wcmp" | "lcmp" | "ncmp" | "hcmp" => Some(BuiltinChecker::Exact),
+        "yesno" | "nyesno" => Some(BuiltinChecker::YesNo),
+        "rcmp4" => Some(float(1e-4)),
+        "rcmp6" | "dcmp" => Some(float(1e-6)),
+        "rcmp9" => Some(float(1e-9)),
+        _ => None,
+    }
+}
+
+/// Source and language tag of a checker or interactor, with headers of the package inlined
+fn asset_source(
+    files: &HashMap<String, Vec<u8>>,
+    asset: Node,
+    languages: &[String],
+) -> Result<(String, String), PolygonError> {
+    let source = element(asset, "source").ok_or_else(|| {
+        PolygonError::Malformed(format!("{} has no source", asset.tag_name().name()))
+    })?;
+    let path = source
+        .attribute("path")
+        .ok_or_else(|| PolygonError::Malformed("source without a path".to_string()))?;
+    let kind = source.attribute("type").unwrap_or_default();
+    let family = language_family(kind.split('.').next().unwrap_or_default());
+    let language_tag = languages
+        .iter()
+        .find(|language| language_family(language) == family)
+        .ok_or_else(|| PolygonError::UnsupportedLanguage(kind.to_string()))?
+        .clone();
+    Ok((inline_includes(files, path)?, language_tag))
+}
+
+/// `C++23(gcc)` and `cpp.g++17` both become `cpp`
+fn language_family(name: &str) -> String {
+    name.to_lowercase()
+        .replace("++", "pp")
+        .replace('#', "sharp")
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect()
+}
+
+/// チェッカーは1ファイルでビルドされるので、`#include "testlib.h"` などを展開しておく
+fn inline_includes(files: &HashMap<String, Vec<u8>>, path: &str) -> Result<String, PolygonError> {
+    let source = text_file(files, path)?;
+    let dir = path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default();
+    let mut inlined = String::new();
+    for line in source.lines() {
+        let header = line
+            .trim()
+            .strip_prefix("#include")
+            .map(|rest| rest.trim())
+            .and_then(|rest| rest.strip_prefix('"'))
+            .and_then(|rest| rest.strip_suffix('"'));
+        let header_path = header.map(|header| match dir {
+            "" => header.to_string(),
+            dir => format!("{}/{}", dir, header),
+        });
+        match header_path {
+            Some(header_path) if files.contains_key(&header_path) => {
+                inlined.push_str(&text_file(files, &header_path)?);
+            }
+            _ => inlined.push_str(line),
+        }
+        inlined.push('\n');
+    }
+    Ok(inlined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROBLEM_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="no"?>
+<problem revision="3" short-name="a-plus-b">
+    <names>
+        <name language="english" value="A + B"/>
+    </names>
+    <statements>
+        <statement charset="UTF-8" language="english" path="statements/english/problem.tex" type="application/x-tex"/>
+    </statements>
+    <judging input-file="" output-file="">
+        <testset name="tests">
+            <time-limit>2000</time-limit>
+            <memory-limit>268435456</memory-limit>
+            <test-count>3</test-count>
+            <input-path-pattern>tests/%02d</input-path-pattern>
+            <answer-path-pattern>tests/%02d.a</answer-path-pattern>
+            <tests>
+                <test method="manual" sample="true"/>
+                <test method="manual"/>
+                <test cmd="gen 10" method="generated"/>
+            </tests>
+        </testset>
+    </judging>
+    <files>
+        <executables>
+            <executable><source path="files/gen.cpp" type="cpp.g++17"/></executable>
+            <executable><source path="files/val.cpp" type="cpp.g++17"/></executable>
+        </executables>
+    </files>
+    <assets>
+        <checker name="std::rcmp6.cpp" type="testlib"><source path="files/check.cpp" type="cpp.g++17"/></checker>
+        <validators>
+            <validator><source path="files/val.cpp" type="cpp.g++17"/></validator>
+        </validators>
+    </assets>
+</problem>
+"#;
+
+    #[test]
+    fn imports_polygon_package() {
+        let files: HashMap<String, Vec<u8>> = [
+            ("a-plus-b/problem.xml", PROBLEM_XML),
+            ("a-plus-b/tests/01", "1 2\n"),
+            ("a-plus-b/tests/01.a", "3\n"),
+            ("a-plus-b/tests/02", "4 5\n"),
+            ("a-plus-b/tests/02.a", "9\n"),
+        ]
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+        .collect();
+
+        let import = import_polygon_package(files, 1, &["C++23(gcc)".to_string()]).unwrap();
+        assert_eq!(import.problem.title, "A + B");
+        assert_eq!(import.problem.time_limit_ms, 2000);
+        assert_eq!(import.problem.memory_limit_kib, 262144);
+        assert_eq!(
+            import
+                .testcases
+                .iter()
+                .map(|testcase| testcase.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sample_01", "test_02"]
+        );
+        assert_eq!(
+            import.unsupported,
+            vec![
+                UnsupportedPart::Statement {
+                    path: "statements/english/problem.tex".to_string(),
+                    kind: "application/x-tex".to_string(),
+                },
+                UnsupportedPart::MissingTest("test_03".to_string()),
+                UnsupportedPart::Validator("files/val.cpp".to_string()),
+                UnsupportedPart::Generator("files/gen.cpp".to_string()),
+            ]
+        );
+
+        let (package, _) = import.into_package();
+        assert_eq!(package.testcase("test_02"), Some(("4 5\n", "9\n")));
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use judge_core::{
+    logic::schema_migration::load_writer_schema,
+    model::procedure::{
+        job_metadata::JobRole,
+        writer_schema::{Procedure, ResourceKind},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Version of the package layout; bump it when `manifest.json` or the file layout changes
+pub const PACKAGE_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+const STATEMENT_PATH: &str = "problem.md";
+const PROCEDURE_PATH: &str = "procedure.json";
+
+/// A problem with everything needed to judge it, portable between instances.
+///
+/// The archive is a tar file laid out like `pylib/example/problems`:
+/// `manifest.json`, `problem.md`, `procedure.json`, `scripts/<name>`,
+/// `testcases/<name>/{input,output}.txt` and `resources/<name>`.
+/// Contents in `procedure.json` are left empty and read from the other files instead.
+pub struct ProblemPackage {
+    pub manifest: PackageManifest,
+    pub statement: String,
+    pub procedure: Procedure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageManifest {
+    pub version: u32,
+    pub title: String,
+    pub difficulty: i32,
+    pub time_limit_ms: i32,
+    pub memory_limit_kib: i32,
+    pub testcases: Vec<PackageTestcase>,
+    pub groups: Vec<PackageTestcaseGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageTestcase {
+    pub name: String,
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageTestcaseGroup {
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PackageError {
+    #[error("{0} is missing from the package")]
+    MissingFile(String),
+    #[error("Package version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("Testcase {0} has no input or output")]
+    MissingTestcase(String),
+    #[error("Malformed package: {0}")]
+    Malformed(String),
+}
+
+impl ProblemPackage {
+    /// Input and expected output of a testcase listed in the manifest
+    pub fn testcase(&self, name: &str) -> Option<(&str, &str)> {
+        let text = |role: JobRole| {
+            let resource_name = self.testcase_resource_name(role, name)?;
+            self.procedure
+                .resources
+                .iter()
+                .find_map(|resource| match resource {
+                    ResourceKind::TextFile(text) if text.name == resource_name => {
+                        Some(text.content.as_str())
+                    }
+                    _ => None,
+                })
+        };
+        Some((
+            text(JobRole::TestcaseInput)?,
+            text(JobRole::TestcaseExpected)?,
+        ))
+    }
+
+    /// Name of the resource playing `role` for the testcase, found by the metadata of the procedure
+    fn testcase_resource_name(&self, role: JobRole, testcase: &str) -> Option<&str> {
+        self.procedure
+            .resources
+            .iter()
+            .map(|resource| resource.name())
+            .find(|name| {
+                let metadata = self.procedure.metadata_of(name);
+                metadata.role == role && metadata.testcases.iter().any(|t| t == testcase)
+            })
+    }
+
+    pub fn to_archive(&self) -> Result<Vec<u8>, PackageError> {
+        let paths = self.resource_paths();
+        let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+        let mut procedure = self.procedure.clone();
+        for script in procedure.scripts.iter_mut() {
+            files.push((
+                script_path(&script.name),
+                std::mem::take(&mut script.content).into_bytes(),
+            ));
+        }
+        for resource in procedure.resources.iter_mut() {
+            let Some(path) = paths.get(resource.name()).cloned() else {
+                continue;
+            };
+            let content = match resource {
+                ResourceKind::TextFile(text) => std::mem::take(&mut text.content).into_bytes(),
+                ResourceKind::BinaryFile(binary) => std::mem::take(&mut binary.content),
+                ResourceKind::Directory(directory) => std::mem::take(&mut directory.archive),
+                _ => continue,
+            };
+            files.push((path, content));
+        }
+        files.push((
+            MANIFEST_PATH.to_string(),
+            to_json(&self.manifest)?.into_bytes(),
+        ));
+        files.push((
+            STATEMENT_PATH.to_string(),
+            self.statement.clone().into_bytes(),
+        ));
+        files.push((
+            PROCEDURE_PATH.to_string(),
+            to_json(&procedure)?.into_bytes(),
+        ));
+
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(&mut header, path, content.as_slice())
+                .map_err(|e| PackageError::Malformed(e.to_string()))?;
+        }
+        builder
+            .into_inner()
+            .map_err(|e| PackageError::Malformed(e.to_string()))
+    }
+
+    pub fn from_archive(archive: &[u8]) -> Result<Self, PackageError> {
+        let mut files = HashMap::new();
+        let mut archive = tar::Archive::new(archive);
+        for entry in archive
+            .entries()
+            .map_err(|e| PackageError::Malformed(e.to_string()))?
+        {
+            let mut entry = entry.map_err(|e| PackageError::Malformed(e.to_string()))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry
+                .path()
+                .map_err(|e| PackageError::Malformed(e.to_string()))?
+                .to_string_lossy()
+                .into_owned();
+            let mut content = Vec::new();
+            entry
+                .read_to_end(&mut content)
+                .map_err(|e| PackageError::Malformed(e.to_string()))?;
+            files.insert(path, content);
+        }
+        let mut take = |path: &str| {
+            files
+                .remove(path)
+                .ok_or_else(|| PackageError::MissingFile(path.to_string()))
+        };
+        let as_text = |path: &str, content: Vec<u8>| {
+            String::from_utf8(content)
+                .map_err(|_| PackageError::Malformed(format!("{} is not UTF-8 text", path)))
+        };
+
+        let manifest: PackageManifest = serde_json::from_slice(&take(MANIFEST_PATH)?)
+            .map_err(|e| PackageError::Malformed(e.to_string()))?;
+        if manifest.version > PACKAGE_VERSION {
+            return Err(PackageError::UnsupportedVersion(manifest.version));
+        }
+        let statement = as_text(STATEMENT_PATH, take(STATEMENT_PATH)?)?;
+        let procedure = load_writer_schema(&as_text(PROCEDURE_PATH, take(PROCEDURE_PATH)?)?)
+            .map_err(|e| PackageError::Malformed(e.to_string()))?;
+
+        let mut package = ProblemPackage {
+            manifest,
+            statement,
+            procedure,
+        };
+        let paths = package.resource_paths();
+        for script in package.procedure.scripts.iter_mut() {
+            let path = script_path(&script.name);
+            script.content = as_text(&path, take(&path)?)?;
+        }
+        for resource in package.procedure.resources.iter_mut() {
+            let Some(path) = paths.get(resource.name()) else {
+                continue;
+            };
+            match resource {
+                ResourceKind::TextFile(text) => text.content = as_text(path, take(path)?)?,
+                ResourceKind::BinaryFile(binary) => binary.content = take(path)?,
+                ResourceKind::Directory(directory) => directory.archive = take(path)?,
+                _ => {}
+            }
+        }
+        for testcase in package.manifest.testcases.iter() {
+            if package.testcase(&testcase.name).is_none() {
+                return Err(PackageError::MissingTestcase(testcase.name.clone()));
+            }
+        }
+        Ok(package)
+    }
+
+    /// Where the content of each resource is stored; testcases keep the layout of the examples
+    fn resource_paths(&self) -> HashMap<String, String> {
+        let mut paths = HashMap::new();
+        for resource in self.procedure.resources.iter() {
+            match resource {
+                ResourceKind::TextFile(_) | ResourceKind::BinaryFile(_) => {
+                    paths.insert(
+                        resource.name().to_string(),
+                        format!("resources/{}", resource.name()),
+                    );
+                }
+                ResourceKind::Directory(_) => {
+                    paths.insert(
+                        resource.name().to_string(),
+                        format!("resources/{}.tar", resource.name()),
+                    );
+                }
+                _ => {}
+            }
+        }
+        for testcase in self.manifest.testcases.iter() {
+            let files = [
+                (JobRole::TestcaseInput, "input.txt"),
+                (JobRole::TestcaseExpected, "output.txt"),
+            ];
+            for (role, file_name) in files {
+                if let Some(name) = self.testcase_resource_name(role, &testcase.name) {
+                    paths.insert(
+                        name.to_string(),
+                        format!("testcases/{}/{}", testcase.name, file_name),
+                    );
+                }
+            }
+        }
+        paths
+    }
+}
+
+fn script_path(name: &str) -> String {
+    format!("scripts/{}", name)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, PackageError> {
+    serde_json::to_string_pretty(value).map_err(|e| PackageError::Malformed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use judge_core::logic::problem_presets::normal_judge::{
+        NormalJudgeTestcase, create_normal_judge_procedure,
+    };
+
+    #[test]
+    fn round_trips_through_archive() {
+        let procedure = create_normal_judge_procedure(vec![NormalJudgeTestcase {
+            name: "sample_1".to_string(),
+            input: "1 2\n".to_string(),
+            expected_output: "3\n".to_string(),
+            group: None,
+        }])
+        .unwrap();
+        let package = ProblemPackage {
+            manifest: PackageManifest {
+                version: PACKAGE_VERSION,
+                title: "Addition".to_string(),
+                difficulty: 1,
+                time_limit_ms: 2000,
+                memory_limit_kib: 262144,
+                testcases: vec![PackageTestcase {
+                    name: "sample_1".to_string(),
+                    group: None,
+                }],
+                groups: vec![],
+            },
+            statement: "# Addition".to_string(),
+            procedure,
+        };
+
+        let restored = ProblemPackage::from_archive(&package.to_archive().unwrap()).unwrap();
+        assert_eq!(restored.statement, package.statement);
+        assert_eq!(restored.testcase("sample_1"), Some(("1 2\n", "3\n")));
+        assert_eq!(
+            serde_json::to_value(&restored.procedure).unwrap(),
+            serde_json::to_value(&package.procedure).unwrap()
+        );
+    }
+}
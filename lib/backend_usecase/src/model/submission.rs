@@ -1,4 +1,4 @@
-use domain::model::submission::Submission;
+use domain::model::submission::{ExecutionTimelineEntry, Submission};
 use sqlx::types::chrono;
 
 #[derive(Debug, Clone)]
@@ -16,7 +16,9 @@ pub struct SubmissionDto {
     pub source_code: String,
     pub code_length: i32,
     pub overall_judge_status: String,
+    pub compile_log: Option<String>,
     pub judge_results: Vec<JudgeResultDto>,
+    pub group_results: Vec<GroupJudgeResultDto>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +29,50 @@ pub struct JudgeResultDto {
     pub score: i64,
     pub time_ms: i32,
     pub memory_kib: i32,
+    pub message: Option<String>,
+    /// `None` unless the viewer is the submitter or the author of the problem
+    pub stderr: Option<String>,
+    pub signal: Option<i32>,
+    pub cpu_time_ms: Option<i32>,
+    pub wall_time_ms: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupJudgeResultDto {
+    pub group_name: String,
+    pub judge_status: String,
+    pub score: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionTimelineEntryDto {
+    pub dep_id: String,
+    pub name: String,
+    pub judge_status: String,
+    pub ready_at_ms: i64,
+    pub started_at_ms: Option<i64>,
+    pub finished_at_ms: i64,
+    pub host: Option<String>,
+    pub retry_count: i32,
+    pub outcome_size_bytes: Option<i64>,
+    pub cached: bool,
+}
+
+impl From<ExecutionTimelineEntry> for ExecutionTimelineEntryDto {
+    fn from(entry: ExecutionTimelineEntry) -> Self {
+        ExecutionTimelineEntryDto {
+            dep_id: entry.dep_id.to_string(),
+            name: entry.name,
+            judge_status: entry.judge_status,
+            ready_at_ms: entry.ready_at_ms,
+            started_at_ms: entry.started_at_ms,
+            finished_at_ms: entry.finished_at_ms,
+            host: entry.host,
+            retry_count: entry.retry_count,
+            outcome_size_bytes: entry.outcome_size_bytes,
+            cached: entry.cached,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -59,7 +105,7 @@ impl From<Submission> for SubmissionSummaryDto {
             max_time_ms: submission.max_time_ms,
             max_memory_kib: submission.max_memory_kib,
             code_length: submission.source.len() as i32,
-            judge_status: submission.overall_judge_status,
+            judge_status: submission.overall_judge_status.to_string(),
         }
     }
 }
@@ -1,10 +1,11 @@
-use domain::model::testcase::TestcaseSummary;
+use domain::model::testcase::{TestcaseGroup, TestcaseSummary};
 use sqlx::types::chrono;
 use uuid::Uuid;
 
 pub struct TestcaseSummaryDto {
     pub id: Uuid,
     pub name: String,
+    pub group: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -14,6 +15,7 @@ impl From<TestcaseSummary> for TestcaseSummaryDto {
         Self {
             id: testcase.id,
             name: testcase.name,
+            group: testcase.group_name,
             created_at: testcase.created_at,
             updated_at: testcase.updated_at,
         }
@@ -23,6 +25,7 @@ impl From<TestcaseSummary> for TestcaseSummaryDto {
 pub struct TestcaseDto {
     pub id: Uuid,
     pub name: String,
+    pub group: Option<String>,
     pub input: String,
     pub output: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -34,10 +37,36 @@ pub struct CreateTestcaseData {
     pub name: String,
     pub input: String,
     pub output: String,
+    pub group: Option<String>,
 }
 
 pub struct UpdateTestcaseData {
     pub name: String,
     pub input: String,
     pub output: String,
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestcaseGroupDto {
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
+}
+
+impl From<TestcaseGroup> for TestcaseGroupDto {
+    fn from(group: TestcaseGroup) -> Self {
+        Self {
+            name: group.name,
+            point: group.point,
+            policy: group.policy,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateTestcaseGroupData {
+    pub name: String,
+    pub point: i64,
+    pub policy: String,
 }
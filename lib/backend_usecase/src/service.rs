@@ -6,6 +6,7 @@ pub mod google_oauth2;
 pub mod icon;
 pub mod language;
 pub mod problem;
+pub mod problem_package;
 pub mod submission;
 pub mod testcase;
 pub mod traq_oauth2;
@@ -15,7 +15,7 @@ use domain::{
 use judge_core::{
     logic::{
         problem_presets::normal_judge::create_normal_judge_procedure,
-        registered_procedure_remover::remove, writer_schema_registerer::register,
+        writer_schema_registerer::register,
     },
     model::{
         dep_name_repository::DepNameRepository, problem_registry::ProblemRegistryServer,
@@ -370,18 +370,8 @@ impl<
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
-        // Delete procedure resources from problem registry (S3) and procedure from database
-        if let Some(procedure) = self
-            .procedure_repository
-            .get_procedure(problem_id)
-            .await
-            .map_err(UsecaseError::internal_server_error_map())?
-        {
-            remove(procedure, self.problem_registry_server.clone())
-                .await
-                .map_err(UsecaseError::internal_server_error_map())?;
-        }
-
+        // Resources may be shared with other problems, so they are left to the
+        // resource_id_counter garbage collection instead of being removed here
         self.procedure_repository
             .delete_procedure(problem_id)
             .await
@@ -0,0 +1,411 @@
+use domain::{
+    model::{
+        problem::CreateNormalProblem,
+        testcase::{CreateTestcase, TestcaseGroup},
+    },
+    repository::{
+        language::LanguageRepository, problem::ProblemRepository, procedure::ProcedureRepository,
+        session::SessionRepository, testcase::TestcaseRepository, user::UserRepository,
+    },
+};
+use judge_core::{
+    logic::{
+        problem_presets::grouped_judge::GroupScoringPolicy, validation,
+        writer_schema_exporter::export, writer_schema_registerer::register,
+    },
+    model::{
+        dep_name_repository::DepNameRepository,
+        identifiers::ResourceId,
+        problem_registry::{ProblemRegistryClient, ProblemRegistryServer},
+    },
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::model::{
+    error::UsecaseError,
+    polygon_package::{PolygonImportDto, import_polygon_package, unpack_zip},
+    problem::{CreateNormalProblemData, NormalProblemDto},
+    problem_package::{
+        PACKAGE_VERSION, PackageManifest, PackageTestcase, PackageTestcaseGroup, ProblemPackage,
+    },
+};
+
+#[derive(Clone)]
+pub struct ProblemPackageService<
+    PR: ProblemRepository,
+    UR: UserRepository,
+    SR: SessionRepository,
+    TR: TestcaseRepository,
+    PcR: ProcedureRepository,
+    RPC: ProblemRegistryClient,
+    PRS: ProblemRegistryServer,
+    DNR: DepNameRepository<i64>,
+    LR: LanguageRepository,
+> {
+    problem_repository: PR,
+    user_repository: UR,
+    session_repository: SR,
+    testcase_repository: TR,
+    procedure_repository: PcR,
+    problem_registry_client: RPC,
+    problem_registry_server: PRS,
+    dep_name_repository: DNR,
+    language_repository: LR,
+}
+
+impl<
+    PR: ProblemRepository,
+    UR: UserRepository,
+    SR: SessionRepository,
+    TR: TestcaseRepository,
+    PcR: ProcedureRepository,
+    RPC: ProblemRegistryClient,
+    PRS: ProblemRegistryServer,
+    DNR: DepNameRepository<i64>,
+    LR: LanguageRepository,
+> ProblemPackageService<PR, UR, SR, TR, PcR, RPC, PRS, DNR, LR>
+{
+    pub fn new(
+        problem_repository: PR,
+        user_repository: UR,
+        session_repository: SR,
+        testcase_repository: TR,
+        procedure_repository: PcR,
+        problem_registry_client: RPC,
+        problem_registry_server: PRS,
+        dep_name_repository: DNR,
+        language_repository: LR,
+    ) -> Self {
+        Self {
+            problem_repository,
+            user_repository,
+            session_repository,
+            testcase_repository,
+            procedure_repository,
+            problem_registry_client,
+            problem_registry_server,
+            dep_name_repository,
+            language_repository,
+        }
+    }
+}
+
+impl<
+    PR: ProblemRepository,
+    UR: UserRepository,
+    SR: SessionRepository,
+    TR: TestcaseRepository,
+    PcR: ProcedureRepository,
+    RPC: ProblemRegistryClient,
+    PRS: ProblemRegistryServer,
+    DNR: DepNameRepository<i64>,
+    LR: LanguageRepository,
+> ProblemPackageService<PR, UR, SR, TR, PcR, RPC, PRS, DNR, LR>
+{
+    /// Packs the problem, its testcases and its procedure into a single archive
+    pub async fn export_problem(
+        &self,
+        session_id: Option<&str>,
+        problem_id: String,
+    ) -> Result<Vec<u8>, UsecaseError> {
+        let problem_id: i64 = problem_id
+            .parse()
+            .map_err(|_| UsecaseError::ValidateError)?;
+
+        let problem = self
+            .problem_repository
+            .get_problem(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)?;
+
+        let display_id = match session_id {
+            Some(session_id) => self
+                .session_repository
+                .get_display_id_by_session_id(session_id)
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?,
+            None => None,
+        };
+
+        // 作問者以外にはテストケースやスクリプトを見せない
+        if display_id.is_none_or(|id| id != problem.author_id) {
+            if problem.is_public {
+                return Err(UsecaseError::Forbidden);
+            } else {
+                return Err(UsecaseError::NotFound);
+            }
+        }
+
+        let testcases = self
+            .testcase_repository
+            .get_testcases(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        let groups = self
+            .testcase_repository
+            .get_testcase_groups(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        let procedure = self
+            .procedure_repository
+            .get_procedure(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or_else(|| UsecaseError::internal_server_error_msg("missing procedure"))?;
+
+        let procedure = export(
+            procedure,
+            self.problem_registry_client.clone(),
+            self.dep_name_repository.clone(),
+            problem_id,
+        )
+        .await
+        .map_err(UsecaseError::internal_server_error_map())?;
+
+        let package = ProblemPackage {
+            manifest: PackageManifest {
+                version: PACKAGE_VERSION,
+                title: problem.title,
+                difficulty: problem.difficulty,
+                time_limit_ms: problem.time_limit_ms,
+                memory_limit_kib: problem.memory_limit_kib,
+                testcases: testcases
+                    .into_iter()
+                    .map(|testcase| PackageTestcase {
+                        name: testcase.name,
+                        group: testcase.group_name,
+                    })
+                    .collect(),
+                groups: groups
+                    .into_iter()
+                    .map(|group| PackageTestcaseGroup {
+                        name: group.name,
+                        point: group.point,
+                        policy: group.policy,
+                    })
+                    .collect(),
+            },
+            statement: problem.statement,
+            procedure,
+        };
+
+        package
+            .to_archive()
+            .map_err(UsecaseError::internal_server_error_map())
+    }
+
+    /// Creates a new private problem owned by the caller from an exported archive
+    pub async fn import_problem(
+        &self,
+        session_id: Option<&str>,
+        archive: Vec<u8>,
+    ) -> Result<NormalProblemDto, UsecaseError> {
+        let display_id = self.importer_id(session_id).await?;
+        let package =
+            ProblemPackage::from_archive(&archive).map_err(|_| UsecaseError::ValidateError)?;
+        self.import_package(display_id, package).await
+    }
+
+    /// Creates a new private problem owned by the caller from a zipped Polygon package
+    pub async fn import_polygon_problem(
+        &self,
+        session_id: Option<&str>,
+        archive: Vec<u8>,
+    ) -> Result<PolygonImportDto, UsecaseError> {
+        let display_id = self.importer_id(session_id).await?;
+        let languages: Vec<String> = self
+            .language_repository
+            .get_languages()
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .into_iter()
+            .map(|language| language.id)
+            .collect();
+
+        let files = unpack_zip(&archive).map_err(|_| UsecaseError::ValidateError)?;
+        let (package, unsupported) = import_polygon_package(files, display_id, &languages)
+            .map_err(|_| UsecaseError::ValidateError)?
+            .into_package();
+        let problem = self.import_package(display_id, package).await?;
+
+        Ok(PolygonImportDto {
+            problem,
+            unsupported: unsupported.iter().map(|part| part.to_string()).collect(),
+        })
+    }
+
+    /// 問題を作成できるユーザーの display_id
+    async fn importer_id(&self, session_id: Option<&str>) -> Result<i64, UsecaseError> {
+        let display_id = match session_id {
+            Some(session_id) => self
+                .session_repository
+                .get_display_id_by_session_id(session_id)
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?
+                .ok_or(UsecaseError::Forbidden)?,
+            None => return Err(UsecaseError::Forbidden),
+        };
+
+        let user = self
+            .user_repository
+            .get_user_by_display_id(display_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::Forbidden)?;
+
+        match user.role {
+            domain::model::user::UserRole::Admin | domain::model::user::UserRole::TrapUser => {
+                Ok(display_id)
+            }
+            _ => Err(UsecaseError::Forbidden),
+        }
+    }
+
+    async fn import_package(
+        &self,
+        display_id: i64,
+        package: ProblemPackage,
+    ) -> Result<NormalProblemDto, UsecaseError> {
+        let manifest = &package.manifest;
+
+        CreateNormalProblemData {
+            title: manifest.title.clone(),
+            statement: package.statement.clone(),
+            time_limit_ms: manifest.time_limit_ms,
+            memory_limit_kib: manifest.memory_limit_kib,
+            difficulty: manifest.difficulty,
+        }
+        .validate()
+        .map_err(|_| UsecaseError::ValidateError)?;
+
+        // グループの配点・採点方式と、testcaseの名前被り・所属を確認
+        {
+            let mut group_names = std::collections::HashSet::new();
+            for group in manifest.groups.iter() {
+                if group.point < 0 || group.policy.parse::<GroupScoringPolicy>().is_err() {
+                    return Err(UsecaseError::ValidateError);
+                }
+                if !group_names.insert(group.name.as_str()) {
+                    return Err(UsecaseError::ValidateError);
+                }
+            }
+            let mut testcase_names = std::collections::HashSet::new();
+            for testcase in manifest.testcases.iter() {
+                if !testcase_names.insert(testcase.name.as_str()) {
+                    return Err(UsecaseError::ValidateError);
+                }
+                if !manifest.groups.is_empty()
+                    && testcase
+                        .group
+                        .as_ref()
+                        .is_none_or(|group| !group_names.contains(group.as_str()))
+                {
+                    return Err(UsecaseError::ValidateError);
+                }
+            }
+        }
+
+        if !validation::writer_schema::validate(&package.procedure).is_empty() {
+            return Err(UsecaseError::ValidateError);
+        }
+
+        let mut testcases = Vec::new();
+        for testcase in manifest.testcases.iter() {
+            let (input, output) = package
+                .testcase(&testcase.name)
+                .ok_or(UsecaseError::ValidateError)?;
+            testcases.push((
+                testcase.name.clone(),
+                testcase.group.clone(),
+                ResourceId::from_content(input.as_bytes()),
+                ResourceId::from_content(output.as_bytes()),
+            ));
+        }
+        let groups = manifest.groups.clone();
+
+        let problem_id = self
+            .problem_repository
+            .create_problem(CreateNormalProblem {
+                author_id: display_id,
+                title: package.manifest.title,
+                statement: package.statement,
+                time_limit_ms: package.manifest.time_limit_ms,
+                memory_limit_kib: package.manifest.memory_limit_kib,
+                difficulty: package.manifest.difficulty,
+            })
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+
+        let registered_procedure = match register(
+            package.procedure,
+            self.problem_registry_server.clone(),
+            self.dep_name_repository.clone(),
+            problem_id,
+        )
+        .await
+        {
+            Ok(registered_procedure) => registered_procedure,
+            Err(e) => {
+                let _ = self.problem_repository.delete_problem(problem_id).await;
+                return Err(UsecaseError::internal_server_error(e));
+            }
+        };
+
+        if self
+            .procedure_repository
+            .create_procedure(problem_id, registered_procedure)
+            .await
+            .is_err()
+        {
+            let _ = self.dep_name_repository.remove_many(problem_id).await;
+            let _ = self.problem_repository.delete_problem(problem_id).await;
+            return Err(UsecaseError::internal_server_error_msg(
+                "failed to create procedure for imported problem",
+            ));
+        }
+
+        self.testcase_repository
+            .replace_testcase_groups(
+                problem_id,
+                groups
+                    .into_iter()
+                    .map(|group| TestcaseGroup {
+                        problem_id,
+                        name: group.name,
+                        point: group.point,
+                        policy: group.policy,
+                    })
+                    .collect(),
+            )
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+
+        self.testcase_repository
+            .create_testcases(
+                testcases
+                    .into_iter()
+                    .map(|(name, group_name, input_id, output_id)| CreateTestcase {
+                        id: Uuid::now_v7(),
+                        problem_id,
+                        name,
+                        input_id: input_id.into(),
+                        output_id: output_id.into(),
+                        group_name,
+                    })
+                    .collect(),
+            )
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+
+        let problem = self
+            .problem_repository
+            .get_problem(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)?;
+
+        Ok(problem.into())
+    }
+}
@@ -1,15 +1,16 @@
 use crate::model::{
     error::UsecaseError,
     submission::{
-        CreateSubmissionData, JudgeResultDto, SubmissionDto, SubmissionGetQueryData,
-        SubmissionOrderByData, SubmissionSummaryDto, SubmissionsDto,
+        CreateSubmissionData, ExecutionTimelineEntryDto, GroupJudgeResultDto, JudgeResultDto,
+        SubmissionDto, SubmissionGetQueryData, SubmissionOrderByData, SubmissionSummaryDto,
+        SubmissionsDto,
     },
 };
 use domain::{
     model::{
         submission::{
-            CreateJudgeResult, CreateSubmission, SubmissionGetQuery, SubmissionOrderBy,
-            UpdateSubmission,
+            CreateExecutionTimelineEntry, CreateGroupJudgeResult, CreateJudgeResult,
+            CreateSubmission, JudgeStatus, SubmissionGetQuery, SubmissionOrderBy, UpdateSubmission,
         },
         user::UserRole,
     },
@@ -19,15 +20,18 @@ use domain::{
         user::UserRepository,
     },
 };
+use futures::StreamExt;
 use judge_core::{
     constant::label::single_judge,
     model::{
         dep_name_repository::DepNameRepository,
         judge::{JudgeRequest, JudgeService},
-        judge_output::{ExecutionJobResult, ExecutionResult},
+        judge_output::{DisplayableExecutionResult, ExecutionJobResult, ExecutionResult},
+        procedure::job_metadata::{self, JobRole},
     },
 };
 use std::collections::HashMap;
+use tokio::{sync::Mutex, task::JoinHandle};
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -51,6 +55,8 @@ pub struct SubmissionService<
     language_repository: LR,
     dep_name_repository: DNR,
     judge_service: JS,
+    /// Running judge tasks by submission, so that a rejudge can wait for the previous run
+    judge_tasks: std::sync::Arc<Mutex<HashMap<Uuid, JoinHandle<()>>>>,
 }
 
 impl<
@@ -86,8 +92,15 @@ impl<
             language_repository,
             dep_name_repository,
             judge_service,
+            judge_tasks: std::sync::Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    async fn track_judge_task(&self, submission_id: Uuid, task: JoinHandle<()>) {
+        let mut judge_tasks = self.judge_tasks.lock().await;
+        judge_tasks.retain(|_, task| !task.is_finished());
+        judge_tasks.insert(submission_id, task);
+    }
 }
 
 impl<
@@ -124,27 +137,35 @@ impl<
             .map_err(UsecaseError::internal_server_error_map())?
             .ok_or(UsecaseError::NotFound)?;
 
-        if !problem.is_public {
-            let session_id = session_id.ok_or(UsecaseError::NotFound)?;
-
-            let display_id = self
+        let display_id = match session_id {
+            Some(session_id) => self
                 .session_repository
                 .get_display_id_by_session_id(&session_id)
                 .await
-                .map_err(UsecaseError::internal_server_error_map())?
-                .ok_or(UsecaseError::NotFound)?;
+                .map_err(UsecaseError::internal_server_error_map())?,
+            None => None,
+        };
 
-            if display_id != problem.author_id {
-                return Err(UsecaseError::NotFound);
-            }
+        if !problem.is_public && display_id != Some(problem.author_id) {
+            return Err(UsecaseError::NotFound);
         }
 
+        // 標準エラー出力にはテストケースの中身が含まれうるので、提出者と問題の作者にだけ見せる
+        let can_see_stderr =
+            display_id.is_some_and(|id| id == submission.user_id || id == problem.author_id);
+
         let judge_results = self
             .submission_repository
             .get_submission_results(submission_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
+        let group_results = self
+            .submission_repository
+            .get_submission_group_results(submission_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+
         Ok(SubmissionDto {
             id: submission.id.to_string(),
             user_id: submission.user_id.to_string(),
@@ -158,16 +179,30 @@ impl<
             max_memory_kib: submission.max_memory_kib,
             code_length: submission.source.len() as i32,
             source_code: submission.source,
-            overall_judge_status: submission.overall_judge_status,
+            overall_judge_status: submission.overall_judge_status.to_string(),
+            compile_log: submission.compile_log,
             judge_results: judge_results
                 .into_iter()
                 .map(|testcase| JudgeResultDto {
                     testcase_id: testcase.testcase_id.to_string(),
                     testcase_name: testcase.testcase_name,
-                    judge_status: testcase.judge_status,
+                    judge_status: testcase.judge_status.to_string(),
                     score: testcase.score,
                     time_ms: testcase.time_ms,
                     memory_kib: testcase.memory_kib,
+                    message: testcase.message,
+                    stderr: testcase.stderr.filter(|_| can_see_stderr),
+                    signal: testcase.signal,
+                    cpu_time_ms: testcase.cpu_time_ms,
+                    wall_time_ms: testcase.wall_time_ms,
+                })
+                .collect(),
+            group_results: group_results
+                .into_iter()
+                .map(|group| GroupJudgeResultDto {
+                    group_name: group.group_name,
+                    judge_status: group.judge_status.to_string(),
+                    score: group.score,
                 })
                 .collect(),
         })
@@ -213,11 +248,16 @@ impl<
             Ok(Some(user_id))
         })?;
 
+        let judge_status = query.judge_status.map_or(Ok(None), |status| {
+            let status = JudgeStatus::new(&status).map_err(|_| UsecaseError::ValidateError)?;
+            Ok(Some(status))
+        })?;
+
         let query = SubmissionGetQuery {
             user_id: user_id,
             limit: query.limit.unwrap_or(50),
             offset: query.offset.unwrap_or(0),
-            judge_status: query.judge_status,
+            judge_status: judge_status,
             language_id: language_id,
             user_name: query.user_name,
             user_query: user_query,
@@ -314,7 +354,7 @@ impl<
             user_id: display_id,
             language_id: body.language_id,
             source: body.source.clone(),
-            judge_status: "WJ".to_string(),
+            judge_status: JudgeStatus::WJ,
             total_score: 0,
             max_time_ms: 0,
             max_memory_kib: 0,
@@ -351,7 +391,7 @@ impl<
             "spawning judge task"
         );
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             tracing::info!(%submission_id, problem_id, "judge task started");
             if let Err(e) = self_clone
                 .async_judge_submission(submission_id, problem_id, procedure, runtime_texts)
@@ -385,6 +425,7 @@ impl<
                 }
             }
         });
+        self.track_judge_task(submission_id, task).await;
 
         self.get_submission(session_id, submission_id.to_string())
             .await
@@ -465,6 +506,19 @@ impl<
             problem.memory_limit_kib.to_string(),
         );
 
+        // 実行中のジャッジがあれば打ち切る
+        self.judge_service
+            .cancel(submission_id.into())
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        // 打ち切ったジャッジがすでに届いた結果を書き終えるまで待ってから消す
+        let previous_task = self.judge_tasks.lock().await.remove(&submission_id);
+        if let Some(previous_task) = previous_task {
+            if let Err(e) = previous_task.await {
+                tracing::warn!(%submission_id, error = %e, "previous judge task panicked");
+            }
+        }
+
         self.submission_repository
             .update_submission(
                 submission_id,
@@ -472,7 +526,8 @@ impl<
                     total_score: 0,
                     max_time_ms: 0,
                     max_memory_kib: 0,
-                    judge_status: "WJ".to_string(),
+                    judge_status: JudgeStatus::WJ,
+                    compile_log: None,
                 },
             )
             .await
@@ -492,7 +547,7 @@ impl<
             "spawning rejudge task"
         );
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             tracing::info!(%submission_id, problem_id = problem.id, "rejudge task started");
             if let Err(e) = self_clone
                 .async_judge_submission(submission_id, problem.id, procedure, runtime_texts)
@@ -526,10 +581,90 @@ impl<
                 }
             }
         });
+        self.track_judge_task(submission_id, task).await;
 
         Ok(())
     }
 
+    /// The trace bundle recorded while judging the submission, admin only
+    pub async fn get_judge_trace(
+        &self,
+        session_id: Option<&str>,
+        submission_id: String,
+    ) -> anyhow::Result<Vec<u8>, UsecaseError> {
+        let user_id = match session_id {
+            Some(session_id) => self
+                .session_repository
+                .get_user_id_by_session_id(session_id)
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?,
+            None => None,
+        };
+        let user_id = user_id.ok_or(UsecaseError::Forbidden)?;
+        let user = self
+            .user_repository
+            .get_user_by_user_id(user_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::Forbidden)?;
+        if user.role != UserRole::Admin {
+            return Err(UsecaseError::Forbidden);
+        }
+
+        let submission_id =
+            Uuid::parse_str(&submission_id).map_err(|_| UsecaseError::ValidateError)?;
+
+        // ジャッジ ID は提出 ID と同じ
+        self.judge_service
+            .trace(submission_id.into())
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)
+    }
+
+    /// When and where each execution of the submission ran, admin only
+    pub async fn get_execution_timeline(
+        &self,
+        session_id: Option<&str>,
+        submission_id: String,
+    ) -> anyhow::Result<Vec<ExecutionTimelineEntryDto>, UsecaseError> {
+        let user_id = match session_id {
+            Some(session_id) => self
+                .session_repository
+                .get_user_id_by_session_id(session_id)
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?,
+            None => None,
+        };
+        let user_id = user_id.ok_or(UsecaseError::Forbidden)?;
+        let user = self
+            .user_repository
+            .get_user_by_user_id(user_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::Forbidden)?;
+        if user.role != UserRole::Admin {
+            return Err(UsecaseError::Forbidden);
+        }
+
+        let submission_id =
+            Uuid::parse_str(&submission_id).map_err(|_| UsecaseError::ValidateError)?;
+
+        self.submission_repository
+            .get_submission(submission_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)?;
+
+        let timeline = self
+            .submission_repository
+            .get_execution_timeline(submission_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+
+        Ok(timeline.into_iter().map(|entry| entry.into()).collect())
+    }
+
     #[tracing::instrument(skip(self, procedure, runtime_texts), fields(%submission_id, problem_id))]
     async fn async_judge_submission(
         &self,
@@ -538,40 +673,6 @@ impl<
         procedure: judge_core::model::procedure::registered::Procedure,
         runtime_texts: HashMap<String, String>,
     ) -> anyhow::Result<(), UsecaseError> {
-        let judge_response = self
-            .judge_service
-            .judge(JudgeRequest {
-                procedure,
-                runtime_texts,
-            })
-            .await;
-
-        let judge_response = match judge_response {
-            Ok(res) => res,
-            Err(e) => {
-                self.submission_repository
-                    .update_submission(
-                        submission_id,
-                        UpdateSubmission {
-                            total_score: 0,
-                            max_time_ms: 0,
-                            max_memory_kib: 0,
-                            judge_status: "IE".to_string(),
-                        },
-                    )
-                    .await
-                    .map_err(UsecaseError::internal_server_error_map())?;
-                return Err(UsecaseError::internal_server_error(e));
-            }
-        };
-
-        let keys = judge_response.keys().cloned().collect::<Vec<_>>();
-        let testcase_names = self
-            .dep_name_repository
-            .get_many(keys)
-            .await
-            .map_err(UsecaseError::internal_server_error_map())?;
-
         let testcases = self
             .testcase_repository
             .get_testcases(problem_id)
@@ -583,81 +684,152 @@ impl<
             .map(|tc| (tc.name, tc.id))
             .collect::<HashMap<_, _>>();
 
-        let mut total_score: i64 = 0; // summary phase から取ってくる
-        let mut max_time_ms: i32 = 0; // summary phase から取ってくる
-        let mut max_memory_kib: i32 = 0; // summary phase から取ってくる
-        let mut overall_status = "IE".to_string(); // summary phase から -> compile phase から取る
-        let mut testcase_results: Vec<CreateJudgeResult> = Vec::new();
-
-        for (dep_id, result) in judge_response.into_iter() {
-            match result {
-                ExecutionJobResult::ExecutionResult(exec) => match exec {
-                    ExecutionResult::Displayable(res) => {
-                        let testcase_name = testcase_names
-                            .get(&dep_id)
-                            .cloned()
-                            .flatten()
-                            .unwrap_or_default();
-
-                        if testcase_name
-                            .starts_with(judge_core::constant::job_name::TEST_PHASE_PREFIX)
-                        {
-                            let testcase_name = testcase_name
-                                .strip_prefix(judge_core::constant::job_name::TEST_PHASE_PREFIX)
-                                .unwrap_or(testcase_name.as_str())
-                                .to_string();
-
-                            let testcase_id =
-                                name_to_id.get(&testcase_name).cloned().unwrap_or_default();
-
-                            testcase_results.push(CreateJudgeResult {
+        // 結果の振り分けは名前ではなく登録時のメタデータで行う
+        let dep_names = self
+            .dep_name_repository
+            .get_many_by_problem_id(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        let recorded_metadata = self
+            .dep_name_repository
+            .get_metadata_by_problem_id(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        let dep_metadata = job_metadata::with_legacy_fallback(&dep_names, recorded_metadata);
+
+        let mut judge_stream = self.judge_service.judge_stream(JudgeRequest {
+            judge_id: submission_id.into(),
+            procedure,
+            runtime_texts,
+        });
+
+        let mut summary = JudgeSummary::default();
+        let mut testcase_count = 0;
+
+        while let Some(item) = judge_stream.next().await {
+            let (dep_id, report) = match item {
+                Ok(item) => item,
+                Err(e) => {
+                    self.submission_repository
+                        .update_submission(
+                            submission_id,
+                            UpdateSubmission {
+                                total_score: 0,
+                                max_time_ms: 0,
+                                max_memory_kib: 0,
+                                judge_status: JudgeStatus::IE,
+                                compile_log: summary.compile_log.clone(),
+                            },
+                        )
+                        .await
+                        .map_err(UsecaseError::internal_server_error_map())?;
+                    return Err(UsecaseError::internal_server_error(e));
+                }
+            };
+
+            let dep_name = dep_names.get(&dep_id).cloned().unwrap_or_default();
+            let metadata = dep_metadata.get(&dep_id).cloned().unwrap_or_default();
+
+            // 隠された実行も含めて、実際に動いた実行はすべて経過を残す
+            // 取り消しや途中のエラーで終わった judge こそ経過を見たいので、届いた順にすぐ書き込む
+            if let Some(execution) = &report.metadata {
+                let entry = CreateExecutionTimelineEntry {
+                    submission_id,
+                    dep_id: dep_id.into(),
+                    name: dep_name,
+                    judge_status: timeline_status(&report.result),
+                    ready_at_ms: execution.ready_at_ms as i64,
+                    started_at_ms: execution.started_at_ms.map(|ms| ms as i64),
+                    finished_at_ms: execution.finished_at_ms as i64,
+                    host: execution.host.clone(),
+                    retry_count: execution.retry_count as i32,
+                    outcome_size_bytes: execution.outcome_size_bytes.map(|size| size as i64),
+                    cached: execution.cached,
+                };
+                self.submission_repository
+                    .create_execution_timeline(vec![entry])
+                    .await
+                    .map_err(UsecaseError::internal_server_error_map())?;
+            }
+
+            let (judge_status, details) = match report.result {
+                ExecutionJobResult::ExecutionResult(ExecutionResult::Displayable(res)) => {
+                    (res.status.clone().into(), Some(res))
+                }
+                ExecutionJobResult::ExecutionResult(ExecutionResult::Hidden(_res)) => {
+                    // todo
+                    continue;
+                }
+                ExecutionJobResult::ExecutionResult(ExecutionResult::Batched(res)) => {
+                    // まとめて実行したテストケースの結果をそれぞれ書き込む
+                    let judge_results = res
+                        .results
+                        .iter()
+                        .enumerate()
+                        .map(|(i, sub)| {
+                            judge_result(
                                 submission_id,
-                                testcase_id,
-                                testcase_name,
-                                judge_status: format!("{:?}", res.status),
-                                score: res.score,
-                                time_ms: res.time as i32,
-                                memory_kib: res.memory as i32,
-                            });
-                        }
-                        if testcase_name == judge_core::constant::job_name::SUMMARY_PHASE {
-                            total_score = res.score;
-                            max_time_ms = res.time as i32;
-                            max_memory_kib = res.memory as i32;
-                            overall_status = format!("{:?}", res.status);
-                        }
-                        if testcase_name == judge_core::constant::job_name::COMPILE_PHASE
-                            && overall_status == "IE"
-                        {
-                            overall_status = format!("{:?}", res.status);
-                        }
-                    }
-                    ExecutionResult::Hidden(_res) => {
-                        // todo
-                    }
-                },
-                ExecutionJobResult::EarlyExit => {}
+                                &name_to_id,
+                                sub.sub_name.clone(),
+                                sub.result.status.clone().into(),
+                                Some(&sub.result),
+                                display_order(&metadata, i),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    testcase_count += judge_results.len();
+                    self.submission_repository
+                        .create_judge_results(judge_results)
+                        .await
+                        .map_err(UsecaseError::internal_server_error_map())?;
+                    continue;
+                }
+                ExecutionJobResult::Failed { reason } => {
+                    // 実行自体に失敗したものは IE として残し、他の結果はそのまま書き込む
+                    tracing::warn!(%submission_id, problem_id, %dep_id, %reason, "execution failed");
+                    (JudgeStatus::IE, None)
+                }
+                ExecutionJobResult::EarlyExit
+                | ExecutionJobResult::Skipped
+                | ExecutionJobResult::ConditionUnmet => {
+                    // 打ち切りや条件不成立で動かなかったテストケースも SKIP として見せる
+                    (JudgeStatus::SKIP, None)
+                }
+                ExecutionJobResult::Cancelled => {
+                    // 再ジャッジなどで打ち切られた場合はこれ以上書き込まない
+                    tracing::info!(%submission_id, problem_id, "judge cancelled");
+                    return Ok(());
+                }
+            };
+
+            let judge_results =
+                summary.record(submission_id, &name_to_id, metadata, judge_status, details);
+            if !judge_results.is_empty() {
+                testcase_count += judge_results.len();
+                self.submission_repository
+                    .create_judge_results(judge_results)
+                    .await
+                    .map_err(UsecaseError::internal_server_error_map())?;
             }
         }
 
-        let testcase_count = testcase_results.len();
-        let overall_status_str = overall_status.clone();
-
+        let overall_status = summary.overall_status();
         self.submission_repository
             .update_submission(
                 submission_id,
                 UpdateSubmission {
-                    total_score,
-                    max_time_ms,
-                    max_memory_kib,
+                    total_score: summary.total_score,
+                    max_time_ms: summary.max_time_ms,
+                    max_memory_kib: summary.max_memory_kib,
                     judge_status: overall_status,
+                    compile_log: summary.compile_log,
                 },
             )
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
         self.submission_repository
-            .create_judge_results(testcase_results)
+            .create_group_judge_results(summary.group_results)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
@@ -665,13 +837,284 @@ impl<
             %submission_id,
             problem_id,
             testcase_count,
-            total_score,
-            max_time_ms,
-            max_memory_kib,
-            overall_status = %overall_status_str,
+            total_score = summary.total_score,
+            max_time_ms = summary.max_time_ms,
+            max_memory_kib = summary.max_memory_kib,
+            overall_status = %overall_status,
             "judge finished"
         );
 
         Ok(())
     }
 }
+
+/// What the reports of a judge add up to, gathered by the role of each execution
+#[derive(Default)]
+struct JudgeSummary {
+    total_score: i64,
+    max_time_ms: i32,
+    max_memory_kib: i32,
+    summary_status: Option<JudgeStatus>,
+    compile_status: Option<JudgeStatus>,
+    /// Whether a checker or interactor failed to build
+    tool_compile_failed: bool,
+    compile_log: Option<String>,
+    group_results: Vec<CreateGroupJudgeResult>,
+}
+
+impl JudgeSummary {
+    /// Records the result of an execution by its role, returning the testcase results it judged
+    fn record(
+        &mut self,
+        submission_id: Uuid,
+        name_to_id: &HashMap<String, Uuid>,
+        metadata: job_metadata::JobMetadata,
+        judge_status: JudgeStatus,
+        details: Option<DisplayableExecutionResult>,
+    ) -> Vec<CreateJudgeResult> {
+        let score = details.as_ref().map(|res| res.score).unwrap_or(0);
+        match metadata.role {
+            JobRole::Test => {
+                // 終わったテストケースから順に書き込んで途中経過を見せる
+                // 結果の出なかったまとめ実行は、含まれるテストケースすべてに同じ状態を書き込む
+                return metadata
+                    .testcases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| {
+                        judge_result(
+                            submission_id,
+                            name_to_id,
+                            name.clone(),
+                            judge_status,
+                            details.as_ref(),
+                            display_order(&metadata, i),
+                        )
+                    })
+                    .collect();
+            }
+            JobRole::GroupSummary => {
+                if let Some(group_name) = metadata.group {
+                    self.group_results.push(CreateGroupJudgeResult {
+                        submission_id,
+                        group_name,
+                        judge_status,
+                        score,
+                    });
+                }
+            }
+            JobRole::Summary => {
+                if judge_status != JudgeStatus::SKIP {
+                    self.total_score = score;
+                    self.max_time_ms = details.as_ref().map(|res| res.time as i32).unwrap_or(0);
+                    self.max_memory_kib =
+                        details.as_ref().map(|res| res.memory as i32).unwrap_or(0);
+                    self.summary_status = Some(judge_status);
+                }
+            }
+            JobRole::Compile => {
+                self.compile_status = Some(judge_status);
+                self.compile_log = details.and_then(|res| res.compile_log);
+            }
+            JobRole::ToolCompile => {
+                // 結果は完了順に届くので、提出側の compile より先に来ても状態は決めない
+                self.tool_compile_failed |= judge_status != JudgeStatus::AC;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn overall_status(&self) -> JudgeStatus {
+        overall_status(
+            self.summary_status,
+            self.compile_status,
+            self.tool_compile_failed,
+        )
+    }
+}
+
+/// Verdict of the whole submission, where a checker or interactor that fails to build is an internal error
+/// unless the submission itself failed to compile
+fn overall_status(
+    summary_status: Option<JudgeStatus>,
+    compile_status: Option<JudgeStatus>,
+    tool_compile_failed: bool,
+) -> JudgeStatus {
+    // 提出のコンパイルに失敗すると summary phase は動かず、提出者はコンパイルログを見るべき
+    if let Some(status) = compile_status.filter(|status| *status != JudgeStatus::AC) {
+        return status;
+    }
+    if tool_compile_failed {
+        return JudgeStatus::IE;
+    }
+    summary_status.unwrap_or(JudgeStatus::IE)
+}
+
+fn judge_result(
+    submission_id: Uuid,
+    name_to_id: &HashMap<String, Uuid>,
+    testcase_name: String,
+    judge_status: JudgeStatus,
+    details: Option<&DisplayableExecutionResult>,
+    display_order: Option<i32>,
+) -> CreateJudgeResult {
+    CreateJudgeResult {
+        submission_id,
+        testcase_id: name_to_id.get(&testcase_name).cloned().unwrap_or_default(),
+        testcase_name,
+        judge_status,
+        score: details.map(|res| res.score).unwrap_or(0),
+        time_ms: details.map(|res| res.time as i32).unwrap_or(0),
+        memory_kib: details.map(|res| res.memory as i32).unwrap_or(0),
+        message: details.and_then(|res| res.message.clone()),
+        stderr: details.and_then(|res| res.stderr.clone()),
+        signal: details.and_then(|res| res.signal),
+        cpu_time_ms: details.and_then(|res| res.cpu_time).map(|ms| ms as i32),
+        wall_time_ms: details.and_then(|res| res.wall_time).map(|ms| ms as i32),
+        display_order,
+    }
+}
+
+/// Display order of the `index`-th testcase judged by an execution
+fn display_order(metadata: &job_metadata::JobMetadata, index: usize) -> Option<i32> {
+    metadata
+        .display_order
+        .map(|order| order as i32 + index as i32)
+}
+
+fn timeline_status(result: &ExecutionJobResult) -> String {
+    match result {
+        ExecutionJobResult::ExecutionResult(ExecutionResult::Displayable(res)) => {
+            format!("{:?}", res.status)
+        }
+        ExecutionJobResult::ExecutionResult(ExecutionResult::Hidden(_)) => "Hidden".to_string(),
+        ExecutionJobResult::ExecutionResult(ExecutionResult::Batched(_)) => "Batched".to_string(),
+        ExecutionJobResult::Failed { .. } => "IE".to_string(),
+        ExecutionJobResult::EarlyExit => "EarlyExit".to_string(),
+        ExecutionJobResult::Cancelled => "Cancelled".to_string(),
+        ExecutionJobResult::Skipped => "Skipped".to_string(),
+        ExecutionJobResult::ConditionUnmet => "ConditionUnmet".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use judge_core::model::{judge_output, procedure::job_metadata::JobMetadata};
+
+    fn displayable(status: judge_output::JudgeStatus, score: i64) -> DisplayableExecutionResult {
+        DisplayableExecutionResult {
+            status,
+            time: 10.0,
+            memory: 1024.0,
+            score,
+            message: None,
+            continue_status: judge_output::ContinueStatus::Continue,
+            compile_log: None,
+            stderr: None,
+            signal: None,
+            cpu_time: None,
+            wall_time: None,
+        }
+    }
+
+    #[test]
+    fn overall_status_ignores_the_order_of_compile_results() {
+        use JudgeStatus::*;
+
+        // checker が先に AC で終わっても、提出の CE が全体の状態になる
+        assert_eq!(overall_status(None, Some(CE), false), CE);
+        assert_eq!(overall_status(Some(WA), Some(AC), false), WA);
+        // checker や interactor のコンパイル失敗は提出者のせいではない
+        assert_eq!(overall_status(None, Some(AC), true), IE);
+        // ただし提出自体がコンパイルできなければ CE を見せる
+        assert_eq!(overall_status(None, Some(CE), true), CE);
+        assert_eq!(overall_status(None, Some(AC), false), IE);
+        assert_eq!(overall_status(None, None, false), IE);
+    }
+
+    #[test]
+    fn judge_summary_routes_results_by_job_role() {
+        let submission_id = Uuid::new_v4();
+        let testcase_id = Uuid::new_v4();
+        let name_to_id = HashMap::from([("a".to_string(), testcase_id)]);
+        let mut summary = JudgeSummary::default();
+        let mut record = |metadata: JobMetadata, status, details| {
+            summary.record(submission_id, &name_to_id, metadata, status, details)
+        };
+
+        assert!(
+            record(
+                JobMetadata::new(JobRole::ToolCompile),
+                JudgeStatus::AC,
+                None
+            )
+            .is_empty()
+        );
+        let mut compile = displayable(judge_output::JudgeStatus::AC, 0);
+        compile.compile_log = Some("ok".to_string());
+        record(
+            JobMetadata::new(JobRole::Compile),
+            JudgeStatus::AC,
+            Some(compile),
+        );
+        // 結果の出なかったまとめ実行は、含まれるテストケースすべてに同じ状態が付く
+        let results = record(
+            JobMetadata::new(JobRole::Test)
+                .with_testcases(vec!["a".to_string(), "b".to_string()])
+                .with_display_order(3),
+            JudgeStatus::IE,
+            None,
+        );
+        let results = results
+            .iter()
+            .map(|res| {
+                (
+                    res.testcase_name.as_str(),
+                    res.testcase_id,
+                    res.judge_status,
+                    res.display_order,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                ("a", testcase_id, JudgeStatus::IE, Some(3)),
+                ("b", Uuid::nil(), JudgeStatus::IE, Some(4)),
+            ]
+        );
+        record(
+            JobMetadata::new(JobRole::GroupSummary).with_group(Some("g".to_string())),
+            JudgeStatus::PA,
+            Some(displayable(judge_output::JudgeStatus::PA, 30)),
+        );
+        // 打ち切られた summary phase は結果を上書きしない
+        record(
+            JobMetadata::new(JobRole::Summary),
+            JudgeStatus::PA,
+            Some(displayable(judge_output::JudgeStatus::PA, 30)),
+        );
+        record(JobMetadata::new(JobRole::Summary), JudgeStatus::SKIP, None);
+        assert!(
+            record(
+                JobMetadata::new(JobRole::Custom),
+                JudgeStatus::WA,
+                Some(displayable(judge_output::JudgeStatus::WA, 0)),
+            )
+            .is_empty()
+        );
+
+        assert_eq!(summary.overall_status(), JudgeStatus::PA);
+        assert_eq!(summary.total_score, 30);
+        assert_eq!(summary.max_time_ms, 10);
+        assert_eq!(summary.compile_log.as_deref(), Some("ok"));
+        let groups = summary
+            .group_results
+            .iter()
+            .map(|res| (res.group_name.as_str(), res.judge_status, res.score))
+            .collect::<Vec<_>>();
+        assert_eq!(groups, vec![("g", JudgeStatus::PA, 30)]);
+    }
+}
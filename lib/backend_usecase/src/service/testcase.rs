@@ -1,26 +1,38 @@
 use domain::{
-    model::testcase::CreateTestcase,
+    model::testcase::{CreateTestcase, TestcaseGroup, TestcaseSummary},
     repository::{
         problem::ProblemRepository, procedure::ProcedureRepository, session::SessionRepository,
         testcase::TestcaseRepository,
     },
 };
 use judge_core::{
-    constant::job_name::v0_features::{testcase_expected_name, testcase_input_name},
     logic::{
-        problem_presets::normal_judge::{NormalJudgeTestcase, create_normal_judge_procedure},
+        problem_presets::{
+            grouped_judge::{
+                GroupScoringPolicy, TestcaseGroup as JudgeTestcaseGroup,
+                create_grouped_judge_procedure_with_checker,
+            },
+            normal_judge::{NormalJudgeTestcase, create_batched_normal_judge_procedure},
+        },
+        registered_procedure_patcher::{PatchError, TestcasePatch, patch, preset_checker_text},
         writer_schema_registerer::register,
     },
     model::{
+        builtin_checker::BuiltinChecker,
         dep_name_repository::DepNameRepository,
+        identifiers::ResourceId,
         problem_registry::{ProblemRegistryClient, ProblemRegistryServer},
+        procedure::{job_metadata, writer_schema::Procedure},
     },
 };
 use uuid::Uuid;
 
 use crate::model::{
     error::UsecaseError,
-    testcase::{CreateTestcaseData, TestcaseDto, TestcaseSummaryDto, UpdateTestcaseData},
+    testcase::{
+        CreateTestcaseData, TestcaseDto, TestcaseGroupDto, TestcaseSummaryDto, UpdateTestcaseData,
+        UpdateTestcaseGroupData,
+    },
 };
 
 #[derive(Clone)]
@@ -165,18 +177,19 @@ impl<
 
         let input = self
             .problem_registry_client
-            .fetch(testcase.input_id.into())
+            .fetch_text(testcase.input_id.into())
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
         let output = self
             .problem_registry_client
-            .fetch(testcase.output_id.into())
+            .fetch_text(testcase.output_id.into())
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
         let testcase = TestcaseDto {
             id: testcase.id,
             name: testcase.name,
+            group: testcase.group_name,
             input,
             output,
             created_at: testcase.created_at,
@@ -240,164 +253,122 @@ impl<
             }
         }
 
-        // procedureを作成し、保存
-        let mut new_testcases = Vec::new();
-        for testcase in now_testcases.iter() {
-            let input = self
-                .problem_registry_client
-                .fetch(testcase.input_id.into())
-                .await
-                .map_err(UsecaseError::internal_server_error_map())?;
-
-            let output = self
-                .problem_registry_client
-                .fetch(testcase.output_id.into())
-                .await
-                .map_err(UsecaseError::internal_server_error_map())?;
-
-            new_testcases.push(NormalJudgeTestcase {
-                name: testcase.name.clone(),
-                input,
-                expected_output: output,
-            });
-        }
-        for testcase in testcases.iter() {
-            new_testcases.push(NormalJudgeTestcase {
-                name: testcase.name.clone(),
-                input: testcase.input.clone(),
-                expected_output: testcase.output.clone(),
-            });
+        // procedureに新しいtestcaseの部分だけを追加する
+        let new_testcases: Vec<(Uuid, NormalJudgeTestcase)> = testcases
+            .into_iter()
+            .map(|testcase| {
+                (
+                    Uuid::now_v7(),
+                    NormalJudgeTestcase {
+                        name: testcase.name,
+                        input: testcase.input,
+                        expected_output: testcase.output,
+                        group: testcase.group,
+                    },
+                )
+            })
+            .collect();
+        let testcase_patch = TestcasePatch {
+            removed: Vec::new(),
+            added: new_testcases
+                .iter()
+                .map(|(_, testcase)| testcase.clone())
+                .collect(),
+        };
+        if !self.patch_procedure(problem_id, testcase_patch).await? {
+            let checker = self.stored_checker(problem_id).await?;
+            let mut testcases = self.fetch_testcases(&now_testcases).await?;
+            testcases.extend(new_testcases);
+            return self.rebuild_procedure(problem_id, testcases, checker).await;
         }
 
-        let procedure = create_normal_judge_procedure(new_testcases)
-            .map_err(UsecaseError::internal_server_error_map())?;
-
-        self.dep_name_repository
-            .remove_many(problem_id)
+        self.testcase_repository
+            .create_testcases(
+                new_testcases
+                    .iter()
+                    .map(|(id, testcase)| testcase_row(*id, problem_id, testcase))
+                    .collect(),
+            )
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
-        let registered_procedure = register(
-            procedure,
-            self.problem_registry_server.clone(),
-            self.dep_name_repository.clone(),
-            problem_id,
-        )
-        .await
-        .map_err(UsecaseError::internal_server_error_map())?;
 
-        let dep_id_to_resource_id = {
-            let mut dep_id_to_resource_id = std::collections::HashMap::new();
-            for text in registered_procedure.texts.iter() {
-                dep_id_to_resource_id.insert(text.dep_id, text.resource_id);
-            }
-            dep_id_to_resource_id
-        };
+        Ok(())
+    }
 
-        self.procedure_repository
-            .update_procedure(problem_id, registered_procedure)
+    pub async fn delete_testcase(
+        &self,
+        session_id: Option<&str>,
+        testcase_id: String,
+    ) -> Result<(), UsecaseError> {
+        let testcase_id = Uuid::parse_str(&testcase_id).map_err(|_| UsecaseError::ValidateError)?;
+
+        let testcase = self
+            .testcase_repository
+            .get_testcase(testcase_id)
             .await
-            .map_err(UsecaseError::internal_server_error_map())?;
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)?;
 
-        // データベースに保存するためのtestcasesを作成
-        let name_to_id = {
-            let id_to_name = self
-                .dep_name_repository
-                .get_many_by_problem_id(problem_id)
+        let problem = self
+            .problem_repository
+            .get_problem(testcase.problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)?;
+
+        let user_id = match session_id {
+            Some(session_id) => self
+                .session_repository
+                .get_display_id_by_session_id(session_id)
                 .await
-                .map_err(UsecaseError::internal_server_error_map())?;
-            let mut name_to_id = std::collections::HashMap::new();
-            for (id, name) in id_to_name {
-                name_to_id.insert(name, id);
-            }
-            name_to_id
+                .map_err(UsecaseError::internal_server_error_map())?,
+            None => None,
         };
 
-        let mut new_testcases: Vec<CreateTestcase> = Vec::new();
-        for testcase in now_testcases.iter() {
-            let input_id = name_to_id
-                .get(testcase_input_name(&testcase.name).as_str())
-                .ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg("missing dep name for testcase input")
-                })?;
-            let input_id = dep_id_to_resource_id.get(input_id).ok_or_else(|| {
-                UsecaseError::internal_server_error_msg(
-                    "missing resource id for testcase input dep",
-                )
-            })?;
-
-            let output_id = name_to_id
-                .get(testcase_expected_name(&testcase.name).as_str())
-                .ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing dep name for testcase expected output",
-                    )
-                })?;
-            let output_id = dep_id_to_resource_id.get(output_id).ok_or_else(|| {
-                UsecaseError::internal_server_error_msg(
-                    "missing resource id for testcase expected output dep",
-                )
-            })?;
+        if !problem.is_public && user_id.is_none_or(|x| x != problem.author_id) {
+            return Err(UsecaseError::NotFound);
+        }
 
-            new_testcases.push(CreateTestcase {
-                id: testcase.id,
-                problem_id,
-                name: testcase.name.clone(),
-                input_id: input_id.to_owned().into(),
-                output_id: output_id.to_owned().into(),
-            });
+        if user_id.is_none_or(|x| x != problem.author_id) {
+            return Err(UsecaseError::Forbidden);
         }
-        for testcase in testcases {
-            let input_id = name_to_id
-                .get(testcase_input_name(&testcase.name).as_str())
-                .ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing dep name for testcase input (new)",
-                    )
-                })?;
-            let input_id = dep_id_to_resource_id.get(input_id).ok_or_else(|| {
-                UsecaseError::internal_server_error_msg(
-                    "missing resource id for testcase input dep (new)",
-                )
-            })?;
-            let output_id = name_to_id
-                .get(testcase_expected_name(&testcase.name).as_str())
-                .ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing dep name for testcase expected output (new)",
-                    )
-                })?;
-            let output_id = dep_id_to_resource_id.get(output_id).ok_or_else(|| {
-                UsecaseError::internal_server_error_msg(
-                    "missing resource id for testcase expected output dep (new)",
-                )
-            })?;
 
-            new_testcases.push(CreateTestcase {
-                id: Uuid::now_v7(),
-                problem_id,
-                name: testcase.name,
-                input_id: input_id.to_owned().into(),
-                output_id: output_id.to_owned().into(),
-            });
+        // procedureから該当testcaseの部分だけを取り除く
+        let testcase_patch = TestcasePatch {
+            removed: vec![testcase.name],
+            added: Vec::new(),
+        };
+        if !self.patch_procedure(problem.id, testcase_patch).await? {
+            let checker = self.stored_checker(problem.id).await?;
+            let now_testcases = self
+                .testcase_repository
+                .get_testcases(problem.id)
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?;
+            let testcases = self
+                .fetch_testcases(
+                    &now_testcases
+                        .into_iter()
+                        .filter(|testcase| testcase.id != testcase_id)
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+            return self.rebuild_procedure(problem.id, testcases, checker).await;
         }
 
-        // testcasesの更新
-        self.testcase_repository
-            .delete_testcases(problem_id)
-            .await
-            .map_err(UsecaseError::internal_server_error_map())?;
         self.testcase_repository
-            .create_testcases(new_testcases)
+            .delete_testcase(testcase_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
         Ok(())
     }
 
-    pub async fn delete_testcase(
+    pub async fn put_testcase(
         &self,
         session_id: Option<&str>,
         testcase_id: String,
+        put_testcase: UpdateTestcaseData,
     ) -> Result<(), UsecaseError> {
         let testcase_id = Uuid::parse_str(&testcase_id).map_err(|_| UsecaseError::ValidateError)?;
 
@@ -438,141 +409,99 @@ impl<
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
-        let mut new_testcases = Vec::new();
+        // 自身を除いたtestcasesの名前が一致するか判定
         for testcase in now_testcases.iter() {
-            if testcase.id != testcase_id {
-                let input = self
-                    .problem_registry_client
-                    .fetch(testcase.input_id.into())
-                    .await
-                    .map_err(UsecaseError::internal_server_error_map())?;
-
-                let output = self
-                    .problem_registry_client
-                    .fetch(testcase.output_id.into())
-                    .await
-                    .map_err(UsecaseError::internal_server_error_map())?;
-
-                new_testcases.push(NormalJudgeTestcase {
-                    name: testcase.name.clone(),
-                    input,
-                    expected_output: output,
-                });
+            if testcase.id != testcase_id && put_testcase.name == testcase.name {
+                return Err(UsecaseError::ValidateError);
             }
         }
 
-        let procedure = create_normal_judge_procedure(new_testcases)
-            .map_err(UsecaseError::internal_server_error_map())?;
+        // procedureのうち該当testcaseの部分だけを差し替える
+        let edited = NormalJudgeTestcase {
+            name: put_testcase.name,
+            input: put_testcase.input,
+            expected_output: put_testcase.output,
+            group: put_testcase.group,
+        };
+        let testcase_patch = TestcasePatch {
+            removed: vec![testcase.name],
+            added: vec![edited.clone()],
+        };
+        if !self.patch_procedure(problem.id, testcase_patch).await? {
+            let checker = self.stored_checker(problem.id).await?;
+            let mut testcases = self
+                .fetch_testcases(
+                    &now_testcases
+                        .into_iter()
+                        .filter(|testcase| testcase.id != testcase_id)
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+            testcases.push((testcase_id, edited));
+            return self.rebuild_procedure(problem.id, testcases, checker).await;
+        }
 
-        self.dep_name_repository
-            .remove_many(problem.id)
+        self.testcase_repository
+            .update_testcase(testcase_row(testcase_id, problem.id, &edited))
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
-        let registered_procedure = register(
-            procedure,
-            self.problem_registry_server.clone(),
-            self.dep_name_repository.clone(),
-            problem.id,
-        )
-        .await
-        .map_err(UsecaseError::internal_server_error_map())?;
 
-        let dep_id_to_resource_id = {
-            let mut dep_id_to_resource_id = std::collections::HashMap::new();
-            for text in registered_procedure.texts.iter() {
-                dep_id_to_resource_id.insert(text.dep_id, text.resource_id);
-            }
-            dep_id_to_resource_id
-        };
+        Ok(())
+    }
 
-        self.procedure_repository
-            .update_procedure(problem.id, registered_procedure)
+    pub async fn get_testcase_groups(
+        &self,
+        session_id: Option<&str>,
+        problem_id: String,
+    ) -> Result<Vec<TestcaseGroupDto>, UsecaseError> {
+        let problem_id = problem_id
+            .parse::<i64>()
+            .map_err(|_| UsecaseError::ValidateError)?;
+
+        let problem = self
+            .problem_repository
+            .get_problem(problem_id)
             .await
-            .map_err(UsecaseError::internal_server_error_map())?;
+            .map_err(UsecaseError::internal_server_error_map())?
+            .ok_or(UsecaseError::NotFound)?;
+
+        if !problem.is_public {
+            let session_id = session_id.ok_or(UsecaseError::NotFound)?;
 
-        // データベースに保存するためのtestcasesを作成
-        let name_to_id = {
-            let id_to_name = self
-                .dep_name_repository
-                .get_many_by_problem_id(problem.id)
+            let user_id = self
+                .session_repository
+                .get_display_id_by_session_id(session_id)
                 .await
-                .map_err(UsecaseError::internal_server_error_map())?;
-            let mut name_to_id = std::collections::HashMap::new();
-            for (id, name) in id_to_name {
-                name_to_id.insert(name, id);
-            }
-            name_to_id
-        };
-        let mut new_testcases: Vec<CreateTestcase> = Vec::new();
-        for testcase in now_testcases.iter() {
-            if testcase.id != testcase_id {
-                let input_id = name_to_id
-                    .get(testcase_input_name(&testcase.name).as_str())
-                    .ok_or_else(|| {
-                        UsecaseError::internal_server_error_msg(
-                            "missing dep name for testcase input (existing)",
-                        )
-                    })?;
-                let input_id = dep_id_to_resource_id.get(input_id).ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing resource id for testcase input dep (existing)",
-                    )
-                })?;
-
-                let output_id = name_to_id
-                    .get(testcase_expected_name(&testcase.name).as_str())
-                    .ok_or_else(|| {
-                        UsecaseError::internal_server_error_msg(
-                            "missing dep name for testcase expected output (existing)",
-                        )
-                    })?;
-                let output_id = dep_id_to_resource_id.get(output_id).ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing resource id for testcase expected output dep (existing)",
-                    )
-                })?;
-
-                new_testcases.push(CreateTestcase {
-                    id: testcase.id,
-                    problem_id: problem.id,
-                    name: testcase.name.clone(),
-                    input_id: input_id.to_owned().into(),
-                    output_id: output_id.to_owned().into(),
-                });
+                .map_err(UsecaseError::internal_server_error_map())?
+                .ok_or(UsecaseError::NotFound)?;
+
+            if problem.author_id != user_id {
+                return Err(UsecaseError::NotFound);
             }
         }
 
-        // testcasesの更新
-        self.testcase_repository
-            .delete_testcases(problem.id)
-            .await
-            .map_err(UsecaseError::internal_server_error_map())?;
-        self.testcase_repository
-            .create_testcases(new_testcases)
+        let groups = self
+            .testcase_repository
+            .get_testcase_groups(problem_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
-        Ok(())
+        Ok(groups.into_iter().map(|x| x.into()).collect())
     }
 
-    pub async fn put_testcase(
+    pub async fn put_testcase_groups(
         &self,
         session_id: Option<&str>,
-        testcase_id: String,
-        put_testcase: UpdateTestcaseData,
+        problem_id: String,
+        groups: Vec<UpdateTestcaseGroupData>,
     ) -> Result<(), UsecaseError> {
-        let testcase_id = Uuid::parse_str(&testcase_id).map_err(|_| UsecaseError::ValidateError)?;
-
-        let testcase = self
-            .testcase_repository
-            .get_testcase(testcase_id)
-            .await
-            .map_err(UsecaseError::internal_server_error_map())?
-            .ok_or(UsecaseError::NotFound)?;
+        let problem_id = problem_id
+            .parse::<i64>()
+            .map_err(|_| UsecaseError::ValidateError)?;
 
         let problem = self
             .problem_repository
-            .get_problem(testcase.problem_id)
+            .get_problem(problem_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?
             .ok_or(UsecaseError::NotFound)?;
@@ -596,172 +525,300 @@ impl<
 
         let now_testcases = self
             .testcase_repository
-            .get_testcases(problem.id)
+            .get_testcases(problem_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
-        // 自身を除いたtestcasesの名前が一致するか判定
-        for testcase in now_testcases.iter() {
-            if testcase.id != testcase_id && put_testcase.name == testcase.name {
+        // 書き込む前に、グループ名の重複と配点・採点方式、既存のtestcaseの所属を確認
+        {
+            let mut name_set = std::collections::HashSet::new();
+            for group in groups.iter() {
+                if group.point < 0 || group.policy.parse::<GroupScoringPolicy>().is_err() {
+                    return Err(UsecaseError::ValidateError);
+                }
+                if !name_set.insert(group.name.clone()) {
+                    return Err(UsecaseError::ValidateError);
+                }
+            }
+            if !groups.is_empty()
+                && now_testcases.iter().any(|testcase| {
+                    testcase
+                        .group_name
+                        .as_ref()
+                        .is_none_or(|group| !name_set.contains(group))
+                })
+            {
+                return Err(UsecaseError::ValidateError);
+            }
+            // testcaseのないグループは採点できない
+            if groups.iter().any(|group| {
+                now_testcases
+                    .iter()
+                    .all(|testcase| testcase.group_name.as_ref() != Some(&group.name))
+            }) {
                 return Err(UsecaseError::ValidateError);
             }
         }
+        let checker = self.stored_checker(problem_id).await?;
 
-        // procedureを作成し、保存
-        let mut new_testcases = Vec::new();
-        for testcase in now_testcases.iter() {
-            if testcase.id != testcase_id {
-                let input = self
-                    .problem_registry_client
-                    .fetch(testcase.input_id.into())
-                    .await
-                    .map_err(UsecaseError::internal_server_error_map())?;
-
-                let output = self
-                    .problem_registry_client
-                    .fetch(testcase.output_id.into())
-                    .await
-                    .map_err(UsecaseError::internal_server_error_map())?;
-
-                new_testcases.push(NormalJudgeTestcase {
+        let groups = groups
+            .into_iter()
+            .map(|group| TestcaseGroup {
+                problem_id,
+                name: group.name,
+                point: group.point,
+                policy: group.policy,
+            })
+            .collect::<Vec<_>>();
+
+        // procedureを作成し直せたときだけグループを保存し、登録済みのprocedureと食い違わないようにする
+        let testcases = self.fetch_testcases(&now_testcases).await?;
+        self.rebuild_procedure_with_groups(problem_id, testcases, checker, &groups)
+            .await?;
+        self.testcase_repository
+            .replace_testcase_groups(problem_id, groups)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())
+    }
+
+    /// Applies `testcase_patch` to the stored procedure.
+    /// Returns `false` without touching anything when the procedure has to be rebuilt instead.
+    async fn patch_procedure(
+        &self,
+        problem_id: i64,
+        testcase_patch: TestcasePatch,
+    ) -> Result<bool, UsecaseError> {
+        let Some(mut procedure) = self
+            .procedure_repository
+            .get_procedure(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+        else {
+            return Ok(false);
+        };
+
+        match patch(
+            &mut procedure,
+            testcase_patch,
+            self.problem_registry_server.clone(),
+            self.dep_name_repository.clone(),
+            problem_id,
+        )
+        .await
+        {
+            Ok(_) => {}
+            Err(PatchError::MissingTemplate | PatchError::BatchedTestcases) => return Ok(false),
+            Err(
+                PatchError::DuplicateName(_)
+                | PatchError::MissingAggregator(_)
+                | PatchError::EmptyGroup(_),
+            ) => {
+                return Err(UsecaseError::ValidateError);
+            }
+            Err(e) => return Err(UsecaseError::internal_server_error(e)),
+        }
+
+        self.procedure_repository
+            .update_procedure(problem_id, procedure)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        Ok(true)
+    }
+
+    /// Built-in checker to build the procedure again with.
+    /// Procedures not built by the normal or grouped preset are refused, as their checker would be lost.
+    async fn stored_checker(&self, problem_id: i64) -> Result<BuiltinChecker, UsecaseError> {
+        let Some(procedure) = self
+            .procedure_repository
+            .get_procedure(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?
+        else {
+            return Ok(BuiltinChecker::Exact);
+        };
+        let dep_names = self
+            .dep_name_repository
+            .get_many_by_problem_id(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        let recorded_metadata = self
+            .dep_name_repository
+            .get_metadata_by_problem_id(problem_id)
+            .await
+            .map_err(UsecaseError::internal_server_error_map())?;
+        let dep_metadata = job_metadata::with_legacy_fallback(&dep_names, recorded_metadata);
+
+        match preset_checker_text(&procedure, &dep_names, &dep_metadata) {
+            Ok(Some(resource_id)) => self
+                .problem_registry_client
+                .fetch_text(resource_id)
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?
+                .parse::<BuiltinChecker>()
+                .map_err(UsecaseError::internal_server_error_map()),
+            Ok(None) => Ok(BuiltinChecker::Exact),
+            Err(_) => Err(UsecaseError::ValidateError),
+        }
+    }
+
+    async fn fetch_testcases(
+        &self,
+        testcases: &[TestcaseSummary],
+    ) -> Result<Vec<(Uuid, NormalJudgeTestcase)>, UsecaseError> {
+        let mut fetched = Vec::new();
+        for testcase in testcases.iter() {
+            let input = self
+                .problem_registry_client
+                .fetch_text(testcase.input_id.into())
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?;
+
+            let output = self
+                .problem_registry_client
+                .fetch_text(testcase.output_id.into())
+                .await
+                .map_err(UsecaseError::internal_server_error_map())?;
+
+            fetched.push((
+                testcase.id,
+                NormalJudgeTestcase {
                     name: testcase.name.clone(),
                     input,
                     expected_output: output,
-                });
-            } else {
-                new_testcases.push(NormalJudgeTestcase {
-                    name: put_testcase.name.clone(),
-                    input: put_testcase.input.clone(),
-                    expected_output: put_testcase.output.clone(),
-                });
-            }
+                    group: testcase.group_name.clone(),
+                },
+            ));
         }
+        Ok(fetched)
+    }
 
-        let procedure = create_normal_judge_procedure(new_testcases)
+    /// Registers a new procedure built from `testcases` and the stored groups, and replaces every testcase row
+    async fn rebuild_procedure(
+        &self,
+        problem_id: i64,
+        testcases: Vec<(Uuid, NormalJudgeTestcase)>,
+        checker: BuiltinChecker,
+    ) -> Result<(), UsecaseError> {
+        let groups = self
+            .testcase_repository
+            .get_testcase_groups(problem_id)
+            .await
             .map_err(UsecaseError::internal_server_error_map())?;
+        self.rebuild_procedure_with_groups(problem_id, testcases, checker, &groups)
+            .await
+    }
+
+    /// Same as `rebuild_procedure`, but with `groups` in place of the stored ones
+    async fn rebuild_procedure_with_groups(
+        &self,
+        problem_id: i64,
+        testcases: Vec<(Uuid, NormalJudgeTestcase)>,
+        checker: BuiltinChecker,
+        groups: &[TestcaseGroup],
+    ) -> Result<(), UsecaseError> {
+        let rows = testcases
+            .iter()
+            .map(|(id, testcase)| testcase_row(*id, problem_id, testcase))
+            .collect();
+        let procedure = Self::create_procedure(
+            testcases
+                .into_iter()
+                .map(|(_, testcase)| testcase)
+                .collect(),
+            checker,
+            groups,
+        )?;
 
         self.dep_name_repository
-            .remove_many(problem.id)
+            .remove_many(problem_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
         let registered_procedure = register(
             procedure,
             self.problem_registry_server.clone(),
             self.dep_name_repository.clone(),
-            problem.id,
+            problem_id,
         )
         .await
         .map_err(UsecaseError::internal_server_error_map())?;
 
-        let dep_id_to_resource_id = {
-            let mut dep_id_to_resource_id = std::collections::HashMap::new();
-            for text in registered_procedure.texts.iter() {
-                dep_id_to_resource_id.insert(text.dep_id, text.resource_id);
-            }
-            dep_id_to_resource_id
-        };
-
         self.procedure_repository
-            .update_procedure(problem.id, registered_procedure)
+            .update_procedure(problem_id, registered_procedure)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
-        // データベースに保存するためのtestcasesを作成
-        let name_to_id = {
-            let id_to_name = self
-                .dep_name_repository
-                .get_many_by_problem_id(problem.id)
-                .await
-                .map_err(UsecaseError::internal_server_error_map())?;
-            let mut name_to_id = std::collections::HashMap::new();
-            for (id, name) in id_to_name {
-                name_to_id.insert(name, id);
-            }
-            name_to_id
-        };
-        let mut new_testcases: Vec<CreateTestcase> = Vec::new();
-        for testcase in now_testcases.iter() {
-            if testcase.id != testcase_id {
-                let input_id = name_to_id
-                    .get(testcase_input_name(&testcase.name).as_str())
-                    .ok_or_else(|| {
-                        UsecaseError::internal_server_error_msg(
-                            "missing dep name for testcase input (edit existing)",
-                        )
-                    })?;
-                let input_id = dep_id_to_resource_id.get(input_id).ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing resource id for testcase input dep (edit existing)",
-                    )
-                })?;
-
-                let output_id = name_to_id
-                    .get(testcase_expected_name(&testcase.name).as_str())
-                    .ok_or_else(|| {
-                        UsecaseError::internal_server_error_msg(
-                            "missing dep name for testcase expected output (edit existing)",
-                        )
-                    })?;
-                let output_id = dep_id_to_resource_id.get(output_id).ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing resource id for testcase expected output dep (edit existing)",
-                    )
-                })?;
-
-                new_testcases.push(CreateTestcase {
-                    id: testcase.id,
-                    problem_id: problem.id,
-                    name: testcase.name.clone(),
-                    input_id: input_id.to_owned().into(),
-                    output_id: output_id.to_owned().into(),
-                });
-            } else {
-                let input_id = name_to_id
-                    .get(testcase_input_name(&put_testcase.name).as_str())
-                    .ok_or_else(|| {
-                        UsecaseError::internal_server_error_msg(
-                            "missing dep name for testcase input (put)",
-                        )
-                    })?;
-                let input_id = dep_id_to_resource_id.get(input_id).ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing resource id for testcase input dep (put)",
-                    )
-                })?;
-
-                let output_id = name_to_id
-                    .get(testcase_expected_name(&put_testcase.name).as_str())
-                    .ok_or_else(|| {
-                        UsecaseError::internal_server_error_msg(
-                            "missing dep name for testcase expected output (put)",
-                        )
-                    })?;
-                let output_id = dep_id_to_resource_id.get(output_id).ok_or_else(|| {
-                    UsecaseError::internal_server_error_msg(
-                        "missing resource id for testcase expected output dep (put)",
-                    )
-                })?;
-
-                new_testcases.push(CreateTestcase {
-                    id: testcase.id,
-                    problem_id: problem.id,
-                    name: put_testcase.name.clone(),
-                    input_id: input_id.to_owned().into(),
-                    output_id: output_id.to_owned().into(),
-                });
-            }
-        }
-
         // testcasesの更新
         self.testcase_repository
-            .delete_testcases(problem.id)
+            .delete_testcases(problem_id)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
         self.testcase_repository
-            .create_testcases(new_testcases)
+            .create_testcases(rows)
             .await
             .map_err(UsecaseError::internal_server_error_map())?;
 
         Ok(())
     }
+
+    /// Uses the grouped preset when there are testcase groups, the normal one otherwise
+    fn create_procedure(
+        testcases: Vec<NormalJudgeTestcase>,
+        checker: BuiltinChecker,
+        groups: &[TestcaseGroup],
+    ) -> Result<Procedure, UsecaseError> {
+        if groups.is_empty() {
+            // テストケースが多い問題は、実行の数が MAX_TEST_EXECUTIONS に収まるようにまとめて実行する
+            let batch_size = testcases.len().div_ceil(MAX_TEST_EXECUTIONS);
+            return create_batched_normal_judge_procedure(testcases, checker, batch_size)
+                .map_err(UsecaseError::internal_server_error_map());
+        }
+
+        let mut judge_groups = Vec::new();
+        for group in groups {
+            let policy = group
+                .policy
+                .parse::<GroupScoringPolicy>()
+                .map_err(UsecaseError::internal_server_error_map())?;
+            judge_groups.push(JudgeTestcaseGroup {
+                name: group.name.clone(),
+                point: group.point,
+                policy,
+            });
+        }
+        // 全てのtestcaseが既存のグループに属しているか確認
+        for testcase in testcases.iter() {
+            let Some(group) = testcase.group.as_ref() else {
+                return Err(UsecaseError::ValidateError);
+            };
+            if !judge_groups.iter().any(|x| &x.name == group) {
+                return Err(UsecaseError::ValidateError);
+            }
+        }
+        // testcaseのないグループは採点できない
+        if judge_groups.iter().any(|group| {
+            testcases
+                .iter()
+                .all(|testcase| testcase.group.as_ref() != Some(&group.name))
+        }) {
+            return Err(UsecaseError::ValidateError);
+        }
+
+        create_grouped_judge_procedure_with_checker(testcases, judge_groups, checker)
+            .map_err(UsecaseError::internal_server_error_map())
+    }
+}
+
+/// Upper bound on the test executions of a procedure built by the normal preset
+const MAX_TEST_EXECUTIONS: usize = 20;
+
+/// ResourceIds are content hashes, so the row is filled in without asking the registry
+fn testcase_row(id: Uuid, problem_id: i64, testcase: &NormalJudgeTestcase) -> CreateTestcase {
+    CreateTestcase {
+        id,
+        problem_id,
+        name: testcase.name.clone(),
+        input_id: ResourceId::from_content(testcase.input.as_bytes()).into(),
+        output_id: ResourceId::from_content(testcase.expected_output.as_bytes()).into(),
+        group_name: testcase.group.clone(),
+    }
 }
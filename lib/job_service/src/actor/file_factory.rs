@@ -65,9 +65,9 @@ impl<P: ProblemRegistryClient> FileFactory<P> {
         match file_conf {
             job::FileConf::EmptyDirectory => Ok(OutcomeToken::from_directory(outcome_id).await),
             job::FileConf::RuntimeText(content) => {
-                Ok(OutcomeToken::from_text(outcome_id, content).await)
+                Ok(OutcomeToken::from_file_content(outcome_id, content.as_bytes()).await)
             }
-            job::FileConf::Text(resource_id) => {
+            job::FileConf::Text(resource_id) | job::FileConf::Binary(resource_id) => {
                 let content = self
                     .problem_registry_client
                     .fetch(resource_id)
@@ -76,7 +76,34 @@ impl<P: ProblemRegistryClient> FileFactory<P> {
                         tracing::error!("Failed to fetch resource: {e}");
                         job::FilePlacementError::PlaceFailed(format!("ResourceFetchError: {e}"))
                     })?;
-                Ok(OutcomeToken::from_text(outcome_id, content).await)
+                Ok(OutcomeToken::from_file_content(outcome_id, &content).await)
+            }
+            job::FileConf::Directory(resource_id) => {
+                let archive = self
+                    .problem_registry_client
+                    .fetch(resource_id)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to fetch resource: {e}");
+                        job::FilePlacementError::PlaceFailed(format!("ResourceFetchError: {e}"))
+                    })?;
+                OutcomeToken::from_archive(outcome_id, &archive)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to unpack archive: {e}");
+                        job::FilePlacementError::PlaceFailed(format!("Invalid archive: {e}"))
+                    })
+            }
+            job::FileConf::Snapshot(job::OutcomeSnapshot::File(content)) => {
+                Ok(OutcomeToken::from_file_content(outcome_id, &content).await)
+            }
+            job::FileConf::Snapshot(job::OutcomeSnapshot::Directory(archive)) => {
+                OutcomeToken::from_archive(outcome_id, &archive)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to unpack snapshot: {e}");
+                        job::FilePlacementError::PlaceFailed(format!("Invalid snapshot: {e}"))
+                    })
             }
         }
     }
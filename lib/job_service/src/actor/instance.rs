@@ -10,12 +10,15 @@ use crate::job_service::OutcomeToken;
 use crate::model::aws::{AwsClient, AwsInstanceInfo};
 use crate::model::grpc::GrpcClient;
 
+pub type ExecutionResponse =
+    Result<(OutcomeToken, std::process::Output, job::ExecutionDetails), job::ExecutionError>;
+
 pub enum InstanceMessage {
     Execution {
         outcome_id_for_res: Uuid,
         dependencies: Vec<job::Dependency<OutcomeToken>>,
-        respond_to:
-            oneshot::Sender<Result<(OutcomeToken, std::process::Output), job::ExecutionError>>,
+        time_reserved_ms: u64,
+        respond_to: oneshot::Sender<ExecutionResponse>,
     },
     Terminate {
         respond_to: oneshot::Sender<Result<(), anyhow::Error>>,
@@ -72,13 +75,25 @@ impl<A: AwsClient, G: GrpcClient> Instance<A, G> {
             InstanceMessage::Execution {
                 outcome_id_for_res,
                 dependencies,
+                time_reserved_ms,
                 respond_to,
             } => {
                 tracing::debug!("[Instance::handle_execution] BEGIN aws_id={}", self.aws_id);
+                let started_at_ms = job::unix_time_ms();
                 let result = self
                     .grpc_client
-                    .execute(outcome_id_for_res, dependencies)
-                    .await;
+                    .execute(outcome_id_for_res, dependencies, time_reserved_ms)
+                    .await
+                    .map(|(outcome, output)| {
+                        let details = job::ExecutionDetails {
+                            host: Some(self.aws_id.clone()),
+                            retry_count: 0,
+                            started_at_ms: Some(started_at_ms),
+                            outcome_size_bytes: outcome.content_size(),
+                            cached: false,
+                        };
+                        (outcome, output, details)
+                    });
                 tracing::debug!("[Instance::handle_execution] END aws_id={}", self.aws_id);
                 let _ = respond_to.send(result); // if this send fails, so does the recv.await after
                 Running::Continue
@@ -8,18 +8,21 @@ use crate::job_service::{OutcomeToken, ReservationToken};
 use crate::{
     actor::{
         Running,
-        instance::{Instance, InstanceMessage},
+        instance::{ExecutionResponse, Instance, InstanceMessage},
     },
     model::{aws::AwsClient, grpc::GrpcClient},
 };
 
+/// Attempts made after the first one when an instance fails to run an execution
+const MAX_EXECUTION_RETRIES: u32 = 2;
+
 pub enum InstancePoolMessage {
     Execution {
         reservation: ReservationToken,
         outcome_id_for_res: Uuid,
         dependencies: Vec<job::Dependency<OutcomeToken>>,
-        respond_to:
-            oneshot::Sender<Result<(OutcomeToken, std::process::Output), job::ExecutionError>>,
+        time_reserved_ms: u64,
+        respond_to: oneshot::Sender<ExecutionResponse>,
     },
     Completion,
 }
@@ -75,10 +78,17 @@ where
                 reservation,
                 outcome_id_for_res,
                 dependencies,
+                time_reserved_ms,
                 respond_to,
             } => {
-                self.handle_execution(reservation, outcome_id_for_res, dependencies, respond_to)
-                    .await;
+                self.handle_execution(
+                    reservation,
+                    outcome_id_for_res,
+                    dependencies,
+                    time_reserved_ms,
+                    respond_to,
+                )
+                .await;
                 Running::Continue
             }
             InstancePoolMessage::Completion => {
@@ -92,9 +102,8 @@ where
         reservation: ReservationToken,
         outcome_id_for_res: Uuid,
         dependencies: Vec<job::Dependency<OutcomeToken>>,
-        respond_to: oneshot::Sender<
-            Result<(OutcomeToken, std::process::Output), job::ExecutionError>,
-        >,
+        time_reserved_ms: u64,
+        respond_to: oneshot::Sender<ExecutionResponse>,
     ) {
         tracing::debug!("[InstancePool::handle_execution] BEGIN");
         self.running_job_count += 1;
@@ -113,24 +122,43 @@ where
         let instance_tx = self.instance_tx.clone();
         let pool_tx = self.pool_tx.clone();
         tokio::spawn(async move {
-            let (tx, rx) = oneshot::channel();
-            let _ = instance_tx
-                .send(InstanceMessage::Execution {
-                    outcome_id_for_res,
-                    dependencies,
-                    respond_to: tx,
-                })
-                .await; // if this send fails, so does the recv.await after
+            let mut retry_count = 0;
+            let result = loop {
+                let (tx, rx) = oneshot::channel();
+                let _ = instance_tx
+                    .send(InstanceMessage::Execution {
+                        outcome_id_for_res,
+                        dependencies: dependencies.clone(),
+                        time_reserved_ms,
+                        respond_to: tx,
+                    })
+                    .await; // if this send fails, so does the recv.await after
 
-            let result = rx
-                .await
-                .map_err(|e| {
-                    tracing::error!("Instance task has been killed: {e}");
-                    job::ExecutionError::InternalError(format!(
-                        "Instance task has been killed: {e}"
-                    ))
-                })
-                .and_then(|res| res);
+                let result = rx
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Instance task has been killed: {e}");
+                        job::ExecutionError::InternalError(format!(
+                            "Instance task has been killed: {e}"
+                        ))
+                    })
+                    .and_then(|res| res);
+                match result {
+                    // インスタンスとの通信の失敗は一時的なものとみなしてやり直す
+                    Err(job::ExecutionError::InternalError(e))
+                        if retry_count < MAX_EXECUTION_RETRIES =>
+                    {
+                        retry_count += 1;
+                        tracing::warn!("Retrying execution ({retry_count}) after error: {e}");
+                    }
+                    result => {
+                        break result.map(|(outcome, output, mut details)| {
+                            details.retry_count = retry_count;
+                            (outcome, output, details)
+                        });
+                    }
+                }
+            };
             let _ = respond_to.send(result); // if this send fails, so does the recv.await after
 
             drop(reservation);
@@ -46,6 +46,7 @@ impl grpc::GrpcClient for GrpcClient {
         &mut self,
         outcome_id_for_res: Uuid,
         dependency: Vec<job::Dependency<OutcomeToken>>,
+        time_reserved_ms: u64,
     ) -> Result<(OutcomeToken, std::process::Output), job::ExecutionError> {
         let mut request = vec![];
         for job::Dependency { envvar, outcome } in dependency {
@@ -59,10 +60,16 @@ impl grpc::GrpcClient for GrpcClient {
             .exec_client
             .execute(ExecuteRequest {
                 dependency: request,
-                exec_time_ms: 998244353,
+                exec_time_ms: i32::try_from(time_reserved_ms).unwrap_or(i32::MAX),
             })
             .await
-            .unwrap()
+            .map_err(|status| match status.code() {
+                tonic::Code::DeadlineExceeded => job::ExecutionError::Timeout(time_reserved_ms),
+                _ => job::ExecutionError::InternalError(format!(
+                    "Execute request failed: {}",
+                    status
+                )),
+            })?
             .into_inner();
 
         tracing::info!("Execute response received: {:?}", resp);
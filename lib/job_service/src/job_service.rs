@@ -1,10 +1,17 @@
-use std::{future::Future, net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{
+    future::Future,
+    io::Read,
+    net::Ipv4Addr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
-use flate2::{Compression, write::GzEncoder};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
 use judge_core::{
     constant::env_var_exec,
     model::{job, problem_registry::ProblemRegistryClient},
 };
+use sha2::{Digest, Sha256};
 use tokio::{
     io::AsyncWriteExt,
     sync::{mpsc, oneshot},
@@ -17,8 +24,12 @@ use crate::{
         instance_pool::{InstancePool, InstancePoolMessage},
     },
     model::{aws::AwsClient, grpc::GrpcClient},
+    outcome_cache::OutcomeCache,
 };
 
+/// Number of execution results kept for reuse
+const OUTCOME_CACHE_CAPACITY: usize = 4096;
+
 #[derive(Clone)]
 pub struct JobService {
     inner: Arc<JobServiceInner>,
@@ -27,6 +38,7 @@ pub struct JobService {
 struct JobServiceInner {
     instance_pool_tx: mpsc::UnboundedSender<InstancePoolMessage>,
     file_factory_tx: mpsc::UnboundedSender<FileFactoryMessage>,
+    outcome_cache: Mutex<OutcomeCache>,
 }
 
 impl JobService {
@@ -61,6 +73,7 @@ impl JobService {
             inner: Arc::new(JobServiceInner {
                 instance_pool_tx,
                 file_factory_tx,
+                outcome_cache: Mutex::new(OutcomeCache::new(OUTCOME_CACHE_CAPACITY)),
             }),
         }
     }
@@ -73,6 +86,8 @@ pub struct ReservationToken {}
 pub struct OutcomeToken {
     pub outcome_id: Uuid,
     path_to_tar_gz: PathBuf,
+    content_hash: Option<[u8; 32]>,
+    content_size: Option<u64>,
 }
 
 impl OutcomeToken {
@@ -93,36 +108,133 @@ impl OutcomeToken {
         enc.finish().unwrap();
         OutcomeToken::from_binary(outcome_id, &tar_buf).await
     }
-    pub async fn from_text(outcome_id: Uuid, text: String) -> Self {
+    pub async fn from_file_content(outcome_id: Uuid, content: &[u8]) -> Self {
         let mut tar_buf = vec![];
         let enc = GzEncoder::new(&mut tar_buf, Compression::default());
         let mut tar = tar::Builder::new(enc);
         let mut header = tar::Header::new_gnu();
-        header.set_size(text.len() as u64);
+        header.set_size(content.len() as u64);
         header.set_mode(0o644);
         header.set_cksum();
         let file_name = format!("{}", outcome_id);
-        tar.append_data(&mut header, file_name, text.as_bytes())
-            .unwrap();
+        tar.append_data(&mut header, file_name, content).unwrap();
         tar.finish().unwrap();
         let enc = tar.into_inner().unwrap();
         enc.finish().unwrap();
         OutcomeToken::from_binary(outcome_id, &tar_buf).await
     }
+    /// Repacks a tar archive of a directory tree so that it unpacks under the outcome directory
+    pub async fn from_archive(outcome_id: Uuid, archive: &[u8]) -> std::io::Result<Self> {
+        let mut tar_buf = vec![];
+        let enc = GzEncoder::new(&mut tar_buf, Compression::default());
+        let mut tar = tar::Builder::new(enc);
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        header.set_cksum();
+        let dir_name = format!("{}/", outcome_id);
+        tar.append_data(&mut header, dir_name, std::io::empty())?;
+        let mut source = tar::Archive::new(archive);
+        for entry in source.entries()? {
+            let mut entry = entry?;
+            let path = PathBuf::from(outcome_id.to_string()).join(entry.path()?);
+            let mut header = entry.header().clone();
+            tar.append_data(&mut header, path, &mut entry)?;
+        }
+        tar.finish()?;
+        let enc = tar.into_inner()?;
+        enc.finish()?;
+        Ok(OutcomeToken::from_binary(outcome_id, &tar_buf).await)
+    }
     pub async fn from_binary(outcome_id: Uuid, binary: &[u8]) -> Self {
         let path_to_tar_gz = PathBuf::from(format!("outcomes/{outcome_id}.tar.gz"));
         let mut file = tokio::fs::File::create(path_to_tar_gz.clone())
             .await
             .unwrap();
         file.write_all(binary).await.unwrap();
+        let (content_hash, content_size) = match content_digest(binary) {
+            Ok((content_hash, content_size)) => (Some(content_hash), Some(content_size)),
+            Err(e) => {
+                tracing::warn!("Failed to hash outcome {outcome_id}: {e}");
+                (None, None)
+            }
+        };
         Self {
             outcome_id,
             path_to_tar_gz,
+            content_hash,
+            content_size,
         }
     }
     pub async fn to_binary(&self) -> Vec<u8> {
         tokio::fs::read(self.path_to_tar_gz.clone()).await.unwrap()
     }
+    #[cfg(test)]
+    pub(crate) fn with_content_hash(content_hash: [u8; 32]) -> Self {
+        Self {
+            outcome_id: Uuid::new_v4(),
+            path_to_tar_gz: PathBuf::new(),
+            content_hash: Some(content_hash),
+            content_size: None,
+        }
+    }
+    /// Hash of the files in the outcome, which does not depend on `outcome_id`
+    pub fn content_hash(&self) -> Option<[u8; 32]> {
+        self.content_hash
+    }
+    /// Total size of the files in the outcome, before compression
+    pub fn content_size(&self) -> Option<u64> {
+        self.content_size
+    }
+}
+
+fn content_digest(binary: &[u8]) -> std::io::Result<([u8; 32], u64)> {
+    let mut archive = tar::Archive::new(GzDecoder::new(binary));
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        // 先頭の outcome_id を取り除いたパスで比べる
+        let path: PathBuf = entry.path()?.components().skip(1).collect();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        entries.push((path, entry.header().entry_type().as_byte(), content));
+    }
+    entries.sort();
+    let content_size = entries
+        .iter()
+        .map(|(_, _, content)| content.len() as u64)
+        .sum();
+    let mut hasher = Sha256::new();
+    for (path, entry_type, content) in entries {
+        let path = path.to_string_lossy();
+        hasher.update((path.len() as u64).to_le_bytes());
+        hasher.update(path.as_bytes());
+        hasher.update([entry_type]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok((hasher.finalize().into(), content_size))
+}
+
+fn snapshot(binary: &[u8]) -> std::io::Result<job::OutcomeSnapshot> {
+    let mut archive = tar::Archive::new(GzDecoder::new(binary));
+    let mut builder = tar::Builder::new(Vec::new());
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        // 先頭の outcome_id を取り除いて、ディレクトリの中身だけを残す
+        let path: PathBuf = entry.path()?.components().skip(1).collect();
+        if path.as_os_str().is_empty() {
+            if entry.header().entry_type().is_dir() {
+                continue;
+            }
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            return Ok(job::OutcomeSnapshot::File(content));
+        }
+        let mut header = entry.header().clone();
+        builder.append_data(&mut header, path, &mut entry)?;
+    }
+    Ok(job::OutcomeSnapshot::Directory(builder.into_inner()?))
 }
 
 #[axum::async_trait]
@@ -141,8 +253,29 @@ impl job::JobService<ReservationToken, OutcomeToken> for JobService {
         &self,
         reservation: ReservationToken,
         mut dependencies: Vec<job::Dependency<OutcomeToken>>,
-    ) -> Result<(OutcomeToken, std::process::Output), job::ExecutionError> {
+        time_reserved_ms: u64,
+        no_cache: bool,
+    ) -> Result<(OutcomeToken, std::process::Output, job::ExecutionDetails), job::ExecutionError>
+    {
         tracing::debug!("[JobService::execute] BEGIN");
+        let cache_key = if no_cache {
+            None
+        } else {
+            OutcomeCache::key(&dependencies, time_reserved_ms)
+        };
+        if let Some(key) = cache_key.as_ref()
+            && let Some((outcome, output)) = self.inner.outcome_cache.lock().unwrap().get(key)
+        {
+            tracing::debug!("[JobService::execute] END (cached)");
+            let details = job::ExecutionDetails {
+                host: None,
+                retry_count: 0,
+                started_at_ms: Some(job::unix_time_ms()),
+                outcome_size_bytes: outcome.content_size(),
+                cached: true,
+            };
+            return Ok((outcome, output, details));
+        }
         let outcome_for_res = self
             .place_file(job::FileConf::EmptyDirectory)
             .await
@@ -162,12 +295,23 @@ impl job::JobService<ReservationToken, OutcomeToken> for JobService {
                 reservation,
                 outcome_id_for_res: outcome_for_res.outcome_id,
                 dependencies,
+                time_reserved_ms,
                 respond_to: tx,
             }); // if this send fails, so does the recv.await below
         let res = rx.await.map_err(|e| {
             tracing::error!("InstancePool task has been killed: {e}");
             job::ExecutionError::InternalError(format!("InstancePool task has been killed: {e}"))
         })?;
+        // スクリプト自体が失敗したものは一時的な障害かもしれないので覚えない
+        if let (Some(key), Ok((outcome, output, _))) = (cache_key, &res)
+            && output.status.success()
+        {
+            self.inner
+                .outcome_cache
+                .lock()
+                .unwrap()
+                .insert(key, outcome.clone(), output.clone());
+        }
         tracing::debug!("[JobService::execute] END");
         res
     }
@@ -192,4 +336,14 @@ impl job::JobService<ReservationToken, OutcomeToken> for JobService {
         tracing::debug!("[JobService::place_file] END");
         res
     }
+
+    async fn snapshot(
+        &self,
+        outcome: &OutcomeToken,
+    ) -> Result<job::OutcomeSnapshot, job::SnapshotError> {
+        let binary = tokio::fs::read(&outcome.path_to_tar_gz)
+            .await
+            .map_err(|e| job::SnapshotError::SnapshotFailed(e.to_string()))?;
+        snapshot(&binary).map_err(|e| job::SnapshotError::SnapshotFailed(e.to_string()))
+    }
 }
@@ -3,3 +3,4 @@ pub mod aws;
 pub mod grpc;
 pub mod job_service;
 pub mod model;
+pub mod outcome_cache;
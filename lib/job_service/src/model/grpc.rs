@@ -9,5 +9,6 @@ pub trait GrpcClient {
         &mut self,
         outcome_id_for_res: Uuid,
         dependency: Vec<job::Dependency<OutcomeToken>>,
+        time_reserved_ms: u64,
     ) -> Result<(OutcomeToken, std::process::Output), job::ExecutionError>;
 }
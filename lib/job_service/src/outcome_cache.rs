@@ -0,0 +1,130 @@
+use std::collections::{HashMap, VecDeque};
+
+use judge_core::model::job;
+use sha2::{Digest, Sha256};
+
+use crate::job_service::OutcomeToken;
+
+pub type CacheKey = [u8; 32];
+
+/// Results of earlier executions, keyed by the contents of their dependencies and their deadline.
+///
+/// The script is one of the dependencies, so two executions share a key
+/// only when they would run the same script on the same files.
+pub struct OutcomeCache {
+    entries: HashMap<CacheKey, (OutcomeToken, std::process::Output)>,
+    // 古いものから捨てる
+    order: VecDeque<CacheKey>,
+    capacity: usize,
+}
+
+impl OutcomeCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// `None` if the content of some dependency is unknown
+    pub fn key(
+        dependencies: &[job::Dependency<OutcomeToken>],
+        time_reserved_ms: u64,
+    ) -> Option<CacheKey> {
+        let mut hashes = dependencies
+            .iter()
+            .map(|dependency| {
+                Some((
+                    dependency.envvar.as_str(),
+                    dependency.outcome.content_hash()?,
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        hashes.sort();
+        let mut hasher = Sha256::new();
+        // 締め切りが違えば TLE になるかどうかも変わる
+        hasher.update(time_reserved_ms.to_le_bytes());
+        for (envvar, content_hash) in hashes {
+            hasher.update((envvar.len() as u64).to_le_bytes());
+            hasher.update(envvar.as_bytes());
+            hasher.update(content_hash);
+        }
+        Some(hasher.finalize().into())
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<(OutcomeToken, std::process::Output)> {
+        self.entries.get(key).cloned()
+    }
+
+    pub fn insert(&mut self, key: CacheKey, outcome: OutcomeToken, output: std::process::Output) {
+        if self.entries.insert(key, (outcome, output)).is_some() {
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(envvar: &str, content: u8) -> job::Dependency<OutcomeToken> {
+        job::Dependency {
+            envvar: envvar.to_string(),
+            outcome: OutcomeToken::with_content_hash([content; 32]),
+        }
+    }
+
+    fn output() -> std::process::Output {
+        std::process::Output {
+            status: std::process::ExitStatus::default(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn key_depends_on_contents_and_deadline_only() {
+        let key = OutcomeCache::key(&[dependency("SCRIPT", 1), dependency("INPUT", 2)], 1000);
+        assert!(key.is_some());
+        assert_eq!(
+            key,
+            OutcomeCache::key(&[dependency("INPUT", 2), dependency("SCRIPT", 1)], 1000)
+        );
+        // どの環境変数にどの中身を渡すかで結果は変わる
+        assert_ne!(
+            key,
+            OutcomeCache::key(&[dependency("SCRIPT", 2), dependency("INPUT", 1)], 1000)
+        );
+        assert_ne!(
+            key,
+            OutcomeCache::key(&[dependency("SCRIPT", 1), dependency("INPUT", 2)], 2000)
+        );
+    }
+
+    #[test]
+    fn evicts_the_oldest_entry() {
+        let mut cache = OutcomeCache::new(2);
+        for content in 0..3 {
+            cache.insert(
+                [content; 32],
+                OutcomeToken::with_content_hash([content; 32]),
+                output(),
+            );
+        }
+        assert!(cache.get(&[0; 32]).is_none());
+        assert!(cache.get(&[1; 32]).is_some());
+        assert!(cache.get(&[2; 32]).is_some());
+        // 同じキーを入れ直しても順番は変わらない
+        cache.insert([1; 32], OutcomeToken::with_content_hash([1; 32]), output());
+        cache.insert([3; 32], OutcomeToken::with_content_hash([3; 32]), output());
+        assert!(cache.get(&[1; 32]).is_none());
+        assert!(cache.get(&[2; 32]).is_some());
+    }
+}
@@ -1,5 +1,7 @@
 pub const SCRIPT_PATH: &str = "TRAOJUDGE_EXEC_SCRIPT";
 pub const OUTPUT_PATH: &str = "TRAOJUDGE_EXEC_OUTPUT";
+/// Set by the execution layer itself, so writers may not bind them as dependencies.
+pub const RESERVED: [&str; 2] = [SCRIPT_PATH, OUTPUT_PATH];
 pub const LANGUAGES_JSON: &str = "TRAOJUDGE_LANGUAGES_JSON";
 pub const BUILD_SOURCE: &str = "TRAOJUDGE_BUILD_SOURCE";
 pub const BUILD_OUTPUT: &str = "TRAOJUDGE_BUILD_OUTPUT";
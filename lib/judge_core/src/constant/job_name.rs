@@ -1,9 +1,29 @@
 pub const COMPILE_PHASE: &str = "compilePhase";
+pub const CHECKER_COMPILE_PHASE: &str = "checkerCompilePhase";
+pub const INTERACTOR_COMPILE_PHASE: &str = "interactorCompilePhase";
 pub const TEST_PHASE_PREFIX: &str = "testPhase_";
 pub fn test_phase_execution_job_name(core_name: &str) -> String {
     format!("{}{}", TEST_PHASE_PREFIX, core_name)
 }
+pub const TEST_BATCH_PREFIX: &str = "testBatch_";
+pub fn test_batch_execution_job_name(index: usize) -> String {
+    format!("{}{}", TEST_BATCH_PREFIX, index)
+}
+pub fn test_batch_tempdir_name(index: usize) -> String {
+    format!("{}{}{}", TESTCASE_TEMPDIR_PREFIX, TEST_BATCH_PREFIX, index)
+}
+pub fn test_batch_testcase_names_name(index: usize) -> String {
+    format!("testcase_names_{}{}", TEST_BATCH_PREFIX, index)
+}
+pub const GROUP_PHASE_PREFIX: &str = "groupPhase_";
+pub fn group_phase_execution_job_name(group_name: &str) -> String {
+    format!("{}{}", GROUP_PHASE_PREFIX, group_name)
+}
 pub const SUMMARY_PHASE: &str = "summaryPhase";
+pub const TESTCASE_TEMPDIR_PREFIX: &str = "tempdir_";
+pub fn testcase_tempdir_name(core_name: &str) -> String {
+    format!("{}{}", TESTCASE_TEMPDIR_PREFIX, core_name)
+}
 pub mod v0_features {
     // Testcase inputs
     const TESTCASE_INPUT_SUFFIX: &str = "_input";
@@ -24,11 +44,17 @@ pub mod v0_features {
             ))
         }
     }
+    pub fn get_core_name_from_testcase_input_name(input_name: &str) -> Option<&str> {
+        input_name.strip_suffix(TESTCASE_INPUT_SUFFIX)
+    }
     // Testcase expected outputs
     const TESTCASE_EXPECTED_SUFFIX: &str = "_expected";
     pub fn testcase_expected_name(core_name: &str) -> String {
         format!("{}{}", core_name, TESTCASE_EXPECTED_SUFFIX)
     }
+    pub fn get_core_name_from_testcase_expected_name(expected_name: &str) -> Option<&str> {
+        expected_name.strip_suffix(TESTCASE_EXPECTED_SUFFIX)
+    }
     pub fn get_testcase_expected_name_from_execution_job_name(
         execution_job_name: &str,
     ) -> anyhow::Result<String> {
@@ -1,9 +1,16 @@
+#[cfg(test)]
+mod fake_job_service;
 pub mod judge_service_impl;
 pub mod output_parser;
 pub mod problem_presets;
 pub mod procedure_builder;
 pub mod registered_procedure_converter;
+pub mod registered_procedure_patcher;
 pub mod registered_procedure_remover;
 pub mod runner;
+pub mod schema_migration;
+pub mod trace_recorder;
+pub mod trace_replayer;
 pub mod validation;
+pub mod writer_schema_exporter;
 pub mod writer_schema_registerer;
@@ -0,0 +1,100 @@
+use crate::model::{job, judge_output::*};
+use std::os::unix::process::ExitStatusExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::Notify;
+
+/// `JobService` for tests, answering AC to every execution unless its dependencies say otherwise:
+/// - an execution given a `WA` envvar answers WA
+/// - an execution given a `FAIL` envvar fails
+/// - an execution given a `BLOCK` envvar never finishes by itself and notifies `blocked`
+/// - placing the runtime text `FAIL` fails
+///
+/// It also records how many outcomes are alive while executing.
+#[derive(Clone, Default)]
+pub struct FakeJobService {
+    pub blocked: Arc<Notify>,
+    pub max_alive: Arc<AtomicUsize>,
+    outcomes: Arc<Mutex<Vec<Weak<()>>>>,
+}
+
+impl FakeJobService {
+    fn new_outcome(&self) -> Arc<()> {
+        let outcome = Arc::new(());
+        self.outcomes.lock().unwrap().push(Arc::downgrade(&outcome));
+        outcome
+    }
+}
+
+#[axum::async_trait]
+impl job::JobService<(), Arc<()>> for FakeJobService {
+    async fn reserve_execution(&self, count: usize) -> Result<Vec<()>, job::ReservationError> {
+        Ok(vec![(); count])
+    }
+
+    async fn place_file(
+        &self,
+        file_conf: job::FileConf,
+    ) -> Result<Arc<()>, job::FilePlacementError> {
+        if matches!(&file_conf, job::FileConf::RuntimeText(content) if content == "FAIL") {
+            return Err(job::FilePlacementError::PlaceFailed("broken".to_string()));
+        }
+        Ok(self.new_outcome())
+    }
+
+    async fn execute(
+        &self,
+        _reservation: (),
+        dependencies: Vec<job::Dependency<Arc<()>>>,
+        _time_reserved_ms: u64,
+        _no_cache: bool,
+    ) -> Result<(Arc<()>, std::process::Output, job::ExecutionDetails), job::ExecutionError> {
+        let alive = self
+            .outcomes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|outcome| outcome.strong_count() > 0)
+            .count();
+        self.max_alive.fetch_max(alive, Ordering::SeqCst);
+        let has_envvar = |envvar: &str| dependencies.iter().any(|dep| dep.envvar == envvar);
+        if has_envvar("BLOCK") {
+            self.blocked.notify_one();
+            std::future::pending::<()>().await;
+        }
+        if has_envvar("FAIL") {
+            return Err(job::ExecutionError::JudgeFailed("broken".to_string()));
+        }
+        let status = if has_envvar("WA") {
+            JudgeStatus::WA
+        } else {
+            JudgeStatus::AC
+        };
+        let result = ExecutionResult::Displayable(DisplayableExecutionResult {
+            status,
+            time: 0.0,
+            memory: 0.0,
+            score: 0,
+            message: None,
+            continue_status: ContinueStatus::Continue,
+            compile_log: None,
+            stderr: None,
+            signal: None,
+            cpu_time: None,
+            wall_time: None,
+        });
+        let output = std::process::Output {
+            status: std::process::ExitStatus::from_raw(0),
+            stdout: serde_json::to_vec(&result).unwrap(),
+            stderr: Vec::new(),
+        };
+        Ok((self.new_outcome(), output, job::ExecutionDetails::default()))
+    }
+
+    async fn snapshot(
+        &self,
+        _outcome: &Arc<()>,
+    ) -> Result<job::OutcomeSnapshot, job::SnapshotError> {
+        Ok(job::OutcomeSnapshot::File(Vec::new()))
+    }
+}
@@ -1,7 +1,13 @@
 use crate::logic::*;
-use crate::model::*;
+use crate::model::{cancellation::CancellationToken, *};
+use futures::StreamExt;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::{Mutex, mpsc};
+
+type ProgressSender =
+    mpsc::UnboundedSender<anyhow::Result<(identifiers::DepId, judge_output::ExecutionJobReport)>>;
 
 #[derive(Debug)]
 pub struct JudgeServiceImpl<
@@ -10,6 +16,8 @@ pub struct JudgeServiceImpl<
     JobService: job::JobService<RToken, OToken>,
 > {
     job_service: JobService,
+    running_judges: Arc<Mutex<HashMap<identifiers::JudgeId, CancellationToken>>>,
+    trace_dir: Option<PathBuf>,
     _phantom: std::marker::PhantomData<(Arc<RToken>, OToken)>,
 }
 
@@ -22,9 +30,128 @@ impl<
     pub fn new(job_service: JobService) -> Self {
         Self {
             job_service,
+            running_judges: Arc::new(Mutex::new(HashMap::new())),
+            trace_dir: None,
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Records a trace of every judge and keeps it as `<trace_dir>/<judge_id>.tar`
+    pub fn with_trace_dir(mut self, trace_dir: PathBuf) -> Self {
+        self.trace_dir = Some(trace_dir);
+        self
+    }
+
+    fn trace_path(&self, judge_id: identifiers::JudgeId) -> Option<PathBuf> {
+        self.trace_dir
+            .as_ref()
+            .map(|trace_dir| trace_dir.join(format!("{}.tar", judge_id)))
+    }
+
+    async fn save_trace(
+        &self,
+        judge_id: identifiers::JudgeId,
+        trace: &trace_recorder::TraceRecorder,
+        identifier_map: &HashMap<identifiers::RuntimeId, identifiers::DepId>,
+    ) -> anyhow::Result<()> {
+        let Some(path) = self.trace_path(judge_id) else {
+            return Ok(());
+        };
+        let bundle = trace.finish(identifier_map).await?.to_bundle()?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, bundle).await?;
+        Ok(())
+    }
+
+    async fn judge_with_progress(
+        &self,
+        judge_request: judge::JudgeRequest,
+        progress: Option<ProgressSender>,
+    ) -> judge::JudgeResponse {
+        let judge_id = judge_request.judge_id;
+        let cancellation = CancellationToken::new();
+        {
+            let mut running_judges = self.running_judges.lock().await;
+            if let Some(previous) = running_judges.insert(judge_id, cancellation.clone()) {
+                tracing::warn!("Judge {} is already running, cancelling it", judge_id);
+                previous.cancel();
+            }
+        }
+        let judge_results = self
+            .run_procedure(&judge_request, cancellation.clone(), progress)
+            .await;
+        {
+            // A newer judge with the same ID may have replaced ours in the meantime
+            let mut running_judges = self.running_judges.lock().await;
+            if running_judges
+                .get(&judge_id)
+                .is_some_and(|token| token.same_as(&cancellation))
+            {
+                running_judges.remove(&judge_id);
+            }
+        }
+        judge_results
+    }
+
+    async fn run_procedure(
+        &self,
+        judge_request: &judge::JudgeRequest,
+        cancellation: CancellationToken,
+        progress: Option<ProgressSender>,
+    ) -> judge::JudgeResponse {
+        let (runtime_procedure, identifier_map) = registered_procedure_converter::convert(
+            &judge_request.procedure,
+            &judge_request.runtime_texts,
+        )?;
+        let (runtime_sender, mut runtime_receiver) = mpsc::unbounded_channel();
+        let trace = self
+            .trace_dir
+            .is_some()
+            .then(|| trace_recorder::TraceRecorder::new(&runtime_procedure));
+        let runner = runner::Runner::new(
+            self.job_service.clone(),
+            runtime_procedure,
+            cancellation,
+            progress.is_some().then_some(runtime_sender),
+            trace.clone(),
+        )
+        .await?;
+        // Translate RuntimeIds to DepIds until the runner drops its sender
+        let forward_progress = async {
+            while let Some((runtime_id, result)) = runtime_receiver.recv().await {
+                if let (Some(progress), Some(dep_id)) =
+                    (progress.as_ref(), identifier_map.get(&runtime_id))
+                {
+                    let _ = progress.send(Ok((*dep_id, result)));
+                }
+            }
+        };
+        let (judge_results, _) = tokio::join!(runner.run(), forward_progress);
+        if let Some(trace) = &trace {
+            // トレースが残せなくても採点結果は返す
+            if let Err(e) = self
+                .save_trace(judge_request.judge_id, trace, &identifier_map)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to save the trace of judge {}: {}",
+                    judge_request.judge_id,
+                    e
+                );
+            }
+        }
+        let judge_results = judge_results?;
+        let mut judge_results_depid = HashMap::new();
+        for (runtime_id, result) in judge_results {
+            let dep_id = *identifier_map
+                .get(&runtime_id)
+                .ok_or(anyhow::anyhow!("DepId not found"))?;
+            judge_results_depid.insert(dep_id, result);
+        }
+        Ok(judge_results_depid)
+    }
 }
 
 impl<
@@ -36,6 +163,8 @@ impl<
     fn clone(&self) -> Self {
         Self {
             job_service: self.job_service.clone(),
+            running_judges: self.running_judges.clone(),
+            trace_dir: self.trace_dir.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -49,20 +178,194 @@ impl<
 > judge::JudgeService for JudgeServiceImpl<RToken, OToken, JobService>
 {
     async fn judge(&self, judge_request: judge::JudgeRequest) -> judge::JudgeResponse {
-        let (runtime_procedure, identifier_map) = registered_procedure_converter::convert(
-            &judge_request.procedure,
-            &judge_request.runtime_texts,
-        )?;
-        let runner = runner::Runner::new(self.job_service.clone(), runtime_procedure).await?;
-        let judge_results = runner.run().await?;
-        let mut judge_results_depid = HashMap::new();
-        for (runtime_id, result) in judge_results {
-            let dep_id = identifier_map
-                .get(&runtime_id)
-                .ok_or(anyhow::anyhow!("DepId not found"))?
-                .clone();
-            judge_results_depid.insert(dep_id, result);
+        self.judge_with_progress(judge_request, None).await
+    }
+
+    fn judge_stream(&self, judge_request: judge::JudgeRequest) -> judge::JudgeResponseStream {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let this = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = this
+                .judge_with_progress(judge_request, Some(sender.clone()))
+                .await
+            {
+                let _ = sender.send(Err(e));
+            }
+        });
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|item| (item, receiver))
+        })
+        .boxed()
+    }
+
+    async fn cancel(&self, judge_id: identifiers::JudgeId) -> anyhow::Result<()> {
+        let running_judges = self.running_judges.lock().await;
+        match running_judges.get(&judge_id) {
+            Some(cancellation) => {
+                tracing::info!("Cancelling judge {}", judge_id);
+                cancellation.cancel();
+            }
+            None => tracing::info!("Judge {} is not running, nothing to cancel", judge_id),
         }
-        Ok(judge_results_depid)
+        Ok(())
+    }
+
+    async fn trace(&self, judge_id: identifiers::JudgeId) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(path) = self.trace_path(judge_id) else {
+            return Ok(None);
+        };
+        match tokio::fs::read(&path).await {
+            Ok(bundle) => Ok(Some(bundle)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JudgeServiceImpl;
+    use crate::logic::fake_job_service::FakeJobService;
+    use crate::model::{
+        identifiers::{DepId, JudgeId, ResourceId},
+        judge::{JudgeRequest, JudgeService},
+        judge_output::*,
+        procedure::registered,
+    };
+    use futures::StreamExt;
+    use std::collections::HashMap;
+
+    /// Executions which each depend on the previous one through the given envvar
+    fn chain(judge_id: JudgeId, envvars: &[&str]) -> (JudgeRequest, Vec<DepId>) {
+        let text = registered::Text {
+            resource_id: ResourceId::from_content(b""),
+            dep_id: DepId::new(),
+        };
+        let mut previous = text.dep_id;
+        let mut executions = Vec::new();
+        for envvar in envvars {
+            let dep_id = DepId::new();
+            executions.push(registered::Execution {
+                dependencies: vec![registered::Dependency {
+                    dep_id: previous,
+                    envvar_name: envvar.to_string(),
+                    condition: None,
+                }],
+                dep_id,
+                time_reserved_ms: 1000,
+                no_cache: false,
+            });
+            previous = dep_id;
+        }
+        let ids = executions
+            .iter()
+            .map(|execution| execution.dep_id)
+            .collect();
+        let procedure = registered::Procedure {
+            runtime_texts: vec![],
+            texts: vec![text],
+            binaries: vec![],
+            directories: vec![],
+            empty_directories: vec![],
+            executions,
+        };
+        let request = JudgeRequest {
+            judge_id,
+            procedure,
+            runtime_texts: HashMap::new(),
+        };
+        (request, ids)
+    }
+
+    #[tokio::test]
+    async fn streams_each_report_before_the_jobs_depending_on_it() {
+        let judge_service = JudgeServiceImpl::new(FakeJobService::default());
+        let (request, ids) = chain(JudgeId::new(), &["INPUT", "PREVIOUS", "PREVIOUS"]);
+
+        let reports = judge_service
+            .judge_stream(request)
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        // 依存先の結果が先に届き、全部届いたところでストリームが閉じる
+        let order = reports
+            .iter()
+            .map(|(dep_id, _)| *dep_id)
+            .collect::<Vec<_>>();
+        assert_eq!(order, ids);
+        for (_, report) in reports.iter() {
+            assert!(matches!(
+                report.result,
+                ExecutionJobResult::ExecutionResult(ExecutionResult::Displayable(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_executions_do_not_abort_the_judge() {
+        let judge_service = JudgeServiceImpl::new(FakeJobService::default());
+        let (request, ids) = chain(JudgeId::new(), &["FAIL", "PREVIOUS"]);
+
+        let reports = judge_service.judge(request).await.unwrap();
+
+        assert!(matches!(
+            &reports[&ids[0]].result,
+            ExecutionJobResult::Failed { reason } if reason.contains("broken")
+        ));
+        // 失敗した実行の後続は動かさずに Skipped として返す
+        assert!(matches!(
+            reports[&ids[1]].result,
+            ExecutionJobResult::Skipped
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_the_running_judge() {
+        let job_service = FakeJobService::default();
+        let judge_service = JudgeServiceImpl::new(job_service.clone());
+        let judge_id = JudgeId::new();
+        let (request, ids) = chain(judge_id, &["BLOCK", "PREVIOUS"]);
+
+        let stream = judge_service.judge_stream(request);
+        job_service.blocked.notified().await;
+        judge_service.cancel(judge_id).await.unwrap();
+        let reports = stream.map(|item| item.unwrap()).collect::<Vec<_>>().await;
+
+        // 実行中のものも、まだ始まっていないものも Cancelled になる
+        let cancelled = reports
+            .iter()
+            .filter(|(_, report)| matches!(report.result, ExecutionJobResult::Cancelled))
+            .map(|(dep_id, _)| *dep_id)
+            .collect::<Vec<_>>();
+        assert_eq!(cancelled, ids);
+        // 終わった judge の取り消しは何もしない
+        judge_service.cancel(judge_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_newer_judge_with_the_same_id_cancels_the_older_one() {
+        let job_service = FakeJobService::default();
+        let judge_service = JudgeServiceImpl::new(job_service.clone());
+        let judge_id = JudgeId::new();
+        let (older, _) = chain(judge_id, &["BLOCK"]);
+        let (newer, newer_ids) = chain(judge_id, &["INPUT"]);
+
+        let older = judge_service.judge_stream(older);
+        job_service.blocked.notified().await;
+        let newer = judge_service.judge(newer).await.unwrap();
+        let older = older.map(|item| item.unwrap().1).collect::<Vec<_>>().await;
+
+        assert!(matches!(
+            older.as_slice(),
+            [ExecutionJobReport {
+                result: ExecutionJobResult::Cancelled,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            newer[&newer_ids[0]].result,
+            ExecutionJobResult::ExecutionResult(ExecutionResult::Displayable(_))
+        ));
     }
 }
@@ -1 +1,36 @@
+pub mod grouped_judge;
+pub mod interactive_judge;
 pub mod normal_judge;
+pub mod special_judge;
+mod tool_judge;
+
+use crate::logic::procedure_builder::{AddJobError, ProcedureBuilder};
+use crate::model::procedure::job_metadata::{JobMetadata, JobRole};
+use normal_judge::NormalJudgeTestcase;
+
+/// Tags the input, expected output and tempdir of a testcase and the execution judging it
+fn set_testcase_metadata(
+    builder: &mut ProcedureBuilder,
+    testcase: &NormalJudgeTestcase,
+    display_order: u32,
+    [input_file, expected_file, tempdir, test_result]: [&str; 4],
+) -> Result<(), AddJobError> {
+    let testcases = vec![testcase.name.clone()];
+    for (name, role) in [
+        (input_file, JobRole::TestcaseInput),
+        (expected_file, JobRole::TestcaseExpected),
+        (tempdir, JobRole::TestcaseTempdir),
+    ] {
+        builder.set_metadata(
+            name,
+            JobMetadata::new(role).with_testcases(testcases.clone()),
+        )?;
+    }
+    builder.set_metadata(
+        test_result,
+        JobMetadata::new(JobRole::Test)
+            .with_testcases(testcases)
+            .with_group(testcase.group.clone())
+            .with_display_order(display_order),
+    )
+}
@@ -0,0 +1,329 @@
+use crate::constant::label::single_judge;
+use crate::constant::*;
+use crate::logic::problem_presets::normal_judge::{CHECKER_TEXT_NAME, NormalJudgeTestcase};
+use crate::logic::procedure_builder::ProcedureBuilder;
+use crate::model::builtin_checker::BuiltinChecker;
+use crate::model::{
+    procedure::{job_metadata::*, writer_schema::*},
+    *,
+};
+use std::collections::HashMap;
+
+/// How the scores of the testcases in a group are turned into the group score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupScoringPolicy {
+    /// The full point if every testcase is accepted, otherwise 0
+    AllOrNothing,
+    /// The point scaled by the minimum testcase score
+    Min,
+    /// The point scaled by the average testcase score
+    Proportional,
+}
+
+impl GroupScoringPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupScoringPolicy::AllOrNothing => "allOrNothing",
+            GroupScoringPolicy::Min => "min",
+            GroupScoringPolicy::Proportional => "proportional",
+        }
+    }
+}
+
+impl std::str::FromStr for GroupScoringPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allOrNothing" => Ok(GroupScoringPolicy::AllOrNothing),
+            "min" => Ok(GroupScoringPolicy::Min),
+            "proportional" => Ok(GroupScoringPolicy::Proportional),
+            _ => Err(anyhow::anyhow!("Unknown group scoring policy: {}", s)),
+        }
+    }
+}
+
+pub struct TestcaseGroup {
+    pub name: String,
+    pub point: i64,
+    pub policy: GroupScoringPolicy,
+}
+
+static GJ_BUILD_SCRIPT: &str = include_str!("./normal_judge_build.py");
+static GJ_RUN_SCRIPT: &str = include_str!("./normal_judge_run.py");
+static GJ_GROUP_SUMMARIZE_SCRIPT: &str = include_str!("./grouped_judge_group_summarize.py");
+static GJ_SUMMARIZE_SCRIPT: &str = include_str!("./grouped_judge_summarize.py");
+
+static COMPILE_PHASE_TIME_RESERVED_MS: i64 = 30000;
+static TEST_PHASE_TIME_RESERVED_MS: i64 = 11000;
+static GROUP_PHASE_TIME_RESERVED_MS: i64 = 2000;
+static SUMMARIZE_PHASE_TIME_RESERVED_MS: i64 = 2000;
+
+/// Every testcase must belong to one of `groups`.
+pub fn create_grouped_judge_procedure(
+    testcases: Vec<NormalJudgeTestcase>,
+    groups: Vec<TestcaseGroup>,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    create_grouped_judge_procedure_with_checker(testcases, groups, BuiltinChecker::Exact)
+}
+
+/// Same as `create_grouped_judge_procedure`, but compares outputs with one of the built-in checkers
+pub fn create_grouped_judge_procedure_with_checker(
+    testcases: Vec<NormalJudgeTestcase>,
+    groups: Vec<TestcaseGroup>,
+    checker: BuiltinChecker,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    let mut group_members: HashMap<String, Vec<usize>> = groups
+        .iter()
+        .map(|group| (group.name.clone(), Vec::new()))
+        .collect();
+    if group_members.len() != groups.len() {
+        return Err(anyhow::anyhow!("Duplicate testcase group names"));
+    }
+    for (i, testcase) in testcases.iter().enumerate() {
+        let group_name = testcase.group.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Testcase {} does not belong to any group", testcase.name)
+        })?;
+        group_members
+            .get_mut(group_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Testcase {} belongs to unknown group {}",
+                    testcase.name,
+                    group_name
+                )
+            })?
+            .push(i);
+    }
+    // 空のグループは merge_judge_status([]) が AC になり、allOrNothing で満点になってしまう
+    if let Some(group) = groups
+        .iter()
+        .find(|group| group_members[&group.name].is_empty())
+    {
+        return Err(anyhow::anyhow!(
+            "Testcase group {} has no testcases",
+            group.name
+        ));
+    }
+
+    let mut builder = ProcedureBuilder::new();
+    let source = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "source".to_string(),
+        label: single_judge::SUBMISSION_SOURCE.to_string(),
+    }))?;
+    let lang_tag = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "language_tag".to_string(),
+        label: single_judge::LANGUAGE_TAG.to_string(),
+    }))?;
+    let time_limit = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "time_limit".to_string(),
+        label: single_judge::TIME_LIMIT_MS.to_string(),
+    }))?;
+    let memory_limit = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "memory_limit".to_string(),
+        label: single_judge::MEMORY_LIMIT_KIB.to_string(),
+    }))?;
+    let checker = builder.add_resource(ResourceKind::TextFile(Text {
+        name: CHECKER_TEXT_NAME.to_string(),
+        content: checker.to_string(),
+    }))?;
+    let build_script = builder.add_script(Text {
+        name: "build_script".to_string(),
+        content: GJ_BUILD_SCRIPT.to_string(),
+    })?;
+    let run_script = builder.add_script(Text {
+        name: "run_script".to_string(),
+        content: GJ_RUN_SCRIPT.to_string(),
+    })?;
+    let group_summarize_script = builder.add_script(Text {
+        name: "group_summarize_script".to_string(),
+        content: GJ_GROUP_SUMMARIZE_SCRIPT.to_string(),
+    })?;
+    let summarize_script = builder.add_script(Text {
+        name: "summarize_script".to_string(),
+        content: GJ_SUMMARIZE_SCRIPT.to_string(),
+    })?;
+    // Build the submission
+    let build_tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+        name: "build_tempdir".to_string(),
+    }))?;
+    let build_result = builder.add_execution(Execution {
+        name: job_name::COMPILE_PHASE.to_string(),
+        script_name: build_script.clone(),
+        dependencies: vec![
+            Dependency {
+                ref_to: source.clone(),
+                envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: lang_tag.clone(),
+                envvar_name: "LANGUAGE_TAG".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: build_tempdir.clone(),
+                envvar_name: "BUILD_TEMPDIR".to_string(),
+                condition: None,
+            },
+        ],
+        time_reserved_ms: COMPILE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
+    })?;
+    builder.set_metadata(&build_result, JobMetadata::new(JobRole::Compile))?;
+    // Run the submission for each testcase
+    let mut test_results = Vec::new();
+    for (i, testcase) in testcases.iter().enumerate() {
+        let input_file = builder.add_resource(ResourceKind::TextFile(Text {
+            name: job_name::v0_features::testcase_input_name(&testcase.name),
+            content: testcase.input.clone(),
+        }))?;
+        let expected_file = builder.add_resource(ResourceKind::TextFile(Text {
+            name: job_name::v0_features::testcase_expected_name(&testcase.name),
+            content: testcase.expected_output.clone(),
+        }))?;
+        let tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+            name: job_name::testcase_tempdir_name(&testcase.name),
+        }))?;
+        let test_result = builder.add_execution(Execution {
+            name: job_name::test_phase_execution_job_name(&testcase.name),
+            script_name: run_script.clone(),
+            dependencies: vec![
+                Dependency {
+                    ref_to: lang_tag.clone(),
+                    envvar_name: "LANGUAGE_TAG".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: time_limit.clone(),
+                    envvar_name: "TIME_LIMIT_MS".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: memory_limit.clone(),
+                    envvar_name: "MEMORY_LIMIT_KIB".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: input_file.clone(),
+                    envvar_name: "INPUT_FILE".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: expected_file.clone(),
+                    envvar_name: "EXPECTED_FILE".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: tempdir.clone(),
+                    envvar_name: "TEMP_DIR".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: build_result.clone(),
+                    envvar_name: "BUILD_OUTPUT_PATH".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: source.clone(),
+                    envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: checker.clone(),
+                    envvar_name: "CHECKER".to_string(),
+                    condition: None,
+                },
+            ],
+            time_reserved_ms: TEST_PHASE_TIME_RESERVED_MS as u64,
+            // 実行時間を測るので、キャッシュせず毎回実行する
+            no_cache: true,
+        })?;
+        super::set_testcase_metadata(
+            &mut builder,
+            testcase,
+            i as u32,
+            [&input_file, &expected_file, &tempdir, &test_result],
+        )?;
+        test_results.push(test_result);
+    }
+    // Score each group from the results of its testcases
+    let mut group_results = Vec::new();
+    for group in groups.iter() {
+        let members = &group_members[&group.name];
+        let testcase_count = builder.add_resource(ResourceKind::TextFile(Text {
+            name: format!("testcase_count_{}", group.name),
+            content: members.len().to_string(),
+        }))?;
+        let group_point = builder.add_resource(ResourceKind::TextFile(Text {
+            name: format!("group_point_{}", group.name),
+            content: group.point.to_string(),
+        }))?;
+        let group_policy = builder.add_resource(ResourceKind::TextFile(Text {
+            name: format!("group_policy_{}", group.name),
+            content: group.policy.as_str().to_string(),
+        }))?;
+        let mut group_dependencies = vec![
+            Dependency {
+                ref_to: testcase_count.clone(),
+                envvar_name: "TESTCASE_COUNT".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: group_point.clone(),
+                envvar_name: "GROUP_POINT".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: group_policy.clone(),
+                envvar_name: "GROUP_POLICY".to_string(),
+                condition: None,
+            },
+        ];
+        for (i, member) in members.iter().enumerate() {
+            group_dependencies.push(Dependency {
+                ref_to: test_results[*member].clone(),
+                envvar_name: format!("OUTPUT_JSON_{}", i),
+                condition: None,
+            });
+        }
+        let group_result = builder.add_execution(Execution {
+            name: job_name::group_phase_execution_job_name(&group.name),
+            script_name: group_summarize_script.clone(),
+            dependencies: group_dependencies,
+            time_reserved_ms: GROUP_PHASE_TIME_RESERVED_MS as u64,
+            no_cache: false,
+        })?;
+        builder.set_metadata(
+            &group_result,
+            JobMetadata::new(JobRole::GroupSummary).with_group(Some(group.name.clone())),
+        )?;
+        group_results.push(group_result);
+    }
+    // Summarize
+    let group_count = builder.add_resource(ResourceKind::TextFile(Text {
+        name: "group_count".to_string(),
+        content: groups.len().to_string(),
+    }))?;
+    let mut summarize_dependencies = vec![Dependency {
+        ref_to: group_count.clone(),
+        envvar_name: "GROUP_COUNT".to_string(),
+        condition: None,
+    }];
+    for (i, group_result) in group_results.iter().enumerate() {
+        summarize_dependencies.push(Dependency {
+            ref_to: group_result.clone(),
+            envvar_name: format!("GROUP_OUTPUT_JSON_{}", i),
+            condition: None,
+        });
+    }
+    let summarize_result = builder.add_execution(Execution {
+        name: job_name::SUMMARY_PHASE.to_string(),
+        script_name: summarize_script.clone(),
+        dependencies: summarize_dependencies,
+        time_reserved_ms: SUMMARIZE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
+    })?;
+    builder.set_metadata(&summarize_result, JobMetadata::new(JobRole::Summary))?;
+    Ok(builder.get_procedure())
+}
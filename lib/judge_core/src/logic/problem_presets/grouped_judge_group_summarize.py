@@ -0,0 +1,54 @@
+#!/usr/bin/env python3-traopy
+from traopy_util.util import v0 as trau  # type: ignore[reportMissingModuleSource]
+from traopy_util.util import common as trau_common  # type: ignore[reportMissingModuleSource]
+import os
+
+# Testcase scores are out of this value
+TESTCASE_FULL_SCORE = 100
+
+testcase_count = int(trau_common.read_file_with_envvar("TESTCASE_COUNT"))
+group_point = int(trau_common.read_file_with_envvar("GROUP_POINT"))
+group_policy = trau_common.read_file_with_envvar("GROUP_POLICY").strip()
+
+results: list[trau.ExecutionResult] = []
+judge_statuses: list[trau.JudgeStatus] = []
+for i in range(testcase_count):
+    json_path = f"{os.environ.get(f'OUTPUT_JSON_{i}')}/out.json"
+    with open(json_path, "r") as f:
+        json = f.read()
+    result = trau.dejsonify_output(json)
+    if result is None:
+        result = trau.ExecutionResult(
+            status=trau.JudgeStatus.WE,
+            time=0.0,
+            memory=0.0,
+            score=0,
+        )
+    results.append(result)
+    judge_statuses.append(result.status)
+
+status = trau.merge_judge_status(judge_statuses)
+time = max((result.time for result in results), default=0.0)
+memory = max((result.memory for result in results), default=0.0)
+if group_policy == "allOrNothing":
+    score = group_point if status == trau.JudgeStatus.AC else 0
+elif group_policy == "min":
+    min_score = min((result.score for result in results), default=TESTCASE_FULL_SCORE)
+    score = group_point * min_score // TESTCASE_FULL_SCORE
+elif group_policy == "proportional":
+    total = sum(result.score for result in results)
+    score = group_point * total // (TESTCASE_FULL_SCORE * max(testcase_count, 1))
+else:
+    status = trau.JudgeStatus.WE
+    score = 0
+json = trau.jsonify_displayable_output(
+    status=status,
+    time_ms=time,
+    memory_kib=memory,
+    score=score,
+    continue_next=True,
+)
+print(json)
+outcome_path = os.environ.get(trau.exec_job_outcome_path_envvar())
+with open(f"{outcome_path}/out.json", "w") as f:
+    f.write(json)
@@ -0,0 +1,24 @@
+use crate::logic::problem_presets::normal_judge::NormalJudgeTestcase;
+use crate::logic::problem_presets::tool_judge::{ToolKind, create_tool_judge_procedure};
+use crate::model::*;
+
+/// Author-supplied interactor program
+pub struct InteractiveJudgeInteractor {
+    pub source: String,
+    pub language_tag: String,
+}
+
+static IJ_RUN_SCRIPT: &str = include_str!("./interactive_judge_run.py");
+
+pub fn create_interactive_judge_procedure(
+    testcases: Vec<NormalJudgeTestcase>,
+    interactor: InteractiveJudgeInteractor,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    create_tool_judge_procedure(
+        testcases,
+        ToolKind::Interactor,
+        interactor.source,
+        interactor.language_tag,
+        IJ_RUN_SCRIPT,
+    )
+}
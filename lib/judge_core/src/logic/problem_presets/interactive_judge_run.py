@@ -0,0 +1,143 @@
+#!/usr/bin/env python3-traopy
+from traopy_util.util import v0 as trau # type: ignore[reportMissingModuleSource]
+from traopy_util.util import common as trau_common # type: ignore[reportMissingModuleSource]
+import asyncio
+import os
+import subprocess
+
+INTERACTOR_TIME_LIMIT_MS = 10000
+INTERACTOR_MESSAGE_MAX_LEN = 1024
+
+def exec_details(exec_stats) -> dict:
+    return {
+        "stderr": exec_stats.stderr,
+        "signal": exec_stats.signal,
+        "cpu_time_ms": exec_stats.time_ms,
+        "wall_time_ms": exec_stats.wall_time_ms,
+    }
+
+# Interactor protocol:
+#   invoked as `<interactor> <input> <expected output>`
+#   its stdout is the contestant's stdin and its stdin is the contestant's stdout
+#   exit code 0 -> AC, 1 -> WA, anything else -> WE
+#   stderr (optional): message shown to the contestant
+async def main():
+    language_tag = trau_common.read_file_with_envvar("LANGUAGE_TAG")
+    interactor_language_tag = trau_common.read_file_with_envvar("INTERACTOR_LANGUAGE_TAG").strip()
+    time_limit_ms = int(trau_common.read_file_with_envvar("TIME_LIMIT_MS"))
+    memory_limit_kib = int(trau_common.read_file_with_envvar("MEMORY_LIMIT_KIB"))
+    input_file_path = os.environ.get("INPUT_FILE")
+    expected_file_path = os.environ.get("EXPECTED_FILE")
+    temp_dir = os.environ.get('TEMP_DIR')
+    interactor_stderr_path = f"{temp_dir}/interactor_stderr.txt"
+    source_path = os.environ.get("BUILD_SOURCE_PATH")
+    build_output_path = os.environ.get("BUILD_OUTPUT_PATH")
+    interactor_source_path = os.environ.get("INTERACTOR_SOURCE_PATH")
+    interactor_build_output_path = os.environ.get("INTERACTOR_BUILD_OUTPUT_PATH")
+
+    language_info = trau.get_language_info(language_tag)
+    interactor_info = trau.get_language_info(interactor_language_tag)
+    command = f"sudo -u participant {language_info.run}"
+    interactor_command = (
+        f"{interactor_info.run} {input_file_path} {expected_file_path}"
+        f" 2> {interactor_stderr_path}"
+    )
+
+    subprocess.run(["useradd", "participant"], check=True)
+    subprocess.run(["chmod", "777", f"{build_output_path}/main.out"], check=True)
+    subprocess.run(["chmod", "777", source_path], check=True)
+    subprocess.run(["chmod", "777", temp_dir], check=True)
+    exec_stats = await trau.exec_interactive_with_stats(
+        cmd=command,
+        envs={
+            trau.build_output_envvar(): f"{build_output_path}/main.out",
+            trau.build_source_envvar(): source_path,
+        },
+        interactor_cmd=interactor_command,
+        interactor_envs={
+            trau.build_output_envvar(): f"{interactor_build_output_path}/interactor.out",
+            trau.build_source_envvar(): interactor_source_path,
+        },
+        time_limit_ms=2000,
+        interactor_time_limit_ms=INTERACTOR_TIME_LIMIT_MS,
+    )
+
+    message = None
+    if os.path.exists(interactor_stderr_path):
+        with open(interactor_stderr_path, "r", errors="replace") as f:
+            message = f.read().strip()[:INTERACTOR_MESSAGE_MAX_LEN] or None
+
+    # Limits are checked against the contestant process only
+    if exec_stats is None:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.TLE,
+            time_ms=2000,
+            memory_kib=0,
+            score=0,
+            continue_next=True,
+        )
+    elif exec_stats.time_ms > time_limit_ms:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.TLE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    elif exec_stats.memory_kib > memory_limit_kib:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.MLE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    # An interactor that rejects early closes the pipe, so WA takes priority over RE
+    elif exec_stats.interactor_exit_code == 1:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.WA,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+            message=message,
+        )
+    elif exec_stats.exit_code != 0:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.RE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    elif exec_stats.interactor_exit_code == 0:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.AC,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=100,
+            continue_next=True,
+            message=message,
+        )
+    else:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.WE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+            message=message,
+        )
+    print(json)
+    outcome_path = os.environ.get(trau.exec_job_outcome_path_envvar())
+    with open(f"{outcome_path}/out.json", "w") as f:
+        f.write(json)
+
+if __name__ == "__main__":
+    asyncio.run(main())
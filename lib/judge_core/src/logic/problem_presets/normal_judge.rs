@@ -1,24 +1,53 @@
 use crate::constant::label::single_judge;
 use crate::constant::*;
 use crate::logic::procedure_builder::ProcedureBuilder;
-use crate::model::{procedure::writer_schema::*, *};
+use crate::model::builtin_checker::BuiltinChecker;
+use crate::model::{
+    procedure::{job_metadata::*, writer_schema::*},
+    *,
+};
 
+#[derive(Clone)]
 pub struct NormalJudgeTestcase {
     pub name: String,
     pub input: String,
     pub expected_output: String,
+    /// Name of the testcase group, used by the grouped judge preset
+    pub group: Option<String>,
 }
 
 static NJ_BUILD_SCRIPT: &str = include_str!("./normal_judge_build.py");
 static NJ_RUN_SCRIPT: &str = include_str!("./normal_judge_run.py");
+static NJ_BATCH_RUN_SCRIPT: &str = include_str!("./normal_judge_batch_run.py");
 static NJ_SUMMARIZE_SCRIPT: &str = include_str!("./normal_judge_summarize.py");
 
 static COMPILE_PHASE_TIME_RESERVED_MS: i64 = 30000;
 static TEST_PHASE_TIME_RESERVED_MS: i64 = 11000;
 static SUMMARIZE_PHASE_TIME_RESERVED_MS: i64 = 2000;
 
+/// Name of the text holding the spec of the built-in checker
+pub const CHECKER_TEXT_NAME: &str = "checker";
+
 pub fn create_normal_judge_procedure(
     testcases: Vec<NormalJudgeTestcase>,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    create_normal_judge_procedure_with_checker(testcases, BuiltinChecker::Exact)
+}
+
+/// Same as `create_normal_judge_procedure`, but compares outputs with one of the built-in checkers
+pub fn create_normal_judge_procedure_with_checker(
+    testcases: Vec<NormalJudgeTestcase>,
+    checker: BuiltinChecker,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    create_batched_normal_judge_procedure(testcases, checker, 1)
+}
+
+/// Runs up to `batch_size` testcases one after another in each test execution,
+/// a `batch_size` of 1 keeps one execution per testcase
+pub fn create_batched_normal_judge_procedure(
+    testcases: Vec<NormalJudgeTestcase>,
+    checker: BuiltinChecker,
+    batch_size: usize,
 ) -> anyhow::Result<procedure::writer_schema::Procedure> {
     // 1st codeblock of builder.ipynb
     let mut builder = ProcedureBuilder::new();
@@ -38,6 +67,10 @@ pub fn create_normal_judge_procedure(
         name: "memory_limit".to_string(),
         label: single_judge::MEMORY_LIMIT_KIB.to_string(),
     }))?;
+    let checker = builder.add_resource(ResourceKind::TextFile(Text {
+        name: CHECKER_TEXT_NAME.to_string(),
+        content: checker.to_string(),
+    }))?;
     // 2nd codeblock of builder.ipynb
     let build_script = builder.add_script(Text {
         name: "build_script".to_string(),
@@ -45,7 +78,11 @@ pub fn create_normal_judge_procedure(
     })?;
     let run_script = builder.add_script(Text {
         name: "run_script".to_string(),
-        content: NJ_RUN_SCRIPT.to_string(),
+        content: if batch_size > 1 {
+            NJ_BATCH_RUN_SCRIPT.to_string()
+        } else {
+            NJ_RUN_SCRIPT.to_string()
+        },
     })?;
     let summarize_script = builder.add_script(Text {
         name: "summarize_script".to_string(),
@@ -62,77 +99,215 @@ pub fn create_normal_judge_procedure(
             Dependency {
                 ref_to: source.clone(),
                 envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                condition: None,
             },
             Dependency {
                 ref_to: lang_tag.clone(),
                 envvar_name: "LANGUAGE_TAG".to_string(),
+                condition: None,
             },
             Dependency {
                 ref_to: build_tempdir.clone(),
                 envvar_name: "BUILD_TEMPDIR".to_string(),
+                condition: None,
             },
         ],
         time_reserved_ms: COMPILE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
     })?;
+    builder.set_metadata(&build_result, JobMetadata::new(JobRole::Compile))?;
     // 4th codeblock of builder.ipynb
     let mut test_results = Vec::new();
-    for testcase in testcases.iter() {
-        let input_file = builder.add_resource(ResourceKind::TextFile(Text {
-            name: job_name::v0_features::testcase_input_name(&testcase.name),
-            content: testcase.input.clone(),
-        }))?;
-        let expected_file = builder.add_resource(ResourceKind::TextFile(Text {
-            name: job_name::v0_features::testcase_expected_name(&testcase.name),
-            content: testcase.expected_output.clone(),
-        }))?;
-        let tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
-            name: format!("tempdir_{}", testcase.name),
-        }))?;
-        let test_result = builder.add_execution(Execution {
-            name: job_name::test_phase_execution_job_name(&testcase.name),
-            script_name: run_script.clone(),
-            dependencies: vec![
+    if batch_size > 1 {
+        for (index, batch) in testcases.chunks(batch_size).enumerate() {
+            let testcase_names = builder.add_resource(ResourceKind::TextFile(Text {
+                name: job_name::test_batch_testcase_names_name(index),
+                content: batch
+                    .iter()
+                    .map(|testcase| testcase.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }))?;
+            let tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+                name: job_name::test_batch_tempdir_name(index),
+            }))?;
+            let batch_names = batch
+                .iter()
+                .map(|testcase| testcase.name.clone())
+                .collect::<Vec<_>>();
+            builder.set_metadata(
+                &tempdir,
+                JobMetadata::new(JobRole::TestcaseTempdir).with_testcases(batch_names.clone()),
+            )?;
+            let mut dependencies = vec![
                 Dependency {
                     ref_to: lang_tag.clone(),
                     envvar_name: "LANGUAGE_TAG".to_string(),
+                    condition: None,
                 },
                 Dependency {
                     ref_to: time_limit.clone(),
                     envvar_name: "TIME_LIMIT_MS".to_string(),
+                    condition: None,
                 },
                 Dependency {
                     ref_to: memory_limit.clone(),
                     envvar_name: "MEMORY_LIMIT_KIB".to_string(),
+                    condition: None,
                 },
                 Dependency {
-                    ref_to: input_file.clone(),
-                    envvar_name: "INPUT_FILE".to_string(),
+                    ref_to: testcase_names,
+                    envvar_name: "TESTCASE_NAMES".to_string(),
+                    condition: None,
                 },
                 Dependency {
-                    ref_to: expected_file.clone(),
-                    envvar_name: "EXPECTED_FILE".to_string(),
-                },
-                Dependency {
-                    ref_to: tempdir.clone(),
+                    ref_to: tempdir,
                     envvar_name: "TEMP_DIR".to_string(),
+                    condition: None,
                 },
                 Dependency {
                     ref_to: build_result.clone(),
                     envvar_name: "BUILD_OUTPUT_PATH".to_string(),
+                    condition: None,
                 },
                 Dependency {
                     ref_to: source.clone(),
                     envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: checker.clone(),
+                    envvar_name: "CHECKER".to_string(),
+                    condition: None,
                 },
-            ],
-            time_reserved_ms: TEST_PHASE_TIME_RESERVED_MS as u64,
-        })?;
-        test_results.push(test_result);
+            ];
+            for (j, testcase) in batch.iter().enumerate() {
+                let input_file = builder.add_resource(ResourceKind::TextFile(Text {
+                    name: job_name::v0_features::testcase_input_name(&testcase.name),
+                    content: testcase.input.clone(),
+                }))?;
+                let expected_file = builder.add_resource(ResourceKind::TextFile(Text {
+                    name: job_name::v0_features::testcase_expected_name(&testcase.name),
+                    content: testcase.expected_output.clone(),
+                }))?;
+                for (name, role) in [
+                    (&input_file, JobRole::TestcaseInput),
+                    (&expected_file, JobRole::TestcaseExpected),
+                ] {
+                    builder.set_metadata(
+                        name,
+                        JobMetadata::new(role).with_testcases(vec![testcase.name.clone()]),
+                    )?;
+                }
+                dependencies.push(Dependency {
+                    ref_to: input_file,
+                    envvar_name: format!("INPUT_FILE_{}", j),
+                    condition: None,
+                });
+                dependencies.push(Dependency {
+                    ref_to: expected_file,
+                    envvar_name: format!("EXPECTED_FILE_{}", j),
+                    condition: None,
+                });
+            }
+            let test_result = builder.add_execution(Execution {
+                name: job_name::test_batch_execution_job_name(index),
+                script_name: run_script.clone(),
+                dependencies,
+                // テストケースを順番に実行するので、その件数分だけ時間を確保する
+                time_reserved_ms: (TEST_PHASE_TIME_RESERVED_MS * batch.len() as i64) as u64,
+                // 実行時間を測るので、キャッシュせず毎回実行する
+                no_cache: true,
+            })?;
+            builder.set_metadata(
+                &test_result,
+                JobMetadata::new(JobRole::Test)
+                    .with_testcases(batch_names)
+                    .with_display_order((index * batch_size) as u32),
+            )?;
+            test_results.push(test_result);
+        }
+    } else {
+        for (i, testcase) in testcases.iter().enumerate() {
+            let input_file = builder.add_resource(ResourceKind::TextFile(Text {
+                name: job_name::v0_features::testcase_input_name(&testcase.name),
+                content: testcase.input.clone(),
+            }))?;
+            let expected_file = builder.add_resource(ResourceKind::TextFile(Text {
+                name: job_name::v0_features::testcase_expected_name(&testcase.name),
+                content: testcase.expected_output.clone(),
+            }))?;
+            let tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+                name: job_name::testcase_tempdir_name(&testcase.name),
+            }))?;
+            let test_result = builder.add_execution(Execution {
+                name: job_name::test_phase_execution_job_name(&testcase.name),
+                script_name: run_script.clone(),
+                dependencies: vec![
+                    Dependency {
+                        ref_to: lang_tag.clone(),
+                        envvar_name: "LANGUAGE_TAG".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: time_limit.clone(),
+                        envvar_name: "TIME_LIMIT_MS".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: memory_limit.clone(),
+                        envvar_name: "MEMORY_LIMIT_KIB".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: input_file.clone(),
+                        envvar_name: "INPUT_FILE".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: expected_file.clone(),
+                        envvar_name: "EXPECTED_FILE".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: tempdir.clone(),
+                        envvar_name: "TEMP_DIR".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: build_result.clone(),
+                        envvar_name: "BUILD_OUTPUT_PATH".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: source.clone(),
+                        envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                        condition: None,
+                    },
+                    Dependency {
+                        ref_to: checker.clone(),
+                        envvar_name: "CHECKER".to_string(),
+                        condition: None,
+                    },
+                ],
+                time_reserved_ms: TEST_PHASE_TIME_RESERVED_MS as u64,
+                // 実行時間を測るので、キャッシュせず毎回実行する
+                no_cache: true,
+            })?;
+            super::set_testcase_metadata(
+                &mut builder,
+                testcase,
+                i as u32,
+                [&input_file, &expected_file, &tempdir, &test_result],
+            )?;
+            test_results.push(test_result);
+        }
     }
     // 5th codeblock of builder.ipynb
     let testcase_count = builder.add_resource(ResourceKind::TextFile(Text {
         name: "testcase_count".to_string(),
-        content: testcases.len().to_string(),
+        // まとめて実行した場合は実行の数だけ結果を読む
+        content: test_results.len().to_string(),
     }))?;
     let ac_point = builder.add_resource(ResourceKind::TextFile(Text {
         name: "ac_point".to_string(),
@@ -142,23 +317,28 @@ pub fn create_normal_judge_procedure(
         Dependency {
             ref_to: testcase_count.clone(),
             envvar_name: "TESTCASE_COUNT".to_string(),
+            condition: None,
         },
         Dependency {
             ref_to: ac_point.clone(),
             envvar_name: "AC_POINT".to_string(),
+            condition: None,
         },
     ];
     for i in 0..test_results.len() {
         summarize_dependencies.push(Dependency {
             ref_to: test_results[i].clone(),
             envvar_name: format!("OUTPUT_JSON_{}", i),
+            condition: None,
         });
     }
-    let _summarize_result = builder.add_execution(Execution {
+    let summarize_result = builder.add_execution(Execution {
         name: job_name::SUMMARY_PHASE.to_string(),
         script_name: summarize_script.clone(),
         dependencies: summarize_dependencies,
         time_reserved_ms: SUMMARIZE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
     })?;
+    builder.set_metadata(&summarize_result, JobMetadata::new(JobRole::Summary))?;
     return Ok(builder.get_procedure());
 }
@@ -0,0 +1,115 @@
+#!/usr/bin/env python3-traopy
+from traopy_util.util import v0 as trau # type: ignore[reportMissingModuleSource]
+from traopy_util.util import common as trau_common # type: ignore[reportMissingModuleSource]
+import asyncio
+import os
+import subprocess
+
+def exec_details(exec_stats) -> dict:
+    return {
+        "stderr": exec_stats.stderr,
+        "signal": exec_stats.signal,
+        "cpu_time_ms": exec_stats.time_ms,
+        "wall_time_ms": exec_stats.wall_time_ms,
+    }
+
+async def judge_one(language_info, time_limit_ms: int, memory_limit_kib: int, checker_spec, index: int) -> str:
+    input_file_path = os.environ.get(f"INPUT_FILE_{index}")
+    expected_file = trau_common.read_file_with_envvar(f"EXPECTED_FILE_{index}")
+    output_file_path = f"{os.environ.get('TEMP_DIR')}/output_{index}.txt"
+    source_path = os.environ.get("BUILD_SOURCE_PATH")
+    build_output_path = os.environ.get("BUILD_OUTPUT_PATH")
+    command = f"sudo -u participant {language_info.run} < {input_file_path} > {output_file_path}"
+
+    subprocess.run(["chmod", "777", input_file_path], check=True)
+    exec_stats = await trau.exec_with_stats(
+        cmd=command,
+        envs={
+            trau.build_output_envvar(): f"{build_output_path}/main.out",
+            trau.build_source_envvar(): source_path,
+        },
+        time_limit_ms=2000,
+    )
+
+    if exec_stats is None:
+        return trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.TLE,
+            time_ms=2000,
+            memory_kib=0,
+            score=0,
+            continue_next=True,
+        )
+    if exec_stats.time_ms > time_limit_ms:
+        return trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.TLE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    if exec_stats.exit_code != 0:
+        return trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.RE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    if exec_stats.memory_kib > memory_limit_kib:
+        return trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.MLE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    with open(output_file_path, "r") as f:
+        output_file = f.read()
+    verdict = trau_common.run_checker(
+        spec=checker_spec,
+        expected=expected_file,
+        actual=output_file,
+    )
+    return trau.jsonify_displayable_output(
+        status=trau.JudgeStatus.AC if verdict.accepted else trau.JudgeStatus.WA,
+        time_ms=exec_stats.time_ms,
+        memory_kib=exec_stats.memory_kib,
+        **exec_details(exec_stats),
+        score=100 if verdict.accepted else 0,
+        continue_next=True,
+        message=None if verdict.accepted else verdict.message,
+    )
+
+async def main():
+    language_tag = trau_common.read_file_with_envvar("LANGUAGE_TAG")
+    time_limit_ms = int(trau_common.read_file_with_envvar("TIME_LIMIT_MS"))
+    memory_limit_kib = int(trau_common.read_file_with_envvar("MEMORY_LIMIT_KIB"))
+    testcase_names = trau_common.read_file_with_envvar("TESTCASE_NAMES").splitlines()
+    source_path = os.environ.get("BUILD_SOURCE_PATH")
+    build_output_path = os.environ.get("BUILD_OUTPUT_PATH")
+    checker_spec = trau_common.read_file_with_envvar("CHECKER").strip()
+
+    language_info = trau.get_language_info(language_tag)
+
+    subprocess.run(["useradd", "participant"], check=True)
+    subprocess.run(["chmod", "777", f"{build_output_path}/main.out"], check=True)
+    subprocess.run(["chmod", "777", source_path], check=True)
+    subprocess.run(["chmod", "777", os.environ.get('TEMP_DIR')], check=True)
+
+    # The testcases share this instance and run one after another
+    results: list[tuple[str, str]] = []
+    for (index, name) in enumerate(testcase_names):
+        json = await judge_one(language_info, time_limit_ms, memory_limit_kib, checker_spec, index)
+        results.append((name, json))
+
+    json = trau.jsonify_batched_output(results=results, continue_next=True)
+    print(json)
+    outcome_path = os.environ.get(trau.exec_job_outcome_path_envvar())
+    with open(f"{outcome_path}/out.json", "w") as f:
+        f.write(json)
+
+if __name__ == "__main__":
+    asyncio.run(main())
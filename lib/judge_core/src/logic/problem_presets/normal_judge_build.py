@@ -35,6 +35,7 @@ async def main():
                 memory_kib=exec_stats.memory_kib,
                 score=0,
                 continue_next=False,
+                compile_log=exec_stats.stderr,
             )
         else:
             json = trau.jsonify_displayable_output(
@@ -43,6 +44,7 @@ async def main():
                 memory_kib=exec_stats.memory_kib,
                 score=0,
                 continue_next=True,
+                compile_log=exec_stats.stderr,
             )
 
     print(json)
@@ -5,6 +5,14 @@ import asyncio
 import os
 import subprocess
 
+def exec_details(exec_stats) -> dict:
+    return {
+        "stderr": exec_stats.stderr,
+        "signal": exec_stats.signal,
+        "cpu_time_ms": exec_stats.time_ms,
+        "wall_time_ms": exec_stats.wall_time_ms,
+    }
+
 async def main():
     language_tag = trau_common.read_file_with_envvar("LANGUAGE_TAG")
     time_limit_ms = int(trau_common.read_file_with_envvar("TIME_LIMIT_MS"))
@@ -14,6 +22,10 @@ async def main():
     output_file_path = f"{os.environ.get('TEMP_DIR')}/output.txt"
     source_path = os.environ.get("BUILD_SOURCE_PATH")
     build_output_path = os.environ.get("BUILD_OUTPUT_PATH")
+    # Presets without a CHECKER dependency fall back to the exact checker
+    checker_spec = None
+    if os.environ.get("CHECKER") is not None:
+        checker_spec = trau_common.read_file_with_envvar("CHECKER").strip()
 
     language_info = trau.get_language_info(language_tag)
     command = f"sudo -u participant {language_info.run} < {input_file_path} > {output_file_path}"
@@ -47,6 +59,7 @@ async def main():
                 status=trau.JudgeStatus.TLE,
                 time_ms=exec_stats.time_ms,
                 memory_kib=exec_stats.memory_kib,
+                **exec_details(exec_stats),
                 score=0,
                 continue_next=True,
             )
@@ -55,6 +68,7 @@ async def main():
                 status=trau.JudgeStatus.RE,
                 time_ms=exec_stats.time_ms,
                 memory_kib=exec_stats.memory_kib,
+                **exec_details(exec_stats),
                 score=0,
                 continue_next=True,
             )
@@ -63,6 +77,7 @@ async def main():
                 status=trau.JudgeStatus.MLE,
                 time_ms=exec_stats.time_ms,
                 memory_kib=exec_stats.memory_kib,
+                **exec_details(exec_stats),
                 score=0,
                 continue_next=True,
             )
@@ -70,14 +85,26 @@ async def main():
             output_file_path = f"{os.environ.get('TEMP_DIR')}/output.txt"
             with open(output_file_path, "r") as f:
                 output_file = f.read()
-            if trau_common.normal_judge_checker(
-                expected=expected_file,
-                actual=output_file,
-            ):
+            if checker_spec is None:
+                accepted = trau_common.normal_judge_checker(
+                    expected=expected_file,
+                    actual=output_file,
+                )
+                message = None
+            else:
+                verdict = trau_common.run_checker(
+                    spec=checker_spec,
+                    expected=expected_file,
+                    actual=output_file,
+                )
+                accepted = verdict.accepted
+                message = verdict.message
+            if accepted:
                 json = trau.jsonify_displayable_output(
                     status=trau.JudgeStatus.AC,
                     time_ms=exec_stats.time_ms,
                     memory_kib=exec_stats.memory_kib,
+                    **exec_details(exec_stats),
                     score=100,
                     continue_next=True,
                 )
@@ -86,8 +113,10 @@ async def main():
                     status=trau.JudgeStatus.WA,
                     time_ms=exec_stats.time_ms,
                     memory_kib=exec_stats.memory_kib,
+                    **exec_details(exec_stats),
                     score=0,
                     continue_next=True,
+                    message=message,
                 )
     print(json)
     outcome_path = os.environ.get(trau.exec_job_outcome_path_envvar())
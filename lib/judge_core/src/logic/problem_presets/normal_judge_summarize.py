@@ -12,6 +12,13 @@ for i in range(testcase_count):
     json_path = f"{os.environ.get(f'OUTPUT_JSON_{i}')}/out.json"
     with open(json_path, "r") as f:
         json = f.read()
+    # A batched execution carries the results of several testcases
+    batched = trau.dejsonify_batched_output(json)
+    if batched is not None:
+        for (_name, result) in batched:
+            results.append(result)
+            judge_statuses.append(result.status)
+        continue
     result = trau.dejsonify_output(json)
     if result is None:
         result = trau.ExecutionResult(
@@ -0,0 +1,24 @@
+use crate::logic::problem_presets::normal_judge::NormalJudgeTestcase;
+use crate::logic::problem_presets::tool_judge::{ToolKind, create_tool_judge_procedure};
+use crate::model::*;
+
+/// Author-supplied checker program
+pub struct SpecialJudgeChecker {
+    pub source: String,
+    pub language_tag: String,
+}
+
+static SJ_RUN_SCRIPT: &str = include_str!("./special_judge_run.py");
+
+pub fn create_special_judge_procedure(
+    testcases: Vec<NormalJudgeTestcase>,
+    checker: SpecialJudgeChecker,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    create_tool_judge_procedure(
+        testcases,
+        ToolKind::Checker,
+        checker.source,
+        checker.language_tag,
+        SJ_RUN_SCRIPT,
+    )
+}
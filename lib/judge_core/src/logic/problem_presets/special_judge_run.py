@@ -0,0 +1,153 @@
+#!/usr/bin/env python3-traopy
+from traopy_util.util import v0 as trau # type: ignore[reportMissingModuleSource]
+from traopy_util.util import common as trau_common # type: ignore[reportMissingModuleSource]
+import asyncio
+import os
+import subprocess
+
+CHECKER_TIME_LIMIT_MS = 10000
+CHECKER_MESSAGE_MAX_LEN = 1024
+
+# Checker protocol:
+#   invoked as `<checker> <input> <contestant output> <expected output>`
+#   exit code 0 -> AC, 1 -> WA, anything else -> WE
+#   stdout (optional): score as the first token, defaults to 100 on AC and 0 otherwise
+#   stderr (optional): message shown to the contestant
+def parse_checker_score(stdout: str, default: int) -> int | None:
+    tokens = stdout.split()
+    if len(tokens) == 0:
+        return default
+    try:
+        return int(tokens[0])
+    except ValueError:
+        return None
+
+def exec_details(exec_stats) -> dict:
+    return {
+        "stderr": exec_stats.stderr,
+        "signal": exec_stats.signal,
+        "cpu_time_ms": exec_stats.time_ms,
+        "wall_time_ms": exec_stats.wall_time_ms,
+    }
+
+async def main():
+    language_tag = trau_common.read_file_with_envvar("LANGUAGE_TAG")
+    checker_language_tag = trau_common.read_file_with_envvar("CHECKER_LANGUAGE_TAG").strip()
+    time_limit_ms = int(trau_common.read_file_with_envvar("TIME_LIMIT_MS"))
+    memory_limit_kib = int(trau_common.read_file_with_envvar("MEMORY_LIMIT_KIB"))
+    input_file_path = os.environ.get("INPUT_FILE")
+    expected_file_path = os.environ.get("EXPECTED_FILE")
+    temp_dir = os.environ.get('TEMP_DIR')
+    output_file_path = f"{temp_dir}/output.txt"
+    checker_stdout_path = f"{temp_dir}/checker_stdout.txt"
+    checker_stderr_path = f"{temp_dir}/checker_stderr.txt"
+    source_path = os.environ.get("BUILD_SOURCE_PATH")
+    build_output_path = os.environ.get("BUILD_OUTPUT_PATH")
+    checker_source_path = os.environ.get("CHECKER_SOURCE_PATH")
+    checker_build_output_path = os.environ.get("CHECKER_BUILD_OUTPUT_PATH")
+
+    language_info = trau.get_language_info(language_tag)
+    command = f"sudo -u participant {language_info.run} < {input_file_path} > {output_file_path}"
+
+    subprocess.run(["useradd", "participant"], check=True)
+    subprocess.run(["chmod", "777", f"{build_output_path}/main.out"], check=True)
+    subprocess.run(["chmod", "777", source_path], check=True)
+    subprocess.run(["chmod", "777", input_file_path], check=True)
+    subprocess.run(["chmod", "777", temp_dir], check=True)
+    exec_stats = await trau.exec_with_stats(
+        cmd=command,
+        envs={
+            trau.build_output_envvar(): f"{build_output_path}/main.out",
+            trau.build_source_envvar(): source_path,
+        },
+        time_limit_ms=2000,
+    )
+
+    if exec_stats is None:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.TLE,
+            time_ms=2000,
+            memory_kib=0,
+            score=0,
+            continue_next=True,
+        )
+    elif exec_stats.time_ms > time_limit_ms:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.TLE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    elif exec_stats.exit_code != 0:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.RE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    elif exec_stats.memory_kib > memory_limit_kib:
+        json = trau.jsonify_displayable_output(
+            status=trau.JudgeStatus.MLE,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=0,
+            continue_next=True,
+        )
+    else:
+        checker_info = trau.get_language_info(checker_language_tag)
+        checker_command = (
+            f"{checker_info.run} {input_file_path} {output_file_path} {expected_file_path}"
+            f" > {checker_stdout_path} 2> {checker_stderr_path}"
+        )
+        checker_stats = await trau.exec_with_stats(
+            cmd=checker_command,
+            envs={
+                trau.build_output_envvar(): f"{checker_build_output_path}/checker.out",
+                trau.build_source_envvar(): checker_source_path,
+            },
+            time_limit_ms=CHECKER_TIME_LIMIT_MS,
+        )
+        checker_stdout = ""
+        checker_stderr = ""
+        if os.path.exists(checker_stdout_path):
+            with open(checker_stdout_path, "r", errors="replace") as f:
+                checker_stdout = f.read()
+        if os.path.exists(checker_stderr_path):
+            with open(checker_stderr_path, "r", errors="replace") as f:
+                checker_stderr = f.read()
+        message = checker_stderr.strip()[:CHECKER_MESSAGE_MAX_LEN] or None
+
+        if checker_stats is None or checker_stats.exit_code not in (0, 1):
+            status = trau.JudgeStatus.WE
+            score = 0
+        elif checker_stats.exit_code == 0:
+            status = trau.JudgeStatus.AC
+            score = parse_checker_score(checker_stdout, 100)
+        else:
+            status = trau.JudgeStatus.WA
+            score = parse_checker_score(checker_stdout, 0)
+        if score is None:
+            status = trau.JudgeStatus.WE
+            score = 0
+            message = "Checker printed an invalid score"
+        json = trau.jsonify_displayable_output(
+            status=status,
+            time_ms=exec_stats.time_ms,
+            memory_kib=exec_stats.memory_kib,
+            **exec_details(exec_stats),
+            score=score,
+            continue_next=True,
+            message=message,
+        )
+    print(json)
+    outcome_path = os.environ.get(trau.exec_job_outcome_path_envvar())
+    with open(f"{outcome_path}/out.json", "w") as f:
+        f.write(json)
+
+if __name__ == "__main__":
+    asyncio.run(main())
@@ -0,0 +1,290 @@
+use crate::constant::label::single_judge;
+use crate::constant::*;
+use crate::logic::problem_presets::normal_judge::NormalJudgeTestcase;
+use crate::logic::procedure_builder::ProcedureBuilder;
+use crate::model::{
+    procedure::{job_metadata::*, writer_schema::*},
+    *,
+};
+
+/// Author-supplied program that judges each test run alongside the submission
+pub(super) enum ToolKind {
+    Checker,
+    Interactor,
+}
+
+impl ToolKind {
+    fn name(&self) -> &'static str {
+        match self {
+            ToolKind::Checker => "checker",
+            ToolKind::Interactor => "interactor",
+        }
+    }
+
+    fn envvar_prefix(&self) -> &'static str {
+        match self {
+            ToolKind::Checker => "CHECKER",
+            ToolKind::Interactor => "INTERACTOR",
+        }
+    }
+
+    fn compile_phase(&self) -> &'static str {
+        match self {
+            ToolKind::Checker => job_name::CHECKER_COMPILE_PHASE,
+            ToolKind::Interactor => job_name::INTERACTOR_COMPILE_PHASE,
+        }
+    }
+}
+
+static TJ_BUILD_SCRIPT: &str = include_str!("./normal_judge_build.py");
+static TJ_TOOL_BUILD_SCRIPT: &str = include_str!("./tool_judge_build.py");
+static TJ_SUMMARIZE_SCRIPT: &str = include_str!("./normal_judge_summarize.py");
+
+static COMPILE_PHASE_TIME_RESERVED_MS: i64 = 30000;
+static TEST_PHASE_TIME_RESERVED_MS: i64 = 21000;
+static SUMMARIZE_PHASE_TIME_RESERVED_MS: i64 = 2000;
+
+/// Builds the submission and the tool once, then runs `run_script` for each testcase
+///
+/// `run_script` finds the tool under `<PREFIX>_LANGUAGE_TAG`, `<PREFIX>_BUILD_OUTPUT_PATH`
+/// and `<PREFIX>_SOURCE_PATH`, where the prefix is `CHECKER` or `INTERACTOR`
+pub(super) fn create_tool_judge_procedure(
+    testcases: Vec<NormalJudgeTestcase>,
+    kind: ToolKind,
+    tool_source: String,
+    tool_language_tag: String,
+    run_script: &str,
+) -> anyhow::Result<procedure::writer_schema::Procedure> {
+    let tool = kind.name();
+    let prefix = kind.envvar_prefix();
+    let mut builder = ProcedureBuilder::new();
+    let source = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "source".to_string(),
+        label: single_judge::SUBMISSION_SOURCE.to_string(),
+    }))?;
+    let lang_tag = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "language_tag".to_string(),
+        label: single_judge::LANGUAGE_TAG.to_string(),
+    }))?;
+    let time_limit = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "time_limit".to_string(),
+        label: single_judge::TIME_LIMIT_MS.to_string(),
+    }))?;
+    let memory_limit = builder.add_resource(ResourceKind::RuntimeTextFile(RuntimeText {
+        name: "memory_limit".to_string(),
+        label: single_judge::MEMORY_LIMIT_KIB.to_string(),
+    }))?;
+    let tool_name = builder.add_resource(ResourceKind::TextFile(Text {
+        name: format!("{}_name", tool),
+        content: tool.to_string(),
+    }))?;
+    let tool_source = builder.add_resource(ResourceKind::TextFile(Text {
+        name: format!("{}_source", tool),
+        content: tool_source,
+    }))?;
+    let tool_lang_tag = builder.add_resource(ResourceKind::TextFile(Text {
+        name: format!("{}_language_tag", tool),
+        content: tool_language_tag,
+    }))?;
+    let build_script = builder.add_script(Text {
+        name: "build_script".to_string(),
+        content: TJ_BUILD_SCRIPT.to_string(),
+    })?;
+    let tool_build_script = builder.add_script(Text {
+        name: format!("{}_build_script", tool),
+        content: TJ_TOOL_BUILD_SCRIPT.to_string(),
+    })?;
+    let run_script = builder.add_script(Text {
+        name: "run_script".to_string(),
+        content: run_script.to_string(),
+    })?;
+    let summarize_script = builder.add_script(Text {
+        name: "summarize_script".to_string(),
+        content: TJ_SUMMARIZE_SCRIPT.to_string(),
+    })?;
+    // Build the submission
+    let build_tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+        name: "build_tempdir".to_string(),
+    }))?;
+    let build_result = builder.add_execution(Execution {
+        name: job_name::COMPILE_PHASE.to_string(),
+        script_name: build_script.clone(),
+        dependencies: vec![
+            Dependency {
+                ref_to: source.clone(),
+                envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: lang_tag.clone(),
+                envvar_name: "LANGUAGE_TAG".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: build_tempdir.clone(),
+                envvar_name: "BUILD_TEMPDIR".to_string(),
+                condition: None,
+            },
+        ],
+        time_reserved_ms: COMPILE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
+    })?;
+    builder.set_metadata(&build_result, JobMetadata::new(JobRole::Compile))?;
+    // Build the tool only once
+    let tool_build_tempdir =
+        builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+            name: format!("{}_build_tempdir", tool),
+        }))?;
+    let tool_build_result = builder.add_execution(Execution {
+        name: kind.compile_phase().to_string(),
+        script_name: tool_build_script.clone(),
+        dependencies: vec![
+            Dependency {
+                ref_to: tool_name.clone(),
+                envvar_name: "TOOL_NAME".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: tool_source.clone(),
+                envvar_name: "TOOL_SOURCE_PATH".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: tool_lang_tag.clone(),
+                envvar_name: "TOOL_LANGUAGE_TAG".to_string(),
+                condition: None,
+            },
+            Dependency {
+                ref_to: tool_build_tempdir.clone(),
+                envvar_name: "BUILD_TEMPDIR".to_string(),
+                condition: None,
+            },
+        ],
+        time_reserved_ms: COMPILE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
+    })?;
+    builder.set_metadata(&tool_build_result, JobMetadata::new(JobRole::ToolCompile))?;
+    // Run the submission together with the tool for each testcase
+    let mut test_results = Vec::new();
+    for (i, testcase) in testcases.iter().enumerate() {
+        let input_file = builder.add_resource(ResourceKind::TextFile(Text {
+            name: job_name::v0_features::testcase_input_name(&testcase.name),
+            content: testcase.input.clone(),
+        }))?;
+        let expected_file = builder.add_resource(ResourceKind::TextFile(Text {
+            name: job_name::v0_features::testcase_expected_name(&testcase.name),
+            content: testcase.expected_output.clone(),
+        }))?;
+        let tempdir = builder.add_resource(ResourceKind::EmptyDirectory(EmptyDirectory {
+            name: job_name::testcase_tempdir_name(&testcase.name),
+        }))?;
+        let test_result = builder.add_execution(Execution {
+            name: job_name::test_phase_execution_job_name(&testcase.name),
+            script_name: run_script.clone(),
+            dependencies: vec![
+                Dependency {
+                    ref_to: lang_tag.clone(),
+                    envvar_name: "LANGUAGE_TAG".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: time_limit.clone(),
+                    envvar_name: "TIME_LIMIT_MS".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: memory_limit.clone(),
+                    envvar_name: "MEMORY_LIMIT_KIB".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: input_file.clone(),
+                    envvar_name: "INPUT_FILE".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: expected_file.clone(),
+                    envvar_name: "EXPECTED_FILE".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: tempdir.clone(),
+                    envvar_name: "TEMP_DIR".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: build_result.clone(),
+                    envvar_name: "BUILD_OUTPUT_PATH".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: source.clone(),
+                    envvar_name: "BUILD_SOURCE_PATH".to_string(),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: tool_lang_tag.clone(),
+                    envvar_name: format!("{}_LANGUAGE_TAG", prefix),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: tool_build_result.clone(),
+                    envvar_name: format!("{}_BUILD_OUTPUT_PATH", prefix),
+                    condition: None,
+                },
+                Dependency {
+                    ref_to: tool_source.clone(),
+                    envvar_name: format!("{}_SOURCE_PATH", prefix),
+                    condition: None,
+                },
+            ],
+            time_reserved_ms: TEST_PHASE_TIME_RESERVED_MS as u64,
+            // 実行時間を測るので、キャッシュせず毎回実行する
+            no_cache: true,
+        })?;
+        super::set_testcase_metadata(
+            &mut builder,
+            testcase,
+            i as u32,
+            [&input_file, &expected_file, &tempdir, &test_result],
+        )?;
+        test_results.push(test_result);
+    }
+    // Summarize
+    let testcase_count = builder.add_resource(ResourceKind::TextFile(Text {
+        name: "testcase_count".to_string(),
+        content: testcases.len().to_string(),
+    }))?;
+    let ac_point = builder.add_resource(ResourceKind::TextFile(Text {
+        name: "ac_point".to_string(),
+        content: 100.to_string(),
+    }))?;
+    let mut summarize_dependencies = vec![
+        Dependency {
+            ref_to: testcase_count.clone(),
+            envvar_name: "TESTCASE_COUNT".to_string(),
+            condition: None,
+        },
+        Dependency {
+            ref_to: ac_point.clone(),
+            envvar_name: "AC_POINT".to_string(),
+            condition: None,
+        },
+    ];
+    for (i, test_result) in test_results.iter().enumerate() {
+        summarize_dependencies.push(Dependency {
+            ref_to: test_result.clone(),
+            envvar_name: format!("OUTPUT_JSON_{}", i),
+            condition: None,
+        });
+    }
+    let summarize_result = builder.add_execution(Execution {
+        name: job_name::SUMMARY_PHASE.to_string(),
+        script_name: summarize_script.clone(),
+        dependencies: summarize_dependencies,
+        time_reserved_ms: SUMMARIZE_PHASE_TIME_RESERVED_MS as u64,
+        no_cache: false,
+    })?;
+    builder.set_metadata(&summarize_result, JobMetadata::new(JobRole::Summary))?;
+    Ok(builder.get_procedure())
+}
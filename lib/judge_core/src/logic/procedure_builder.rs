@@ -1,5 +1,5 @@
-use crate::model::procedure::writer_schema::*;
-use std::collections::HashMap;
+use crate::model::procedure::{job_metadata::JobMetadata, writer_schema::*};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone)]
 enum Job {
@@ -12,22 +12,20 @@ enum Job {
 #[derive(Debug, Clone)]
 pub struct ProcedureBuilder {
     jobs: HashMap<String, Job>,
+    metadata: BTreeMap<String, JobMetadata>,
 }
 
 impl ProcedureBuilder {
     pub fn new() -> Self {
         ProcedureBuilder {
             jobs: HashMap::new(),
+            metadata: BTreeMap::new(),
         }
     }
 
     /// Add a resource to the procedure and return the name of the resource
     pub fn add_resource(&mut self, resource: ResourceKind) -> Result<String, AddJobError> {
-        let name = match &resource {
-            ResourceKind::EmptyDirectory(empty_directory) => empty_directory.name.clone(),
-            ResourceKind::RuntimeTextFile(runtime_text) => runtime_text.name.clone(),
-            ResourceKind::TextFile(text) => text.name.clone(),
-        };
+        let name = resource.name().to_string();
         self.jobs
             .insert(name.clone(), Job::Resource(resource))
             .map_or_else(
@@ -74,9 +72,19 @@ impl ProcedureBuilder {
         Ok(name)
     }
 
+    /// Attach metadata to a job that has already been added
+    pub fn set_metadata(&mut self, name: &str, metadata: JobMetadata) -> Result<(), AddJobError> {
+        if !self.jobs.contains_key(name) {
+            return Err(AddJobError::DependencyNotFound(name.to_string()));
+        }
+        self.metadata.insert(name.to_string(), metadata);
+        Ok(())
+    }
+
     /// Export the procedure
     pub fn get_procedure(&self) -> Procedure {
         Procedure {
+            version: SCHEMA_VERSION,
             resources: self
                 .jobs
                 .iter()
@@ -101,7 +109,31 @@ impl ProcedureBuilder {
                     _ => None,
                 })
                 .collect(),
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl From<Procedure> for ProcedureBuilder {
+    fn from(procedure: Procedure) -> Self {
+        let mut builder = ProcedureBuilder::new();
+        builder.metadata = procedure.metadata;
+        for resource in procedure.resources {
+            builder
+                .jobs
+                .insert(resource.name().to_string(), Job::Resource(resource));
+        }
+        for script in procedure.scripts {
+            builder
+                .jobs
+                .insert(script.name.clone(), Job::Script(script));
+        }
+        for execution in procedure.executions {
+            builder
+                .jobs
+                .insert(execution.name.clone(), Job::Execution(execution));
         }
+        builder
     }
 }
 
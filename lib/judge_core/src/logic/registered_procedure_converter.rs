@@ -13,6 +13,18 @@ pub fn convert(
         runtime_id_to_dep_id.insert(runtime_id.clone(), dep_id.clone());
         dep_id_to_runtime_id.insert(dep_id.clone(), runtime_id.clone());
     }
+    for binary in registered_procedure.binaries.iter() {
+        let runtime_id = RuntimeId::new();
+        let dep_id = binary.dep_id;
+        runtime_id_to_dep_id.insert(runtime_id, dep_id);
+        dep_id_to_runtime_id.insert(dep_id, runtime_id);
+    }
+    for directory in registered_procedure.directories.iter() {
+        let runtime_id = RuntimeId::new();
+        let dep_id = directory.dep_id;
+        runtime_id_to_dep_id.insert(runtime_id, dep_id);
+        dep_id_to_runtime_id.insert(dep_id, runtime_id);
+    }
     for empty_directory in registered_procedure.empty_directories.iter() {
         let runtime_id = RuntimeId::new();
         let dep_id = empty_directory.dep_id.clone();
@@ -32,6 +44,8 @@ pub fn convert(
         dep_id_to_runtime_id.insert(dep_id.clone(), runtime_id.clone());
     }
     let mut texts = Vec::new();
+    let mut binaries = Vec::new();
+    let mut directories = Vec::new();
     let mut runtime_texts = Vec::new();
     let mut empty_directories = Vec::new();
     let mut executions = Vec::new();
@@ -47,6 +61,24 @@ pub fn convert(
             runtime_id,
         });
     }
+    for binary in registered_procedure.binaries.iter() {
+        let runtime_id = dep_id_to_runtime_id.get(&binary.dep_id).copied().ok_or(
+            ConversionError::InternalError("Binary dep_id not found".to_string()),
+        )?;
+        binaries.push(runtime::Binary {
+            resource_id: binary.resource_id,
+            runtime_id,
+        });
+    }
+    for directory in registered_procedure.directories.iter() {
+        let runtime_id = dep_id_to_runtime_id.get(&directory.dep_id).copied().ok_or(
+            ConversionError::InternalError("Directory dep_id not found".to_string()),
+        )?;
+        directories.push(runtime::Directory {
+            resource_id: directory.resource_id,
+            runtime_id,
+        });
+    }
     for empty_directory in registered_procedure.empty_directories.iter() {
         let runtime_id = dep_id_to_runtime_id
             .get(&empty_directory.dep_id)
@@ -92,16 +124,20 @@ pub fn convert(
             dependencies.push(runtime::Dependency {
                 runtime_id,
                 envvar_name: dep.envvar_name.clone(),
+                condition: dep.condition.clone(),
             });
         }
         executions.push(runtime::Execution {
             dependencies,
             runtime_id,
             time_reserved_ms: execution.time_reserved_ms,
+            no_cache: execution.no_cache,
         });
     }
     let procedure = runtime::Procedure {
         texts,
+        binaries,
+        directories,
         runtime_texts,
         empty_directories,
         executions,
@@ -0,0 +1,1109 @@
+use crate::{
+    constant::job_name::{self, v0_features::*},
+    logic::problem_presets::normal_judge::{CHECKER_TEXT_NAME, NormalJudgeTestcase},
+    model::{
+        dep_name_repository::DepNameRepository,
+        identifiers::{DepId, ResourceId},
+        problem_registry::{ProblemRegistryServer, RegistrationError},
+        procedure::{
+            job_metadata::{self, JobMetadata, JobRole},
+            registered::*,
+        },
+    },
+};
+use futures::future::join_all;
+use std::collections::{HashMap, HashSet};
+
+const TESTCASE_COUNT_ENVVAR: &str = "TESTCASE_COUNT";
+const OUTPUT_JSON_ENVVAR_PREFIX: &str = "OUTPUT_JSON_";
+const TESTCASE_NAMES_ENVVAR: &str = "TESTCASE_NAMES";
+const INPUT_FILE_ENVVAR_PREFIX: &str = "INPUT_FILE_";
+const EXPECTED_FILE_ENVVAR_PREFIX: &str = "EXPECTED_FILE_";
+
+/// Testcases to take out of and put into a procedure built by the normal or grouped judge preset.
+/// Removals are applied first, so a testcase is replaced by removing and adding the same name.
+pub struct TestcasePatch {
+    pub removed: Vec<String>,
+    pub added: Vec<NormalJudgeTestcase>,
+}
+
+/// Everything a patch touched besides the procedure itself
+#[derive(Debug, Default)]
+pub struct PatchSummary {
+    pub added_dep_names: HashMap<DepId, String>,
+    pub added_metadata: HashMap<DepId, JobMetadata>,
+    /// Metadata of kept deps whose testcases changed, such as a batched execution
+    pub updated_metadata: HashMap<DepId, JobMetadata>,
+    pub removed_dep_ids: Vec<DepId>,
+    /// Resources the procedure did not refer to before the patch
+    pub added_resources: HashMap<ResourceId, Vec<u8>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PatchError {
+    #[error("Testcase {0} is not part of the procedure")]
+    UnknownTestcase(String),
+    #[error("Name {0} is already used in the procedure")]
+    DuplicateName(String),
+    #[error("No execution aggregates the result of testcase {0}")]
+    MissingAggregator(String),
+    #[error("The procedure has no testcase to take the execution layout from")]
+    MissingTemplate,
+    #[error("The procedure runs several testcases in one execution without recording which")]
+    BatchedTestcases,
+    #[error("Group {0} would be left without testcases")]
+    EmptyGroup(String),
+    #[error("The procedure was not built by the normal or grouped judge preset")]
+    NotPresetProcedure,
+    #[error(transparent)]
+    Registration(#[from] RegistrationError),
+    #[error("Internal error: {0}")]
+    InternalError(String),
+}
+
+/// Patches a registered procedure, uploads the new resources and keeps the dep names in sync.
+pub async fn patch<
+    PRServer: ProblemRegistryServer,
+    DNRepo: DepNameRepository<IdType>,
+    IdType: Clone,
+>(
+    procedure: &mut Procedure,
+    testcase_patch: TestcasePatch,
+    pr_server: PRServer,
+    dn_repo: DNRepo,
+    problem_id: IdType,
+) -> Result<PatchSummary, PatchError> {
+    let dep_names = dn_repo
+        .get_many_by_problem_id(problem_id.clone())
+        .await
+        .map_err(|e| PatchError::InternalError(e.to_string()))?;
+    let recorded_metadata = dn_repo
+        .get_metadata_by_problem_id(problem_id.clone())
+        .await
+        .map_err(|e| PatchError::InternalError(e.to_string()))?;
+    let dep_metadata = job_metadata::with_legacy_fallback(&dep_names, recorded_metadata);
+    let summary = patch_testcases(procedure, &dep_names, &dep_metadata, testcase_patch)?;
+    let mut futures = Vec::new();
+    for (resource_id, content) in summary.added_resources.iter() {
+        let pr_server = pr_server.clone();
+        futures.push(async move {
+            if pr_server.exists(*resource_id).await? {
+                return Ok(());
+            }
+            pr_server.register(*resource_id, content.clone()).await
+        });
+    }
+    join_all(futures)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    dn_repo
+        .remove_by_dep_ids(problem_id.clone(), summary.removed_dep_ids.clone())
+        .await
+        .map_err(|e| PatchError::InternalError(e.to_string()))?;
+    dn_repo
+        .insert_many(problem_id.clone(), summary.added_dep_names.clone())
+        .await
+        .map_err(|e| PatchError::InternalError(e.to_string()))?;
+    let mut metadata = summary.added_metadata.clone();
+    metadata.extend(summary.updated_metadata.clone());
+    dn_repo
+        .insert_metadata_many(problem_id, metadata)
+        .await
+        .map_err(|e| PatchError::InternalError(e.to_string()))?;
+    Ok(summary)
+}
+
+/// Removes and adds testcase subgraphs in place.
+/// Testcases run together by a batched execution are taken out of and put into their batch.
+/// `dep_names` and `dep_metadata` hold the names and metadata of every dep of `procedure`.
+/// `procedure` is left untouched when an error is returned.
+pub fn patch_testcases(
+    procedure: &mut Procedure,
+    dep_names: &HashMap<DepId, String>,
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+    testcase_patch: TestcasePatch,
+) -> Result<PatchSummary, PatchError> {
+    let mut patched = procedure.clone();
+    let mut dep_metadata = dep_metadata.clone();
+    let mut name_to_id: HashMap<String, DepId> = dep_names
+        .iter()
+        .map(|(dep_id, name)| (name.clone(), *dep_id))
+        .collect();
+    let mut summary = PatchSummary::default();
+
+    // メタデータを記録する前にまとめて実行した procedure は、どの testcase を含むか分からない
+    if dep_metadata
+        .values()
+        .any(|metadata| metadata.role == JobRole::Test && metadata.testcases.is_empty())
+    {
+        return Err(PatchError::BatchedTestcases);
+    }
+
+    // 追加する testcase の実行はいずれかの既存 testcase を雛形にするため、削除より先に取っておく
+    let template = if testcase_patch.added.is_empty() {
+        None
+    } else {
+        Some(find_template(&patched, &dep_metadata)?)
+    };
+    // まとめ実行に足す testcase の数は、最初に組まれたときの最大件数に合わせる
+    let batch_size = dep_metadata
+        .values()
+        .filter(|metadata| metadata.role == JobRole::Test)
+        .map(|metadata| metadata.testcases.len())
+        .max()
+        .unwrap_or(1);
+    let mut next_display_order = dep_metadata
+        .values()
+        .filter_map(|metadata| {
+            let display_order = metadata.display_order?;
+            Some(display_order + metadata.testcases.len().max(1) as u32)
+        })
+        .max()
+        .unwrap_or(0);
+
+    for name in testcase_patch.removed.iter() {
+        let lookup = |dep_metadata: &HashMap<DepId, JobMetadata>, role: JobRole| {
+            find_job(dep_metadata, |metadata| {
+                metadata.role == role && metadata.testcases.contains(name)
+            })
+            .ok_or_else(|| PatchError::UnknownTestcase(name.clone()))
+        };
+        let execution_id = lookup(&dep_metadata, JobRole::Test)?;
+        let input_id = lookup(&dep_metadata, JobRole::TestcaseInput)?;
+        let expected_id = lookup(&dep_metadata, JobRole::TestcaseExpected)?;
+        let tempdir_id = lookup(&dep_metadata, JobRole::TestcaseTempdir)?;
+        let remaining = dep_metadata[&execution_id]
+            .testcases
+            .iter()
+            .filter(|testcase| *testcase != name)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut removed_ids = vec![input_id, expected_id];
+        if remaining.is_empty() {
+            let names_id = patched
+                .executions
+                .iter()
+                .find(|execution| execution.dep_id == execution_id)
+                .and_then(|execution| dependency_id(execution, TESTCASE_NAMES_ENVVAR));
+            removed_ids.extend([execution_id, tempdir_id]);
+            removed_ids.extend(names_id);
+            patched
+                .executions
+                .retain(|execution| execution.dep_id != execution_id);
+            patched
+                .empty_directories
+                .retain(|empty_directory| empty_directory.dep_id != tempdir_id);
+            for index in 0..patched.executions.len() {
+                let execution = &mut patched.executions[index];
+                let before = execution.dependencies.len();
+                execution
+                    .dependencies
+                    .retain(|dependency| dependency.dep_id != execution_id);
+                if execution.dependencies.len() != before {
+                    renumber(execution, OUTPUT_JSON_ENVVAR_PREFIX);
+                    update_testcase_count(&mut patched, index, &mut summary)?;
+                }
+            }
+        } else {
+            // 同じまとめ実行に残る testcase はそのまま、入力の番号だけ詰める
+            let execution = patched
+                .executions
+                .iter_mut()
+                .find(|execution| execution.dep_id == execution_id)
+                .ok_or_else(|| PatchError::UnknownTestcase(name.clone()))?;
+            execution.dependencies.retain(|dependency| {
+                dependency.dep_id != input_id && dependency.dep_id != expected_id
+            });
+            renumber(execution, INPUT_FILE_ENVVAR_PREFIX);
+            renumber(execution, EXPECTED_FILE_ENVVAR_PREFIX);
+            execution.time_reserved_ms =
+                execution.time_reserved_ms / (remaining.len() as u64 + 1) * remaining.len() as u64;
+            set_batch_testcases(
+                &mut patched,
+                &mut dep_metadata,
+                &mut summary,
+                [execution_id, tempdir_id],
+                remaining,
+            )?;
+        }
+        patched
+            .texts
+            .retain(|text| !removed_ids.contains(&text.dep_id));
+
+        for dep_id in removed_ids {
+            if let Some(name) = dep_names.get(&dep_id) {
+                name_to_id.remove(name);
+            }
+            dep_metadata.remove(&dep_id);
+            summary.updated_metadata.remove(&dep_id);
+            summary.removed_dep_ids.push(dep_id);
+        }
+    }
+
+    for testcase in testcase_patch.added.iter() {
+        let Some((template, template_ids, time_per_testcase)) = template.as_ref() else {
+            return Err(PatchError::MissingTemplate);
+        };
+        let input_id = add_name(
+            &mut name_to_id,
+            &mut summary,
+            testcase_input_name(&testcase.name),
+        )?;
+        let expected_id = add_name(
+            &mut name_to_id,
+            &mut summary,
+            testcase_expected_name(&testcase.name),
+        )?;
+        let testcases = vec![testcase.name.clone()];
+        for (dep_id, role, content) in [
+            (input_id, JobRole::TestcaseInput, &testcase.input),
+            (
+                expected_id,
+                JobRole::TestcaseExpected,
+                &testcase.expected_output,
+            ),
+        ] {
+            summary.added_metadata.insert(
+                dep_id,
+                JobMetadata::new(role).with_testcases(testcases.clone()),
+            );
+            let content = content.clone().into_bytes();
+            let resource_id = ResourceId::from_content(&content);
+            patched.texts.push(Text {
+                resource_id,
+                dep_id,
+            });
+            summary.added_resources.insert(resource_id, content);
+        }
+
+        if dependency_id(template, TESTCASE_NAMES_ENVVAR).is_none() {
+            let execution_id = add_name(
+                &mut name_to_id,
+                &mut summary,
+                job_name::test_phase_execution_job_name(&testcase.name),
+            )?;
+            let tempdir_id = add_name(
+                &mut name_to_id,
+                &mut summary,
+                job_name::testcase_tempdir_name(&testcase.name),
+            )?;
+            summary.added_metadata.insert(
+                tempdir_id,
+                JobMetadata::new(JobRole::TestcaseTempdir).with_testcases(testcases.clone()),
+            );
+            summary.added_metadata.insert(
+                execution_id,
+                JobMetadata::new(JobRole::Test)
+                    .with_testcases(testcases)
+                    .with_group(testcase.group.clone())
+                    .with_display_order(next_display_order),
+            );
+            next_display_order += 1;
+            patched
+                .empty_directories
+                .push(EmptyDirectory { dep_id: tempdir_id });
+            patched.executions.push(Execution {
+                dependencies: template
+                    .dependencies
+                    .iter()
+                    .map(|dependency| Dependency {
+                        dep_id: match template_ids
+                            .iter()
+                            .position(|dep_id| *dep_id == dependency.dep_id)
+                        {
+                            Some(0) => input_id,
+                            Some(1) => expected_id,
+                            Some(_) => tempdir_id,
+                            None => dependency.dep_id,
+                        },
+                        envvar_name: dependency.envvar_name.clone(),
+                        condition: dependency.condition.clone(),
+                    })
+                    .collect(),
+                dep_id: execution_id,
+                time_reserved_ms: template.time_reserved_ms,
+                no_cache: template.no_cache,
+            });
+            add_output(
+                &mut patched,
+                &dep_metadata,
+                testcase,
+                execution_id,
+                &mut summary,
+            )?;
+            continue;
+        }
+
+        // 空きのあるまとめ実行に足し、なければ雛形から新しいまとめ実行を作る
+        let batch = dep_metadata
+            .iter()
+            .filter(|(dep_id, metadata)| {
+                metadata.role == JobRole::Test
+                    && metadata.testcases.len() < batch_size
+                    && metadata.group == testcase.group
+                    && patched.executions.iter().any(|execution| {
+                        execution.dep_id == **dep_id
+                            && dependency_id(execution, TESTCASE_NAMES_ENVVAR).is_some()
+                    })
+            })
+            .min_by_key(|(_, metadata)| (metadata.display_order, metadata.testcases.len()))
+            .map(|(dep_id, _)| *dep_id);
+        let (execution_id, tempdir_id) = match batch {
+            Some(execution_id) => {
+                let tempdir_id = find_job(&dep_metadata, |metadata| {
+                    metadata.role == JobRole::TestcaseTempdir
+                        && metadata.testcases == dep_metadata[&execution_id].testcases
+                })
+                .ok_or(PatchError::MissingTemplate)?;
+                (execution_id, tempdir_id)
+            }
+            None => {
+                let index = (0..)
+                    .find(|index| {
+                        [
+                            job_name::test_batch_execution_job_name(*index),
+                            job_name::test_batch_tempdir_name(*index),
+                            job_name::test_batch_testcase_names_name(*index),
+                        ]
+                        .iter()
+                        .all(|name| !name_to_id.contains_key(name))
+                    })
+                    .unwrap_or_default();
+                let execution_id = add_name(
+                    &mut name_to_id,
+                    &mut summary,
+                    job_name::test_batch_execution_job_name(index),
+                )?;
+                let tempdir_id = add_name(
+                    &mut name_to_id,
+                    &mut summary,
+                    job_name::test_batch_tempdir_name(index),
+                )?;
+                let names_id = add_name(
+                    &mut name_to_id,
+                    &mut summary,
+                    job_name::test_batch_testcase_names_name(index),
+                )?;
+                for (dep_id, metadata) in [
+                    (tempdir_id, JobMetadata::new(JobRole::TestcaseTempdir)),
+                    (
+                        execution_id,
+                        JobMetadata::new(JobRole::Test)
+                            .with_group(testcase.group.clone())
+                            .with_display_order(next_display_order),
+                    ),
+                ] {
+                    dep_metadata.insert(dep_id, metadata.clone());
+                    summary.added_metadata.insert(dep_id, metadata);
+                }
+                next_display_order += batch_size as u32;
+                patched
+                    .empty_directories
+                    .push(EmptyDirectory { dep_id: tempdir_id });
+                let template_names_id = dependency_id(template, TESTCASE_NAMES_ENVVAR);
+                patched.executions.push(Execution {
+                    dependencies: template
+                        .dependencies
+                        .iter()
+                        .filter(|dependency| {
+                            !dependency.envvar_name.starts_with(INPUT_FILE_ENVVAR_PREFIX)
+                                && !dependency
+                                    .envvar_name
+                                    .starts_with(EXPECTED_FILE_ENVVAR_PREFIX)
+                        })
+                        .map(|dependency| Dependency {
+                            dep_id: if Some(dependency.dep_id) == template_names_id {
+                                names_id
+                            } else if dependency.dep_id == template_ids[2] {
+                                tempdir_id
+                            } else {
+                                dependency.dep_id
+                            },
+                            envvar_name: dependency.envvar_name.clone(),
+                            condition: dependency.condition.clone(),
+                        })
+                        .collect(),
+                    dep_id: execution_id,
+                    time_reserved_ms: 0,
+                    no_cache: template.no_cache,
+                });
+                add_output(
+                    &mut patched,
+                    &dep_metadata,
+                    testcase,
+                    execution_id,
+                    &mut summary,
+                )?;
+                (execution_id, tempdir_id)
+            }
+        };
+
+        let execution = patched
+            .executions
+            .iter_mut()
+            .find(|execution| execution.dep_id == execution_id)
+            .ok_or(PatchError::MissingTemplate)?;
+        let position = execution
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.envvar_name.starts_with(INPUT_FILE_ENVVAR_PREFIX))
+            .count();
+        execution.dependencies.push(Dependency {
+            dep_id: input_id,
+            envvar_name: format!("{}{}", INPUT_FILE_ENVVAR_PREFIX, position),
+            condition: None,
+        });
+        execution.dependencies.push(Dependency {
+            dep_id: expected_id,
+            envvar_name: format!("{}{}", EXPECTED_FILE_ENVVAR_PREFIX, position),
+            condition: None,
+        });
+        execution.time_reserved_ms += time_per_testcase;
+        let mut batch_testcases = dep_metadata[&execution_id].testcases.clone();
+        batch_testcases.push(testcase.name.clone());
+        set_batch_testcases(
+            &mut patched,
+            &mut dep_metadata,
+            &mut summary,
+            [execution_id, tempdir_id],
+            batch_testcases,
+        )?;
+    }
+
+    // testcase のなくなったグループは採点できない
+    for execution in patched.executions.iter() {
+        let Some(group) = dep_metadata
+            .get(&execution.dep_id)
+            .filter(|metadata| metadata.role == JobRole::GroupSummary)
+            .and_then(|metadata| metadata.group.as_ref())
+        else {
+            continue;
+        };
+        if !execution.dependencies.iter().any(|dependency| {
+            dependency
+                .envvar_name
+                .starts_with(OUTPUT_JSON_ENVVAR_PREFIX)
+        }) {
+            return Err(PatchError::EmptyGroup(group.clone()));
+        }
+    }
+
+    let before: HashSet<ResourceId> = resource_ids(procedure).collect();
+    let after: HashSet<ResourceId> = resource_ids(&patched).collect();
+    summary
+        .added_resources
+        .retain(|resource_id, _| after.contains(resource_id) && !before.contains(resource_id));
+    *procedure = patched;
+    Ok(summary)
+}
+
+/// The text holding the built-in checker of a procedure built by the normal or grouped judge preset,
+/// or `None` if it compares outputs exactly without one.
+/// Other procedures, such as special judges, cannot be built again from their testcases alone.
+pub fn preset_checker_text(
+    procedure: &Procedure,
+    dep_names: &HashMap<DepId, String>,
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+) -> Result<Option<ResourceId>, PatchError> {
+    if procedure.executions.iter().any(|execution| {
+        dep_metadata
+            .get(&execution.dep_id)
+            .is_none_or(|metadata| matches!(metadata.role, JobRole::ToolCompile | JobRole::Custom))
+    }) {
+        return Err(PatchError::NotPresetProcedure);
+    }
+    Ok(procedure
+        .texts
+        .iter()
+        .find(|text| {
+            dep_names
+                .get(&text.dep_id)
+                .is_some_and(|name| name == CHECKER_TEXT_NAME)
+        })
+        .map(|text| text.resource_id))
+}
+
+fn add_name(
+    name_to_id: &mut HashMap<String, DepId>,
+    summary: &mut PatchSummary,
+    name: String,
+) -> Result<DepId, PatchError> {
+    if name_to_id.contains_key(&name) {
+        return Err(PatchError::DuplicateName(name));
+    }
+    let dep_id = DepId::new();
+    name_to_id.insert(name.clone(), dep_id);
+    summary.added_dep_names.insert(dep_id, name);
+    Ok(dep_id)
+}
+
+fn find_job(
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+    predicate: impl Fn(&JobMetadata) -> bool,
+) -> Option<DepId> {
+    dep_metadata
+        .iter()
+        .find(|(_, metadata)| predicate(metadata))
+        .map(|(dep_id, _)| *dep_id)
+}
+
+/// The job of `role` that belongs to testcase `name` alone
+fn testcase_job(
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+    role: JobRole,
+    name: &str,
+) -> Option<DepId> {
+    find_job(dep_metadata, |metadata| {
+        metadata.role == role && metadata.testcases == [name]
+    })
+}
+
+fn dependency_id(execution: &Execution, envvar_name: &str) -> Option<DepId> {
+    execution
+        .dependencies
+        .iter()
+        .find(|dependency| dependency.envvar_name == envvar_name)
+        .map(|dependency| dependency.dep_id)
+}
+
+/// Any test execution along with the input, expected output and tempdir dep ids of its first testcase,
+/// and the time it reserves per testcase
+fn find_template(
+    procedure: &Procedure,
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+) -> Result<(Execution, [DepId; 3], u64), PatchError> {
+    for execution in procedure.executions.iter() {
+        let Some(testcases) = dep_metadata
+            .get(&execution.dep_id)
+            .filter(|metadata| metadata.role == JobRole::Test)
+            .map(|metadata| metadata.testcases.as_slice())
+        else {
+            continue;
+        };
+        let Some(name) = testcases.first() else {
+            continue;
+        };
+        let ids = [
+            testcase_job(dep_metadata, JobRole::TestcaseInput, name),
+            testcase_job(dep_metadata, JobRole::TestcaseExpected, name),
+            find_job(dep_metadata, |metadata| {
+                metadata.role == JobRole::TestcaseTempdir && metadata.testcases.contains(name)
+            }),
+        ];
+        if let [Some(input_id), Some(expected_id), Some(tempdir_id)] = ids {
+            let time_per_testcase = execution.time_reserved_ms / testcases.len() as u64;
+            return Ok((
+                execution.clone(),
+                [input_id, expected_id, tempdir_id],
+                time_per_testcase,
+            ));
+        }
+    }
+    Err(PatchError::MissingTemplate)
+}
+
+/// Passes the result of `execution_id` to the group summary of the testcase, or to the summary
+fn add_output(
+    procedure: &mut Procedure,
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+    testcase: &NormalJudgeTestcase,
+    execution_id: DepId,
+    summary: &mut PatchSummary,
+) -> Result<(), PatchError> {
+    // グループがあればグループの集計に、なければ全体の集計に結果を渡す
+    let aggregator_id = testcase
+        .group
+        .as_ref()
+        .and_then(|group| {
+            find_job(dep_metadata, |metadata| {
+                metadata.role == JobRole::GroupSummary && metadata.group.as_ref() == Some(group)
+            })
+        })
+        .or_else(|| find_job(dep_metadata, |metadata| metadata.role == JobRole::Summary));
+    let index = aggregator_id
+        .and_then(|aggregator_id| {
+            procedure
+                .executions
+                .iter()
+                .position(|execution| execution.dep_id == aggregator_id)
+        })
+        .filter(|index| {
+            dependency_id(&procedure.executions[*index], TESTCASE_COUNT_ENVVAR).is_some()
+        })
+        .ok_or_else(|| PatchError::MissingAggregator(testcase.name.clone()))?;
+    let aggregator = &mut procedure.executions[index];
+    let output_count = aggregator
+        .dependencies
+        .iter()
+        .filter(|dependency| {
+            dependency
+                .envvar_name
+                .starts_with(OUTPUT_JSON_ENVVAR_PREFIX)
+        })
+        .count();
+    aggregator.dependencies.push(Dependency {
+        dep_id: execution_id,
+        envvar_name: format!("{}{}", OUTPUT_JSON_ENVVAR_PREFIX, output_count),
+        condition: None,
+    });
+    update_testcase_count(procedure, index, summary)
+}
+
+/// Records the testcases run by a batched execution in its names text and in the metadata of it and its tempdir
+fn set_batch_testcases(
+    procedure: &mut Procedure,
+    dep_metadata: &mut HashMap<DepId, JobMetadata>,
+    summary: &mut PatchSummary,
+    [execution_id, tempdir_id]: [DepId; 2],
+    testcases: Vec<String>,
+) -> Result<(), PatchError> {
+    let names_id = procedure
+        .executions
+        .iter()
+        .find(|execution| execution.dep_id == execution_id)
+        .and_then(|execution| dependency_id(execution, TESTCASE_NAMES_ENVVAR))
+        .ok_or_else(|| {
+            PatchError::InternalError("Batched execution has no testcase names".to_string())
+        })?;
+    set_text(
+        procedure,
+        names_id,
+        testcases.join("\n").into_bytes(),
+        summary,
+    );
+    for dep_id in [execution_id, tempdir_id] {
+        let metadata = dep_metadata
+            .get(&dep_id)
+            .cloned()
+            .unwrap_or_default()
+            .with_testcases(testcases.clone());
+        dep_metadata.insert(dep_id, metadata.clone());
+        if summary.added_dep_names.contains_key(&dep_id) {
+            summary.added_metadata.insert(dep_id, metadata);
+        } else {
+            summary.updated_metadata.insert(dep_id, metadata);
+        }
+    }
+    Ok(())
+}
+
+fn renumber(execution: &mut Execution, envvar_prefix: &str) {
+    let mut index = 0;
+    for dependency in execution.dependencies.iter_mut() {
+        if dependency.envvar_name.starts_with(envvar_prefix) {
+            dependency.envvar_name = format!("{}{}", envvar_prefix, index);
+            index += 1;
+        }
+    }
+}
+
+/// Rewrites the testcase count text of an aggregating execution to its number of outputs
+fn update_testcase_count(
+    procedure: &mut Procedure,
+    execution_index: usize,
+    summary: &mut PatchSummary,
+) -> Result<(), PatchError> {
+    let execution = &procedure.executions[execution_index];
+    let Some(count_id) = dependency_id(execution, TESTCASE_COUNT_ENVVAR) else {
+        return Ok(());
+    };
+    if !procedure.texts.iter().any(|text| text.dep_id == count_id) {
+        return Err(PatchError::InternalError(
+            "Testcase count is not a text".to_string(),
+        ));
+    }
+    let content = execution
+        .dependencies
+        .iter()
+        .filter(|dependency| {
+            dependency
+                .envvar_name
+                .starts_with(OUTPUT_JSON_ENVVAR_PREFIX)
+        })
+        .count()
+        .to_string()
+        .into_bytes();
+    set_text(procedure, count_id, content, summary);
+    Ok(())
+}
+
+/// Points the text `dep_id` at `content`, adding the text if the procedure has none
+fn set_text(
+    procedure: &mut Procedure,
+    dep_id: DepId,
+    content: Vec<u8>,
+    summary: &mut PatchSummary,
+) {
+    let resource_id = ResourceId::from_content(&content);
+    match procedure
+        .texts
+        .iter_mut()
+        .find(|text| text.dep_id == dep_id)
+    {
+        Some(text) if text.resource_id == resource_id => return,
+        Some(text) => text.resource_id = resource_id,
+        None => procedure.texts.push(Text {
+            resource_id,
+            dep_id,
+        }),
+    }
+    summary.added_resources.insert(resource_id, content);
+}
+
+fn resource_ids(procedure: &Procedure) -> impl Iterator<Item = ResourceId> + '_ {
+    procedure
+        .texts
+        .iter()
+        .map(|text| text.resource_id)
+        .chain(procedure.binaries.iter().map(|binary| binary.resource_id))
+        .chain(
+            procedure
+                .directories
+                .iter()
+                .map(|directory| directory.resource_id),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{
+        problem_presets::{
+            grouped_judge::{
+                GroupScoringPolicy, TestcaseGroup, create_grouped_judge_procedure,
+                create_grouped_judge_procedure_with_checker,
+            },
+            normal_judge::{
+                create_batched_normal_judge_procedure, create_normal_judge_procedure,
+                create_normal_judge_procedure_with_checker,
+            },
+            special_judge::{SpecialJudgeChecker, create_special_judge_procedure},
+        },
+        writer_schema_registerer::{metadata_by_dep_id, transpile_inner},
+    };
+    use crate::model::builtin_checker::BuiltinChecker;
+
+    fn testcase(name: &str, input: &str) -> NormalJudgeTestcase {
+        NormalJudgeTestcase {
+            name: name.to_string(),
+            input: input.to_string(),
+            expected_output: format!("{input}_out"),
+            group: None,
+        }
+    }
+
+    fn output_deps(procedure: &Procedure, dep_names: &HashMap<DepId, String>) -> Vec<String> {
+        let summary_id = dep_names
+            .iter()
+            .find(|(_, name)| name.as_str() == job_name::SUMMARY_PHASE)
+            .map(|(dep_id, _)| *dep_id)
+            .unwrap();
+        let summary = procedure
+            .executions
+            .iter()
+            .find(|execution| execution.dep_id == summary_id)
+            .unwrap();
+        summary
+            .dependencies
+            .iter()
+            .filter(|dependency| {
+                dependency
+                    .envvar_name
+                    .starts_with(OUTPUT_JSON_ENVVAR_PREFIX)
+            })
+            .map(|dependency| {
+                format!(
+                    "{}={}",
+                    dependency.envvar_name, dep_names[&dependency.dep_id]
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replaces_testcase_subgraphs() {
+        let writer_schema =
+            create_normal_judge_procedure(vec![testcase("a", "1"), testcase("b", "2")]).unwrap();
+        let (mut procedure, _, mut dep_names) = transpile_inner(writer_schema.clone()).unwrap();
+        let dep_metadata = metadata_by_dep_id(&writer_schema.metadata, &dep_names);
+        let original = procedure.clone();
+
+        let summary = patch_testcases(
+            &mut procedure,
+            &dep_names,
+            &dep_metadata,
+            TestcasePatch {
+                removed: vec!["a".to_string()],
+                added: vec![testcase("c", "3"), testcase("d", "3")],
+            },
+        )
+        .unwrap();
+        for dep_id in summary.removed_dep_ids.iter() {
+            dep_names.remove(dep_id);
+        }
+        dep_names.extend(summary.added_dep_names.clone());
+
+        assert_eq!(
+            output_deps(&procedure, &dep_names),
+            vec![
+                "OUTPUT_JSON_0=testPhase_b",
+                "OUTPUT_JSON_1=testPhase_c",
+                "OUTPUT_JSON_2=testPhase_d"
+            ]
+        );
+        assert_eq!(procedure.executions.len(), original.executions.len() + 1);
+        assert_eq!(summary.removed_dep_ids.len(), 4);
+        assert_eq!(summary.added_dep_names.len(), 8);
+        assert_eq!(summary.added_metadata.len(), 8);
+        let ids = |content: &str| ResourceId::from_content(content.as_bytes());
+        let mut added: Vec<ResourceId> = summary.added_resources.keys().copied().collect();
+        added.sort_by_key(|id| id.to_string());
+        let mut expected = vec![ids("3"), ids("3_out")];
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(added, expected);
+        let refers_to = |id: ResourceId| procedure.texts.iter().any(|text| text.resource_id == id);
+        assert!(!refers_to(ids("1")) && !refers_to(ids("1_out")));
+        // 件数は "2" から "3" に変わるが、"2" は b の入力としてまだ使われている
+        assert!(refers_to(ids("2")) && refers_to(ids("3")));
+    }
+
+    #[test]
+    fn leaves_procedure_untouched_on_error() {
+        let writer_schema = create_normal_judge_procedure(vec![testcase("a", "1")]).unwrap();
+        let (mut procedure, _, dep_names) = transpile_inner(writer_schema).unwrap();
+        // メタデータを記録する前に登録された procedure は名前から役割を推測する
+        let dep_metadata = job_metadata::with_legacy_fallback(&dep_names, HashMap::new());
+        let executions = procedure.executions.len();
+        let result = patch_testcases(
+            &mut procedure,
+            &dep_names,
+            &dep_metadata,
+            TestcasePatch {
+                removed: vec!["a".to_string(), "missing".to_string()],
+                added: vec![],
+            },
+        );
+        assert!(matches!(result, Err(PatchError::UnknownTestcase(name)) if name == "missing"));
+        assert_eq!(procedure.executions.len(), executions);
+    }
+
+    #[test]
+    fn patches_batched_executions() {
+        let testcases = (0..5).map(|i| testcase(&i.to_string(), "1")).collect();
+        let writer_schema =
+            create_batched_normal_judge_procedure(testcases, BuiltinChecker::Exact, 2).unwrap();
+        let (mut procedure, _, mut dep_names) = transpile_inner(writer_schema.clone()).unwrap();
+        let dep_metadata = metadata_by_dep_id(&writer_schema.metadata, &dep_names);
+        // compile, 3 つのまとめ実行 (0,1 / 2,3 / 4), summary
+        assert_eq!(procedure.executions.len(), 5);
+        let summary = patch_testcases(
+            &mut procedure,
+            &dep_names,
+            &dep_metadata,
+            TestcasePatch {
+                removed: vec!["0".to_string(), "4".to_string()],
+                added: vec![testcase("5", "5"), testcase("6", "6"), testcase("7", "7")],
+            },
+        )
+        .unwrap();
+        for dep_id in summary.removed_dep_ids.iter() {
+            dep_names.remove(dep_id);
+        }
+        dep_names.extend(summary.added_dep_names.clone());
+        let dep_id = |name: &str| {
+            dep_names
+                .iter()
+                .find(|(_, dep_name)| dep_name.as_str() == name)
+                .map(|(dep_id, _)| *dep_id)
+                .unwrap()
+        };
+        let execution = |name: &str| {
+            procedure
+                .executions
+                .iter()
+                .find(|execution| execution.dep_id == dep_id(name))
+                .unwrap()
+        };
+        let text = |dep_id: DepId| {
+            procedure
+                .texts
+                .iter()
+                .find(|text| text.dep_id == dep_id)
+                .unwrap()
+                .resource_id
+        };
+
+        // 0 の抜けた 1 つ目に 5 を足し、空になった 3 つ目は 6, 7 のまとめ実行として作り直す
+        assert_eq!(
+            output_deps(&procedure, &dep_names),
+            vec![
+                "OUTPUT_JSON_0=testBatch_0",
+                "OUTPUT_JSON_1=testBatch_1",
+                "OUTPUT_JSON_2=testBatch_2"
+            ]
+        );
+        assert_eq!(procedure.executions.len(), 5);
+        let first = execution("testBatch_0");
+        let inputs = first
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.envvar_name.starts_with(INPUT_FILE_ENVVAR_PREFIX))
+            .map(|dependency| {
+                format!(
+                    "{}={}",
+                    dependency.envvar_name, dep_names[&dependency.dep_id]
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(inputs, vec!["INPUT_FILE_0=1_input", "INPUT_FILE_1=5_input"]);
+        assert_eq!(first.time_reserved_ms, 22000);
+        assert_eq!(execution("testBatch_2").time_reserved_ms, 22000);
+        assert_eq!(
+            text(dep_id("testcase_names_testBatch_0")),
+            ResourceId::from_content(b"1\n5")
+        );
+        assert_eq!(
+            text(dep_id("testcase_names_testBatch_2")),
+            ResourceId::from_content(b"6\n7")
+        );
+
+        let updated = &summary.updated_metadata[&dep_id("testBatch_0")];
+        assert_eq!(updated.testcases, vec!["1", "5"]);
+        assert_eq!(
+            summary.updated_metadata[&dep_id("tempdir_testBatch_0")].testcases,
+            vec!["1", "5"]
+        );
+        let added = &summary.added_metadata[&dep_id("testBatch_2")];
+        assert_eq!(added.testcases, vec!["6", "7"]);
+        assert_eq!(added.role, JobRole::Test);
+        assert!(
+            procedure
+                .texts
+                .iter()
+                .all(|text| text.resource_id != ResourceId::from_content(b"0\n1"))
+        );
+    }
+
+    #[test]
+    fn refuses_unrecorded_batches() {
+        let testcases = (0..5).map(|i| testcase(&i.to_string(), "1")).collect();
+        let writer_schema =
+            create_batched_normal_judge_procedure(testcases, BuiltinChecker::Exact, 2).unwrap();
+        let (mut procedure, _, dep_names) = transpile_inner(writer_schema).unwrap();
+        // メタデータを記録する前に登録されたまとめ実行は、含む testcase が分からない
+        let dep_metadata = job_metadata::with_legacy_fallback(&dep_names, HashMap::new());
+        let result = patch_testcases(
+            &mut procedure,
+            &dep_names,
+            &dep_metadata,
+            TestcasePatch {
+                removed: vec!["0".to_string()],
+                added: vec![],
+            },
+        );
+        assert!(matches!(result, Err(PatchError::BatchedTestcases)));
+    }
+
+    #[test]
+    fn refuses_to_empty_a_group() {
+        let grouped = |name: &str, group: &str| NormalJudgeTestcase {
+            group: Some(group.to_string()),
+            ..testcase(name, name)
+        };
+        let groups = ["g1", "g2"]
+            .into_iter()
+            .map(|name| TestcaseGroup {
+                name: name.to_string(),
+                point: 50,
+                policy: GroupScoringPolicy::AllOrNothing,
+            })
+            .collect();
+        let writer_schema = create_grouped_judge_procedure(
+            vec![grouped("a", "g1"), grouped("b", "g1"), grouped("c", "g2")],
+            groups,
+        )
+        .unwrap();
+        let (mut procedure, _, dep_names) = transpile_inner(writer_schema.clone()).unwrap();
+        let dep_metadata = metadata_by_dep_id(&writer_schema.metadata, &dep_names);
+        let (executions, texts) = (procedure.executions.len(), procedure.texts.len());
+
+        // 空になったグループを集計すると、結果のない allOrNothing が満点になってしまう
+        let result = patch_testcases(
+            &mut procedure,
+            &dep_names,
+            &dep_metadata,
+            TestcasePatch {
+                removed: vec!["c".to_string()],
+                added: vec![],
+            },
+        );
+        assert!(matches!(result, Err(PatchError::EmptyGroup(group)) if group == "g2"));
+        assert_eq!(procedure.executions.len(), executions);
+        assert_eq!(procedure.texts.len(), texts);
+
+        // 同じグループに差し替えるなら、途中で空になっても構わない
+        patch_testcases(
+            &mut procedure,
+            &dep_names,
+            &dep_metadata,
+            TestcasePatch {
+                removed: vec!["c".to_string()],
+                added: vec![grouped("c", "g2")],
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn finds_the_checker_of_preset_procedures() {
+        let registered = |writer_schema: crate::model::procedure::writer_schema::Procedure| {
+            let (procedure, _, dep_names) = transpile_inner(writer_schema.clone()).unwrap();
+            let dep_metadata = metadata_by_dep_id(&writer_schema.metadata, &dep_names);
+            preset_checker_text(&procedure, &dep_names, &dep_metadata)
+        };
+        let checker = BuiltinChecker::Float {
+            abs_eps: 1e-6,
+            rel_eps: 1e-6,
+        };
+        let writer_schema =
+            create_normal_judge_procedure_with_checker(vec![testcase("a", "1")], checker.clone())
+                .unwrap();
+        assert_eq!(
+            registered(writer_schema).unwrap(),
+            Some(ResourceId::from_content(checker.to_string().as_bytes()))
+        );
+        let writer_schema = create_grouped_judge_procedure_with_checker(
+            vec![NormalJudgeTestcase {
+                group: Some("g".to_string()),
+                ..testcase("a", "1")
+            }],
+            vec![TestcaseGroup {
+                name: "g".to_string(),
+                point: 100,
+                policy: GroupScoringPolicy::Min,
+            }],
+            checker.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            registered(writer_schema).unwrap(),
+            Some(ResourceId::from_content(checker.to_string().as_bytes()))
+        );
+
+        // 作問者のチェッカーは testcase だけからは作り直せない
+        let writer_schema = create_special_judge_procedure(
+            vec![testcase("a", "1")],
+            SpecialJudgeChecker {
+                source: "int main() {}".to_string(),
+                language_tag: "cpp".to_string(),
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            registered(writer_schema),
+            Err(PatchError::NotPresetProcedure)
+        ));
+    }
+}
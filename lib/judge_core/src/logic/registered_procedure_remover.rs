@@ -10,6 +10,12 @@ pub async fn remove<PRServer: ProblemRegistryServer>(
     for text in procedure.texts.iter() {
         futures.push(pr_server.remove(text.resource_id.clone()));
     }
+    for binary in procedure.binaries.iter() {
+        futures.push(pr_server.remove(binary.resource_id));
+    }
+    for directory in procedure.directories.iter() {
+        futures.push(pr_server.remove(directory.resource_id));
+    }
     let future = join_all(futures);
     future
         .await
@@ -1,8 +1,189 @@
-use crate::model::{identifiers::RuntimeId, job, judge_output, procedure::runtime};
+use super::trace_recorder::TraceRecorder;
+use crate::model::{
+    cancellation::CancellationToken, identifiers::RuntimeId, job, judge_output, procedure::runtime,
+};
 use anyhow::Context;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use futures::StreamExt;
+use futures::future::Either;
+use futures::stream::FuturesUnordered;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::mpsc;
+
+struct ExecConf<ReservationToken> {
+    reservation: ReservationToken,
+    dependencies: Vec<runtime::Dependency>,
+    time_reserved_ms: u64,
+    no_cache: bool,
+}
+
+/// What a finished job hands back to the scheduling loop in `Runner::run`
+enum Finished<OutcomeToken> {
+    File {
+        runtime_id: RuntimeId,
+        outcome: Result<OutcomeToken, job::FilePlacementError>,
+    },
+    Execution {
+        runtime_id: RuntimeId,
+        /// `None` unless the jobs which depend on the execution may run
+        outcome: Option<OutcomeToken>,
+        report: Box<judge_output::ExecutionJobReport>,
+    },
+}
+
+/// Scheduling state of `Runner::run`, owned by the single task driving the jobs.
+/// Every dependency edge is visited a constant number of times over a whole run.
+struct Schedule<ReservationToken, OutcomeToken> {
+    /// Executions which have not been started yet
+    pending: HashMap<RuntimeId, ExecConf<ReservationToken>>,
+    /// Dependencies of each pending execution whose outcome is not available yet
+    waiting_for: HashMap<RuntimeId, usize>,
+    /// Executions depending on each job, once per dependency edge
+    consumers: HashMap<RuntimeId, Vec<RuntimeId>>,
+    /// Edges from pending executions which may still take the outcome of each job
+    holders: HashMap<RuntimeId, usize>,
+    /// Outcomes kept only while some holder is left
+    outcomes: HashMap<RuntimeId, OutcomeToken>,
+    /// Pending executions which can never run because a job they depend on produced no outcome
+    dead: HashSet<RuntimeId>,
+    outputs: HashMap<RuntimeId, judge_output::ExecutionJobReport>,
+}
+
+impl<ReservationToken, OutcomeToken: Clone> Schedule<ReservationToken, OutcomeToken> {
+    fn new(pending: HashMap<RuntimeId, ExecConf<ReservationToken>>) -> Self {
+        let mut waiting_for = HashMap::new();
+        let mut consumers = HashMap::<RuntimeId, Vec<RuntimeId>>::new();
+        let mut holders = HashMap::new();
+        for (runtime_id, exec_conf) in pending.iter() {
+            waiting_for.insert(*runtime_id, exec_conf.dependencies.len());
+            for dep in exec_conf.dependencies.iter() {
+                consumers
+                    .entry(dep.runtime_id)
+                    .or_default()
+                    .push(*runtime_id);
+                *holders.entry(dep.runtime_id).or_insert(0) += 1;
+            }
+        }
+        Self {
+            pending,
+            waiting_for,
+            consumers,
+            holders,
+            outcomes: HashMap::new(),
+            dead: HashSet::new(),
+            outputs: HashMap::new(),
+        }
+    }
+
+    /// Executions without any dependency
+    fn initially_ready(&self) -> Vec<RuntimeId> {
+        self.waiting_for
+            .iter()
+            .filter(|(_, waiting_for)| **waiting_for == 0)
+            .map(|(runtime_id, _)| *runtime_id)
+            .collect()
+    }
+
+    /// Makes the outcome of `runtime_id` available and returns the executions which became ready
+    fn resolve(&mut self, runtime_id: RuntimeId, outcome: OutcomeToken) -> Vec<RuntimeId> {
+        if self.holders.contains_key(&runtime_id) {
+            self.outcomes.insert(runtime_id, outcome);
+        }
+        let mut ready = Vec::new();
+        for consumer in self.consumers.get(&runtime_id).into_iter().flatten() {
+            if let Some(waiting_for) = self.waiting_for.get_mut(consumer) {
+                *waiting_for -= 1;
+                if *waiting_for == 0 {
+                    ready.push(*consumer);
+                }
+            }
+        }
+        ready
+    }
+
+    /// `runtime_id` produced no outcome, so nothing downstream of it can run
+    fn abandon(&mut self, runtime_id: RuntimeId) {
+        let mut stack = vec![runtime_id];
+        while let Some(runtime_id) = stack.pop() {
+            let Some(consumers) = self.consumers.get(&runtime_id) else {
+                continue;
+            };
+            for consumer in consumers.clone() {
+                if self.pending.contains_key(&consumer) && self.dead.insert(consumer) {
+                    let dependencies = self.pending[&consumer].dependencies.clone();
+                    self.release(&dependencies);
+                    stack.push(consumer);
+                }
+            }
+        }
+    }
+
+    /// Takes `runtime_id` out of `pending` to be started
+    fn start(&mut self, runtime_id: RuntimeId) -> Option<ExecConf<ReservationToken>> {
+        self.waiting_for.remove(&runtime_id);
+        self.pending.remove(&runtime_id)
+    }
+
+    fn conditions_hold(&self, dependencies: &[runtime::Dependency]) -> bool {
+        dependencies.iter().all(|dep| {
+            dep.condition.as_ref().is_none_or(|condition| {
+                self.outputs
+                    .get(&dep.runtime_id)
+                    .is_some_and(|report| condition.holds(&report.result))
+            })
+        })
+    }
+
+    /// Clones the outcomes `dependencies` refer to and stops holding them
+    fn take_outcomes(
+        &mut self,
+        dependencies: &[runtime::Dependency],
+    ) -> anyhow::Result<HashMap<RuntimeId, OutcomeToken>> {
+        let mut outcomes = HashMap::new();
+        for dep in dependencies.iter() {
+            let outcome = self
+                .outcomes
+                .get(&dep.runtime_id)
+                .context("Dependency not satisfied yet")?;
+            outcomes.insert(dep.runtime_id, outcome.clone());
+        }
+        self.release(dependencies);
+        Ok(outcomes)
+    }
+
+    fn release(&mut self, dependencies: &[runtime::Dependency]) {
+        for dep in dependencies.iter() {
+            if let Some(holders) = self.holders.get_mut(&dep.runtime_id) {
+                *holders -= 1;
+                if *holders == 0 {
+                    self.holders.remove(&dep.runtime_id);
+                    self.outcomes.remove(&dep.runtime_id);
+                }
+            }
+        }
+    }
+
+    /// Executions left in `pending` which transitively depend on a job whose result matches `is_origin`
+    fn find_downstream(
+        &self,
+        is_origin: impl Fn(&judge_output::ExecutionJobResult) -> bool,
+    ) -> HashSet<RuntimeId> {
+        let mut stack = self
+            .outputs
+            .iter()
+            .filter(|(_, report)| is_origin(&report.result))
+            .map(|(runtime_id, _)| *runtime_id)
+            .collect::<Vec<_>>();
+        let mut found = HashSet::new();
+        while let Some(runtime_id) = stack.pop() {
+            for consumer in self.consumers.get(&runtime_id).into_iter().flatten() {
+                if self.pending.contains_key(consumer) && found.insert(*consumer) {
+                    stack.push(*consumer);
+                }
+            }
+        }
+        found
+    }
+}
 
 pub struct Runner<
     ReservationToken: Send + Sync + 'static,
@@ -10,10 +191,12 @@ pub struct Runner<
     JobServiceType: job::JobService<ReservationToken, OutcomeToken>,
 > {
     job_service: JobServiceType,
-    outcomes: Arc<Mutex<HashMap<RuntimeId, OutcomeToken>>>,
-    outputs: Arc<Mutex<HashMap<RuntimeId, judge_output::ExecutionJobResult>>>,
-    exec_confs: Arc<Mutex<HashMap<RuntimeId, (ReservationToken, Vec<runtime::Dependency>)>>>,
+    exec_confs: HashMap<RuntimeId, ExecConf<ReservationToken>>,
     file_confs: HashMap<RuntimeId, job::FileConf>,
+    cancellation: CancellationToken,
+    progress: Option<mpsc::UnboundedSender<(RuntimeId, judge_output::ExecutionJobReport)>>,
+    trace: Option<TraceRecorder>,
+    _phantom: std::marker::PhantomData<OutcomeToken>,
 }
 
 impl<
@@ -25,49 +208,135 @@ impl<
     pub async fn new(
         job_service: JobServiceType,
         procedure: runtime::Procedure,
+        cancellation: CancellationToken,
+        progress: Option<mpsc::UnboundedSender<(RuntimeId, judge_output::ExecutionJobReport)>>,
+        trace: Option<TraceRecorder>,
     ) -> anyhow::Result<Self> {
         let file_confs = Self::create_file_confs(&procedure);
         let exec_confs = Self::create_exec_confs(&procedure, &job_service).await?;
         Ok(Self {
             job_service,
-            outcomes: Arc::new(Mutex::new(HashMap::new())),
-            outputs: Arc::new(Mutex::new(HashMap::new())),
-            exec_confs: Arc::new(Mutex::new(exec_confs)),
-            file_confs: file_confs,
+            exec_confs,
+            file_confs,
+            cancellation,
+            progress,
+            trace,
+            _phantom: std::marker::PhantomData,
         })
     }
 
+    /// Drives every job from this one task. An execution starts as soon as the last
+    /// outcome it depends on is available, and an outcome is dropped once no
+    /// pending execution can take it any more.
     #[tracing::instrument(skip(self))]
-    pub async fn run(self) -> anyhow::Result<HashMap<RuntimeId, judge_output::ExecutionJobResult>> {
+    pub async fn run(
+        mut self,
+    ) -> anyhow::Result<HashMap<RuntimeId, judge_output::ExecutionJobReport>> {
         tracing::info!("Starting the runner");
-        {
-            let first_futures = {
-                let mut first_futures = Vec::new();
-                for (runtime_id, file_conf) in self.file_confs.iter() {
-                    let future = self.run_file_job(runtime_id.clone(), file_conf.clone());
-                    first_futures.push(future);
+        let mut schedule = Schedule::new(std::mem::take(&mut self.exec_confs));
+        let mut running = FuturesUnordered::new();
+        for (runtime_id, file_conf) in std::mem::take(&mut self.file_confs) {
+            running.push(Either::Left(self.run_file_job(runtime_id, file_conf)));
+        }
+        let mut ready = schedule.initially_ready();
+        loop {
+            for runtime_id in ready.drain(..) {
+                if self.cancellation.is_cancelled() {
+                    tracing::info!("Cancelled, not scheduling next jobs");
+                    break;
                 }
-                first_futures
-            };
-            if first_futures.is_empty() {
-                self.run_next(&HashMap::new()).await?;
-            } else {
-                futures::future::join_all(first_futures)
-                    .await
-                    .into_iter()
-                    .collect::<Result<Vec<_>, _>>()?;
+                let Some(exec_conf) = schedule.start(runtime_id) else {
+                    continue;
+                };
+                // 依存先の結果が揃ったので、依存に付いた条件をここで評価する
+                if !schedule.conditions_hold(&exec_conf.dependencies) {
+                    tracing::info!("Condition unmet for {}", runtime_id);
+                    schedule.release(&exec_conf.dependencies);
+                    self.record_output(
+                        &mut schedule.outputs,
+                        runtime_id,
+                        judge_output::ExecutionJobResult::ConditionUnmet,
+                        None,
+                    )
+                    .await;
+                    schedule.abandon(runtime_id);
+                    continue;
+                }
+                let outcomes = schedule.take_outcomes(&exec_conf.dependencies)?;
+                running.push(Either::Right(
+                    self.run_execution_job(runtime_id, exec_conf, outcomes),
+                ));
             }
-            let mut outputs = self.outputs.lock().await;
-            let exec_confs = self.exec_confs.lock().await;
-            for (runtime_id, _) in exec_confs.iter() {
-                outputs.insert(
-                    runtime_id.clone(),
-                    judge_output::ExecutionJobResult::EarlyExit,
-                );
+            let Some(finished) = running.next().await else {
+                break;
+            };
+            match finished? {
+                Finished::File {
+                    runtime_id,
+                    outcome: Ok(outcome),
+                } => {
+                    ready = schedule.resolve(runtime_id, outcome);
+                }
+                Finished::File {
+                    runtime_id,
+                    outcome: Err(e),
+                } => {
+                    // 置けなかったファイルに依存する実行だけを Skipped にする
+                    tracing::warn!("Failed to place file for {}: {}", runtime_id, e);
+                    self.record_output(
+                        &mut schedule.outputs,
+                        runtime_id,
+                        judge_output::ExecutionJobResult::Failed {
+                            reason: e.to_string(),
+                        },
+                        None,
+                    )
+                    .await;
+                    schedule.abandon(runtime_id);
+                }
+                Finished::Execution {
+                    runtime_id,
+                    outcome,
+                    report,
+                } => {
+                    // Report the result before starting the jobs which depend on it
+                    let judge_output::ExecutionJobReport { result, metadata } = *report;
+                    self.record_output(&mut schedule.outputs, runtime_id, result, metadata)
+                        .await;
+                    match outcome {
+                        Some(outcome) => ready = schedule.resolve(runtime_id, outcome),
+                        None => schedule.abandon(runtime_id),
+                    }
+                }
             }
-            tracing::info!("Runner completed");
-            Ok(outputs.clone())
         }
+
+        let not_executed = if self.cancellation.is_cancelled() {
+            judge_output::ExecutionJobResult::Cancelled
+        } else {
+            judge_output::ExecutionJobResult::EarlyExit
+        };
+        // 失敗による打ち切りを条件による打ち切りより優先して報告する
+        let skipped = schedule.find_downstream(|result| {
+            matches!(result, judge_output::ExecutionJobResult::Failed { .. })
+        });
+        let condition_unmet = schedule.find_downstream(|result| {
+            matches!(result, judge_output::ExecutionJobResult::ConditionUnmet)
+        });
+        let not_started = schedule.pending.keys().copied().collect::<Vec<_>>();
+        for runtime_id in not_started {
+            let result = if skipped.contains(&runtime_id) {
+                judge_output::ExecutionJobResult::Skipped
+            } else if condition_unmet.contains(&runtime_id) {
+                judge_output::ExecutionJobResult::ConditionUnmet
+            } else {
+                not_executed.clone()
+            };
+            self.record_output(&mut schedule.outputs, runtime_id, result, None)
+                .await;
+        }
+        tracing::info!("Runner completed");
+        Ok(schedule.outputs)
     }
 
     #[tracing::instrument]
@@ -78,6 +347,14 @@ impl<
             let file_conf = job::FileConf::Text(text.resource_id.clone());
             file_confs.insert(text.runtime_id.clone(), file_conf);
         }
+        for binary in procedure.binaries.iter() {
+            let file_conf = job::FileConf::Binary(binary.resource_id);
+            file_confs.insert(binary.runtime_id, file_conf);
+        }
+        for directory in procedure.directories.iter() {
+            let file_conf = job::FileConf::Directory(directory.resource_id);
+            file_confs.insert(directory.runtime_id, file_conf);
+        }
         for runtime_text in procedure.runtime_texts.iter() {
             let file_conf = job::FileConf::RuntimeText(runtime_text.content.clone());
             file_confs.insert(runtime_text.runtime_id.clone(), file_conf);
@@ -94,7 +371,7 @@ impl<
     async fn create_exec_confs(
         procedure: &runtime::Procedure,
         job_service: &JobServiceType,
-    ) -> anyhow::Result<HashMap<RuntimeId, (ReservationToken, Vec<runtime::Dependency>)>> {
+    ) -> anyhow::Result<HashMap<RuntimeId, ExecConf<ReservationToken>>> {
         tracing::info!("Creating execution configurations");
         let mut reservations_vec = job_service
             .reserve_execution(procedure.executions.len())
@@ -112,9 +389,18 @@ impl<
                 .map(|dep| runtime::Dependency {
                     runtime_id: dep.runtime_id.clone(),
                     envvar_name: dep.envvar_name.clone(),
+                    condition: dep.condition.clone(),
                 })
                 .collect();
-            reservations.insert(execution.runtime_id.clone(), (reservation, dependencies));
+            reservations.insert(
+                execution.runtime_id,
+                ExecConf {
+                    reservation,
+                    dependencies,
+                    time_reserved_ms: execution.time_reserved_ms,
+                    no_cache: execution.no_cache,
+                },
+            );
         }
         tracing::info!("Execution configurations created");
         Ok(reservations)
@@ -125,123 +411,285 @@ impl<
         &self,
         runtime_id: RuntimeId,
         file_conf: job::FileConf,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Finished<OutcomeToken>> {
         tracing::info!("Running file job for {}", runtime_id);
-        let outcome_token = self
-            .job_service
-            .place_file(file_conf)
-            .await
-            .context(format!("Failed to place file for runtime {}", runtime_id))?;
-        tracing::info!("File placed for {}", runtime_id);
-        let outcomes = self.new_outcome(runtime_id, outcome_token).await;
-        tracing::info!("File job for {} completed", runtime_id);
-        self.run_next(&outcomes).await?;
-        tracing::info!("Returning from file job for {}", runtime_id);
-        Ok(())
+        let outcome = self.job_service.place_file(file_conf).await;
+        tracing::info!("File job finished for {}", runtime_id);
+        Ok(Finished::File {
+            runtime_id,
+            outcome,
+        })
     }
 
-    #[tracing::instrument(skip(self, reservation, dependencies))]
+    #[tracing::instrument(skip(self, exec_conf, outcomes))]
     async fn run_execution_job(
         &self,
         runtime_id: RuntimeId,
-        reservation: ReservationToken,
-        dependencies: Vec<job::Dependency<OutcomeToken>>,
-    ) -> anyhow::Result<()> {
+        exec_conf: ExecConf<ReservationToken>,
+        outcomes: HashMap<RuntimeId, OutcomeToken>,
+    ) -> anyhow::Result<Finished<OutcomeToken>> {
         tracing::info!("Running execution job for {}", runtime_id);
-        let (outcome_token, output) = self
-            .job_service
-            .execute(reservation, dependencies)
-            .await
-            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-        tracing::info!("Execution completed for {}", runtime_id);
-        let result = super::output_parser::parse(&output)
-            .map_err(|e| anyhow::anyhow!(e.to_string()))
-            .context("Failed to parse output")?;
-        tracing::info!("Output parsed for {}", runtime_id);
-        if match &result {
-            judge_output::ExecutionResult::Displayable(result_inner) => {
-                result_inner.continue_status.clone()
-            }
-            judge_output::ExecutionResult::Hidden(result_inner) => {
-                result_inner.continue_status.clone()
+        let ready_at_ms = job::unix_time_ms();
+        if let Some(trace) = &self.trace {
+            trace
+                .record_dependencies(&self.job_service, runtime_id, &outcomes)
+                .await;
+        }
+        let dependencies = exec_conf
+            .dependencies
+            .iter()
+            .map(|dep| {
+                outcomes
+                    .get(&dep.runtime_id)
+                    .context("Dependency not satisfied yet")
+                    .map(|outcome| job::Dependency {
+                        envvar: dep.envvar_name.clone(),
+                        outcome: outcome.clone(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        std::mem::drop(outcomes);
+        // 実行が始まる前に終わった場合は started_at_ms を持たない
+        let metadata = |details: job::ExecutionDetails| judge_output::ExecutionMetadata {
+            ready_at_ms,
+            started_at_ms: details.started_at_ms,
+            finished_at_ms: job::unix_time_ms(),
+            host: details.host,
+            retry_count: details.retry_count,
+            outcome_size_bytes: details.outcome_size_bytes,
+            cached: details.cached,
+        };
+        let finished = |outcome, result, metadata| Finished::Execution {
+            runtime_id,
+            outcome,
+            report: Box::new(judge_output::ExecutionJobReport {
+                result,
+                metadata: Some(metadata),
+            }),
+        };
+        // Dropping the in-flight `execute` future aborts the execution
+        let execution = self.job_service.execute(
+            exec_conf.reservation,
+            dependencies,
+            exec_conf.time_reserved_ms,
+            exec_conf.no_cache,
+        );
+        let (outcome_token, output, details) = tokio::select! {
+            result = execution => match result {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("Execution failed for {}: {}", runtime_id, e);
+                    return Ok(finished(
+                        None,
+                        judge_output::ExecutionJobResult::Failed {
+                            reason: e.to_string(),
+                        },
+                        metadata(job::ExecutionDetails::default()),
+                    ));
+                }
+            },
+            _ = self.cancellation.cancelled() => {
+                tracing::info!("Execution cancelled for {}", runtime_id);
+                return Ok(finished(
+                    None,
+                    judge_output::ExecutionJobResult::Cancelled,
+                    metadata(job::ExecutionDetails::default()),
+                ));
             }
-        } == judge_output::ContinueStatus::Continue
-        {
-            let outcomes = self.new_outcome(runtime_id, outcome_token).await;
-            self.run_next(&outcomes)
-                .await
-                .context(format!("Failed to run next job after {}", runtime_id))?;
-        }
-        {
-            let mut outputs = self.outputs.lock().await;
-            outputs.insert(
-                runtime_id,
-                judge_output::ExecutionJobResult::ExecutionResult(result),
-            );
-            std::mem::drop(outputs);
+        };
+        tracing::info!("Execution completed for {}", runtime_id);
+        let metadata = metadata(details);
+        if let Some(trace) = &self.trace {
+            trace.record_output(runtime_id, &output).await;
         }
-        tracing::info!("Returning from execution job for {}", runtime_id);
-        Ok(())
+        let result = match super::output_parser::parse(&output) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("Failed to parse output for {}: {}", runtime_id, e);
+                return Ok(finished(
+                    None,
+                    judge_output::ExecutionJobResult::Failed {
+                        reason: format!("Failed to parse output: {}", e),
+                    },
+                    metadata,
+                ));
+            }
+        };
+        tracing::info!("Output parsed for {}", runtime_id);
+        let outcome = (*result.continue_status() == judge_output::ContinueStatus::Continue)
+            .then_some(outcome_token);
+        Ok(finished(
+            outcome,
+            judge_output::ExecutionJobResult::ExecutionResult(result),
+            metadata,
+        ))
     }
 
-    async fn new_outcome(
+    async fn record_output(
         &self,
+        outputs: &mut HashMap<RuntimeId, judge_output::ExecutionJobReport>,
         runtime_id: RuntimeId,
-        outcome_token: OutcomeToken,
-    ) -> HashMap<RuntimeId, OutcomeToken> {
-        tracing::info!("Creating new outcome for {}", runtime_id);
-        let outcomes = {
-            let mut outcomes = self.outcomes.lock().await;
-            outcomes.insert(runtime_id, outcome_token);
-            let cloned: HashMap<RuntimeId, OutcomeToken> = outcomes.clone();
-            std::mem::drop(outcomes);
-            cloned
-        };
-        tracing::info!("New outcome created for {}", runtime_id);
-        outcomes
-    }
-
-    #[tracing::instrument(skip(self, outcomes))]
-    async fn run_next(&self, outcomes: &HashMap<RuntimeId, OutcomeToken>) -> anyhow::Result<()> {
-        tracing::info!("Running next jobs");
-        let next_job_futures = {
-            let mut next_job_futures = Vec::new();
-            let mut exec_confs = self.exec_confs.lock().await;
-            // Find all jobs which dependencies are all satisfied
-            let mut next_job_dependencies = HashMap::new();
-            for (runtime_id, (_, dependencies)) in exec_confs.iter() {
-                let available_dependencies = dependencies
-                    .iter()
-                    .map(|dep| {
-                        outcomes
-                            .get(&dep.runtime_id)
-                            .context("Dependency not satisfied yet")
-                            .map(|outcome| job::Dependency {
-                                envvar: dep.envvar_name.clone(),
-                                outcome: outcome.clone(),
-                            })
-                    })
-                    .collect::<Result<Vec<_>, _>>()
-                    .ok();
-                if let Some(available_dependencies) = available_dependencies {
-                    next_job_dependencies.insert(runtime_id.clone(), available_dependencies);
-                }
-            }
-            for (runtime_id, dependencies) in next_job_dependencies.into_iter() {
-                let (reservation, _) = exec_confs
-                    .remove(&runtime_id)
-                    .context("Failed to remove exec conf")?;
-                let future = self.run_execution_job(runtime_id, reservation, dependencies);
-                next_job_futures.push(future);
+        result: judge_output::ExecutionJobResult,
+        metadata: Option<judge_output::ExecutionMetadata>,
+    ) {
+        if let Some(trace) = &self.trace {
+            trace.record_result(runtime_id, &result).await;
+        }
+        let report = judge_output::ExecutionJobReport { result, metadata };
+        if let Some(progress) = &self.progress {
+            // The receiver may have been dropped, the result is still returned from `run`
+            let _ = progress.send((runtime_id, report.clone()));
+        }
+        outputs.insert(runtime_id, report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Runner;
+    use crate::logic::fake_job_service::FakeJobService;
+    use crate::model::{
+        cancellation::CancellationToken,
+        identifiers::RuntimeId,
+        judge_output::*,
+        procedure::{condition::Condition, runtime},
+    };
+    use std::sync::atomic::Ordering;
+
+    fn execution(dependencies: &[(RuntimeId, &str, Option<Condition>)]) -> runtime::Execution {
+        runtime::Execution {
+            dependencies: dependencies
+                .iter()
+                .map(|(runtime_id, envvar_name, condition)| runtime::Dependency {
+                    runtime_id: *runtime_id,
+                    envvar_name: envvar_name.to_string(),
+                    condition: condition.clone(),
+                })
+                .collect(),
+            runtime_id: RuntimeId::new(),
+            time_reserved_ms: 1000,
+            no_cache: false,
+        }
+    }
+
+    fn status(report: &ExecutionJobReport) -> Option<JudgeStatus> {
+        match &report.result {
+            ExecutionJobResult::ExecutionResult(ExecutionResult::Displayable(result)) => {
+                Some(result.status.clone())
             }
-            std::mem::drop(exec_confs);
-            next_job_futures
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn guards_branches_and_releases_outcomes() {
+        let directory = RuntimeId::new();
+        let run = execution(&[(directory, "WA", None)]);
+        let on_ac = execution(&[(
+            run.runtime_id,
+            "RUN",
+            Some(Condition::StatusIn(vec![JudgeStatus::AC])),
+        )]);
+        let after_on_ac = execution(&[(on_ac.runtime_id, "ON_AC", None)]);
+        let fallback = execution(&[(
+            run.runtime_id,
+            "RUN",
+            Some(Condition::StatusNotIn(vec![JudgeStatus::AC])),
+        )]);
+        let mut executions = vec![run, on_ac, after_on_ac, fallback];
+        for _ in 0..500 {
+            let previous = executions.last().unwrap().runtime_id;
+            executions.push(execution(&[(previous, "PREVIOUS", None)]));
+        }
+        let ids = executions
+            .iter()
+            .map(|execution| execution.runtime_id)
+            .collect::<Vec<_>>();
+        let procedure = runtime::Procedure {
+            runtime_texts: vec![],
+            texts: vec![],
+            binaries: vec![],
+            directories: vec![],
+            empty_directories: vec![runtime::EmptyDirectory {
+                runtime_id: directory,
+            }],
+            executions,
         };
-        futures::future::join_all(next_job_futures)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
-        tracing::info!("Next jobs completed");
-        Ok(())
+
+        let job_service = FakeJobService::default();
+        let runner = Runner::new(
+            job_service.clone(),
+            procedure,
+            CancellationToken::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let outputs = runner.run().await.unwrap();
+
+        assert_eq!(outputs.len(), ids.len());
+        assert_eq!(status(&outputs[&ids[0]]), Some(JudgeStatus::WA));
+        for unmet in [ids[1], ids[2]] {
+            assert!(matches!(
+                outputs[&unmet].result,
+                ExecutionJobResult::ConditionUnmet
+            ));
+        }
+        for ran in ids[3..].iter() {
+            assert_eq!(status(&outputs[ran]), Some(JudgeStatus::AC));
+        }
+        // 直前の Execution の outcome だけが残っている
+        assert!(job_service.max_alive.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn failed_files_skip_only_their_dependents() {
+        let broken = RuntimeId::new();
+        let directory = RuntimeId::new();
+        let reads_broken = execution(&[(broken, "INPUT", None)]);
+        let after_broken = execution(&[(reads_broken.runtime_id, "PREVIOUS", None)]);
+        let independent = execution(&[(directory, "DIR", None)]);
+        let ids = [
+            reads_broken.runtime_id,
+            after_broken.runtime_id,
+            independent.runtime_id,
+        ];
+        let procedure = runtime::Procedure {
+            runtime_texts: vec![runtime::RuntimeText {
+                content: "FAIL".to_string(),
+                runtime_id: broken,
+            }],
+            texts: vec![],
+            binaries: vec![],
+            directories: vec![],
+            empty_directories: vec![runtime::EmptyDirectory {
+                runtime_id: directory,
+            }],
+            executions: vec![reads_broken, after_broken, independent],
+        };
+
+        let runner = Runner::new(
+            FakeJobService::default(),
+            procedure,
+            CancellationToken::new(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let outputs = runner.run().await.unwrap();
+
+        assert!(matches!(
+            &outputs[&broken].result,
+            ExecutionJobResult::Failed { reason } if reason.contains("broken")
+        ));
+        for skipped in [ids[0], ids[1]] {
+            assert!(matches!(
+                outputs[&skipped].result,
+                ExecutionJobResult::Skipped
+            ));
+        }
+        assert_eq!(status(&outputs[&ids[2]]), Some(JudgeStatus::AC));
     }
 }
@@ -0,0 +1,145 @@
+use crate::model::procedure::writer_schema;
+use serde_json::Value;
+
+const VERSION_KEY: &str = "version";
+
+/// Upgrades a document from the version equal to its index to the next one.
+pub type Migration = fn(&mut Value) -> Result<(), MigrationError>;
+
+/// Migrations of `writer_schema::Procedure`; its length must equal `writer_schema::SCHEMA_VERSION`
+const WRITER_SCHEMA_MIGRATIONS: [Migration; writer_schema::SCHEMA_VERSION as usize] = [
+    // v0 has no version field; the layout is otherwise the same as v1
+    |_| Ok(()),
+    // v2 adds `ResourceKind::BinaryFile`, which no v1 document contains
+    |_| Ok(()),
+    // v3 adds `ResourceKind::Directory`, which no v2 document contains
+    |_| Ok(()),
+    // v4 adds `Execution::no_cache`; v3 executions were always cached
+    |document| {
+        for execution in array_mut(document, "executions")? {
+            object_mut(execution)?
+                .entry("no_cache")
+                .or_insert(Value::Bool(false));
+        }
+        Ok(())
+    },
+    // v5 adds `Dependency::condition`; v4 dependencies always held
+    |document| {
+        for execution in array_mut(document, "executions")? {
+            for dependency in array_mut(execution, "dependencies")? {
+                object_mut(dependency)?
+                    .entry("condition")
+                    .or_insert(Value::Null);
+            }
+        }
+        Ok(())
+    },
+    // v6 adds `Procedure::metadata`; roles of v5 jobs are guessed from their names
+    |document| {
+        object_mut(document)?
+            .entry("metadata")
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        Ok(())
+    },
+];
+
+/// Upgrades `document` to the latest version known by `migrations`.
+/// Documents without a version field are treated as version 0.
+pub fn migrate(mut document: Value, migrations: &[Migration]) -> Result<Value, MigrationError> {
+    let supported = migrations.len() as u32;
+    let found = match document.get(VERSION_KEY) {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| MigrationError::Malformed(format!("Invalid version {}", version)))?,
+    };
+    if found > supported {
+        return Err(MigrationError::UnsupportedVersion { found, supported });
+    }
+    for (version, migration) in migrations.iter().enumerate().skip(found as usize) {
+        migration(&mut document)?;
+        object_mut(&mut document)?.insert(VERSION_KEY.to_string(), Value::from(version as u32 + 1));
+    }
+    Ok(document)
+}
+
+fn object_mut(value: &mut Value) -> Result<&mut serde_json::Map<String, Value>, MigrationError> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| MigrationError::Malformed("Expected a JSON object".to_string()))
+}
+
+fn array_mut<'a>(value: &'a mut Value, key: &str) -> Result<&'a mut Vec<Value>, MigrationError> {
+    object_mut(value)?
+        .get_mut(key)
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| MigrationError::Malformed(format!("\"{}\" is not a JSON array", key)))
+}
+
+/// Parses a writer-schema document of any supported version
+pub fn load_writer_schema(json: &str) -> Result<writer_schema::Procedure, MigrationError> {
+    let document =
+        serde_json::from_str(json).map_err(|e| MigrationError::Malformed(e.to_string()))?;
+    let document = migrate(document, &WRITER_SCHEMA_MIGRATIONS)?;
+    serde_json::from_value(document).map_err(|e| MigrationError::Malformed(e.to_string()))
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum MigrationError {
+    #[error(
+        "Document version {found} is newer than the latest supported version {supported}; upgrade the judge libraries"
+    )]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("Malformed document: {0}")]
+    Malformed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_unversioned_writer_schema() {
+        let json = r#"{"resources": [], "executions": [], "scripts": []}"#;
+        let procedure = load_writer_schema(json).unwrap();
+        assert_eq!(procedure.version, writer_schema::SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn upgrades_older_writer_schema() {
+        for version in 1..writer_schema::SCHEMA_VERSION {
+            let json = format!(
+                r#"{{
+                    "version": {},
+                    "resources": [{{"TextFile": {{"name": "input", "content": "1"}}}}],
+                    "executions": [{{
+                        "name": "run",
+                        "script_name": "script",
+                        "dependencies": [{{"ref_to": "input", "envvar_name": "INPUT"}}],
+                        "time_reserved_ms": 1000
+                    }}],
+                    "scripts": [{{"name": "script", "content": ""}}]
+                }}"#,
+                version
+            );
+            let procedure = load_writer_schema(&json).unwrap();
+            assert_eq!(procedure.version, writer_schema::SCHEMA_VERSION);
+            assert!(!procedure.executions[0].no_cache);
+            assert!(procedure.executions[0].dependencies[0].condition.is_none());
+            assert!(procedure.metadata.is_empty());
+        }
+    }
+
+    #[test]
+    fn refuses_newer_writer_schema() {
+        let json = format!(
+            r#"{{"version": {}, "resources": [], "executions": [], "scripts": []}}"#,
+            writer_schema::SCHEMA_VERSION + 1
+        );
+        assert!(matches!(
+            load_writer_schema(&json),
+            Err(MigrationError::UnsupportedVersion { .. })
+        ));
+    }
+}
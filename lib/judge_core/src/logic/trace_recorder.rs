@@ -0,0 +1,157 @@
+use crate::model::{
+    identifiers::{DepId, RuntimeId},
+    job, judge_output,
+    judge_trace::{JudgeTrace, TracedDependency, TracedExecution, TracedOutput},
+    procedure::runtime,
+};
+use anyhow::Context;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Collects what `Runner` sees while running a procedure, to be turned into a `JudgeTrace`
+#[derive(Debug, Clone)]
+pub struct TraceRecorder {
+    inner: Arc<Mutex<TraceRecorderInner>>,
+}
+
+#[derive(Debug)]
+struct TraceRecorderInner {
+    executions: HashMap<RuntimeId, RecordedExecution>,
+    outcomes: HashMap<RuntimeId, job::OutcomeSnapshot>,
+}
+
+#[derive(Debug)]
+struct RecordedExecution {
+    dependencies: Vec<runtime::Dependency>,
+    time_reserved_ms: u64,
+    no_cache: bool,
+    output: Option<TracedOutput>,
+    result: Option<judge_output::ExecutionJobResult>,
+}
+
+impl TraceRecorder {
+    pub fn new(procedure: &runtime::Procedure) -> Self {
+        let executions = procedure
+            .executions
+            .iter()
+            .map(|execution| {
+                (
+                    execution.runtime_id,
+                    RecordedExecution {
+                        dependencies: execution.dependencies.clone(),
+                        time_reserved_ms: execution.time_reserved_ms,
+                        no_cache: execution.no_cache,
+                        output: None,
+                        result: None,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            inner: Arc::new(Mutex::new(TraceRecorderInner {
+                executions,
+                outcomes: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Snapshots the outcomes `runtime_id` depends on which are not recorded yet.
+    /// A failed snapshot only leaves a hole in the trace, it does not fail the judge.
+    pub async fn record_dependencies<
+        ReservationToken: Send + Sync + 'static,
+        OutcomeToken: Clone + Send + Sync + 'static,
+        JobServiceType: job::JobService<ReservationToken, OutcomeToken>,
+    >(
+        &self,
+        job_service: &JobServiceType,
+        runtime_id: RuntimeId,
+        outcomes: &HashMap<RuntimeId, OutcomeToken>,
+    ) {
+        let missing = {
+            let inner = self.inner.lock().await;
+            let Some(execution) = inner.executions.get(&runtime_id) else {
+                return;
+            };
+            execution
+                .dependencies
+                .iter()
+                .map(|dep| dep.runtime_id)
+                .filter(|dependency| !inner.outcomes.contains_key(dependency))
+                .collect::<Vec<_>>()
+        };
+        for dependency in missing {
+            let Some(outcome) = outcomes.get(&dependency) else {
+                continue;
+            };
+            match job_service.snapshot(outcome).await {
+                Ok(snapshot) => {
+                    let mut inner = self.inner.lock().await;
+                    inner.outcomes.insert(dependency, snapshot);
+                }
+                Err(e) => tracing::warn!("Failed to snapshot {} for the trace: {}", dependency, e),
+            }
+        }
+    }
+
+    pub async fn record_output(&self, runtime_id: RuntimeId, output: &std::process::Output) {
+        let mut inner = self.inner.lock().await;
+        if let Some(execution) = inner.executions.get_mut(&runtime_id) {
+            execution.output = Some(output.into());
+        }
+    }
+
+    pub async fn record_result(
+        &self,
+        runtime_id: RuntimeId,
+        result: &judge_output::ExecutionJobResult,
+    ) {
+        let mut inner = self.inner.lock().await;
+        if let Some(execution) = inner.executions.get_mut(&runtime_id) {
+            execution.result = Some(result.clone());
+        }
+    }
+
+    pub async fn finish(
+        &self,
+        identifier_map: &HashMap<RuntimeId, DepId>,
+    ) -> anyhow::Result<JudgeTrace> {
+        let dep_id = |runtime_id: &RuntimeId| {
+            identifier_map
+                .get(runtime_id)
+                .copied()
+                .context(format!("DepId not found for {}", runtime_id))
+        };
+        let inner = self.inner.lock().await;
+        let mut executions = Vec::new();
+        for (runtime_id, execution) in inner.executions.iter() {
+            let dependencies = execution
+                .dependencies
+                .iter()
+                .map(|dep| {
+                    Ok(TracedDependency {
+                        dep_id: dep_id(&dep.runtime_id)?,
+                        envvar_name: dep.envvar_name.clone(),
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            executions.push(TracedExecution {
+                dep_id: dep_id(runtime_id)?,
+                dependencies,
+                time_reserved_ms: execution.time_reserved_ms,
+                no_cache: execution.no_cache,
+                output: execution.output.clone(),
+                result: execution.result.clone(),
+            });
+        }
+        let outcomes = inner
+            .outcomes
+            .iter()
+            .map(|(runtime_id, snapshot)| Ok((dep_id(runtime_id)?, snapshot.clone())))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+        Ok(JudgeTrace {
+            executions,
+            outcomes,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+use crate::model::{identifiers::DepId, job, judge_output, judge_trace::JudgeTrace};
+use anyhow::Context;
+
+#[derive(Debug, Clone)]
+pub struct ReplayedExecution {
+    /// `None` if the execution itself failed
+    pub output: Option<std::process::Output>,
+    pub result: judge_output::ExecutionJobResult,
+}
+
+/// Runs the execution `dep_id` of `trace` again on `job_service`,
+/// placing its dependencies from the snapshots in the trace.
+/// The result is never taken from a cache.
+pub async fn replay<
+    ReservationToken: Send + Sync + 'static,
+    OutcomeToken: Clone + Send + Sync + 'static,
+    JobServiceType: job::JobService<ReservationToken, OutcomeToken>,
+>(
+    job_service: &JobServiceType,
+    trace: &JudgeTrace,
+    dep_id: DepId,
+) -> anyhow::Result<ReplayedExecution> {
+    let execution = trace
+        .execution(dep_id)
+        .context(format!("Execution {} is not in the trace", dep_id))?;
+    let mut dependencies = Vec::new();
+    for dependency in execution.dependencies.iter() {
+        let snapshot = trace.outcomes.get(&dependency.dep_id).context(format!(
+            "Outcome of {} was not recorded, {} cannot be replayed",
+            dependency.dep_id, dep_id
+        ))?;
+        let outcome = job_service
+            .place_file(job::FileConf::Snapshot(snapshot.clone()))
+            .await
+            .context(format!("Failed to place {}", dependency.dep_id))?;
+        dependencies.push(job::Dependency {
+            envvar: dependency.envvar_name.clone(),
+            outcome,
+        });
+    }
+    let reservation = job_service
+        .reserve_execution(1)
+        .await
+        .context("Failed to reserve execution")?
+        .pop()
+        .context("Failed to reserve execution")?;
+    let output = match job_service
+        .execute(reservation, dependencies, execution.time_reserved_ms, true)
+        .await
+    {
+        Ok((_, output, _)) => output,
+        Err(e) => {
+            return Ok(ReplayedExecution {
+                output: None,
+                result: judge_output::ExecutionJobResult::Failed {
+                    reason: e.to_string(),
+                },
+            });
+        }
+    };
+    // Runner と同じ基準で結果を解釈する
+    let result = match super::output_parser::parse(&output) {
+        Ok(result) => judge_output::ExecutionJobResult::ExecutionResult(result),
+        Err(e) => judge_output::ExecutionJobResult::Failed {
+            reason: format!("Failed to parse output: {}", e),
+        },
+    };
+    Ok(ReplayedExecution {
+        output: Some(output),
+        result,
+    })
+}
@@ -1,2 +1,2 @@
 mod has_cycle;
-pub mod runtime_procedure;
+pub mod writer_schema;
@@ -0,0 +1,291 @@
+use super::has_cycle::{Edge, has_cycle};
+use crate::{
+    constant::env_var_exec,
+    model::{problem_registry::SchemaDiagnostic, procedure::writer_schema::*},
+};
+use std::collections::{HashMap, HashSet};
+use std::path::Component;
+
+/// Collects every problem found in a writer-schema procedure.
+/// An empty result means the procedure can be registered.
+pub fn validate(procedure: &Procedure) -> Vec<SchemaDiagnostic> {
+    let mut diagnostics = Vec::new();
+    if procedure.version > SCHEMA_VERSION {
+        diagnostics.push(SchemaDiagnostic::UnsupportedVersion {
+            found: procedure.version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    let resource_names: Vec<&str> = procedure.resources.iter().map(ResourceKind::name).collect();
+    let script_names: HashSet<&str> = procedure.scripts.iter().map(|s| s.name.as_str()).collect();
+    let executions: HashMap<&str, &Execution> = procedure
+        .executions
+        .iter()
+        .map(|execution| (execution.name.as_str(), execution))
+        .collect();
+    let is_static = |name: &str| resource_names.contains(&name) || script_names.contains(name);
+
+    let mut consumed = HashSet::new();
+    let mut invalid = HashSet::new();
+    for execution in procedure.executions.iter() {
+        if !script_names.contains(execution.script_name.as_str()) {
+            diagnostics.push(SchemaDiagnostic::MissingScript {
+                execution: execution.name.clone(),
+                script_name: execution.script_name.clone(),
+            });
+            invalid.insert(execution.name.as_str());
+        }
+        let mut envvar_names = HashSet::new();
+        for dep in execution.dependencies.iter() {
+            consumed.insert(dep.ref_to.as_str());
+            if !is_static(&dep.ref_to) && !executions.contains_key(dep.ref_to.as_str()) {
+                diagnostics.push(SchemaDiagnostic::UnknownDependency {
+                    execution: execution.name.clone(),
+                    ref_to: dep.ref_to.clone(),
+                });
+                invalid.insert(execution.name.as_str());
+            } else if dep.condition.is_some() && is_static(&dep.ref_to) {
+                diagnostics.push(SchemaDiagnostic::ConditionOnResource {
+                    execution: execution.name.clone(),
+                    ref_to: dep.ref_to.clone(),
+                });
+                invalid.insert(execution.name.as_str());
+            }
+            if env_var_exec::RESERVED.contains(&dep.envvar_name.as_str()) {
+                diagnostics.push(SchemaDiagnostic::ReservedEnvvar {
+                    execution: execution.name.clone(),
+                    envvar_name: dep.envvar_name.clone(),
+                });
+            } else if !envvar_names.insert(dep.envvar_name.as_str()) {
+                diagnostics.push(SchemaDiagnostic::DuplicateEnvvar {
+                    execution: execution.name.clone(),
+                    envvar_name: dep.envvar_name.clone(),
+                });
+            }
+        }
+    }
+
+    let edges = procedure
+        .executions
+        .iter()
+        .flat_map(|execution| {
+            execution
+                .dependencies
+                .iter()
+                .filter(|dep| executions.contains_key(dep.ref_to.as_str()))
+                .map(|dep| Edge {
+                    from: dep.ref_to.as_str(),
+                    to: execution.name.as_str(),
+                })
+        })
+        .collect();
+    match has_cycle(edges) {
+        Ok(true) => diagnostics.push(SchemaDiagnostic::Cycle),
+        Ok(false) => {}
+        Err(e) => diagnostics.push(SchemaDiagnostic::CycleCheckFailed {
+            reason: e.to_string(),
+        }),
+    }
+
+    // 依存がすべて揃いうる Execution を不動点まで広げる
+    let mut reachable = HashSet::new();
+    loop {
+        let before = reachable.len();
+        for execution in procedure.executions.iter() {
+            let name = execution.name.as_str();
+            if reachable.contains(name) || invalid.contains(name) {
+                continue;
+            }
+            if execution
+                .dependencies
+                .iter()
+                .all(|dep| is_static(&dep.ref_to) || reachable.contains(dep.ref_to.as_str()))
+            {
+                reachable.insert(name);
+            }
+        }
+        if reachable.len() == before {
+            break;
+        }
+    }
+    for execution in procedure.executions.iter() {
+        let name = execution.name.as_str();
+        if !reachable.contains(name) && !invalid.contains(name) {
+            diagnostics.push(SchemaDiagnostic::UnreachableExecution {
+                execution: execution.name.clone(),
+            });
+        }
+    }
+
+    for resource in procedure.resources.iter() {
+        if let ResourceKind::Directory(directory) = resource {
+            if let Err(reason) = check_archive(&directory.archive) {
+                diagnostics.push(SchemaDiagnostic::InvalidArchive {
+                    name: directory.name.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    for name in procedure.metadata.keys() {
+        if !is_static(name) && !executions.contains_key(name.as_str()) {
+            diagnostics.push(SchemaDiagnostic::MetadataForUnknownJob { name: name.clone() });
+        }
+    }
+
+    for name in resource_names {
+        if !consumed.contains(name) {
+            diagnostics.push(SchemaDiagnostic::UnusedResource {
+                name: name.to_string(),
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Every entry must stay inside the directory once unpacked
+fn check_archive(archive: &[u8]) -> Result<(), String> {
+    let mut archive = tar::Archive::new(archive);
+    for entry in archive.entries().map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path().map_err(|e| e.to_string())?;
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(format!("{} escapes the directory", path.display()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use crate::model::{
+        judge_output::JudgeStatus,
+        problem_registry::SchemaDiagnostic,
+        procedure::{condition::Condition, writer_schema::*},
+    };
+
+    fn execution(name: &str, deps: &[(&str, &str)]) -> Execution {
+        Execution {
+            name: name.to_string(),
+            script_name: "script".to_string(),
+            dependencies: deps
+                .iter()
+                .map(|(ref_to, envvar_name)| Dependency {
+                    ref_to: ref_to.to_string(),
+                    envvar_name: envvar_name.to_string(),
+                    condition: None,
+                })
+                .collect(),
+            time_reserved_ms: 1000,
+            no_cache: false,
+        }
+    }
+
+    fn procedure(resources: &[&str], executions: Vec<Execution>) -> Procedure {
+        Procedure {
+            version: SCHEMA_VERSION,
+            resources: resources
+                .iter()
+                .map(|name| {
+                    ResourceKind::EmptyDirectory(EmptyDirectory {
+                        name: name.to_string(),
+                    })
+                })
+                .collect(),
+            executions,
+            scripts: vec![Text {
+                name: "script".to_string(),
+                content: String::new(),
+            }],
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn valid_procedure() {
+        let procedure = procedure(
+            &["dir"],
+            vec![
+                execution("build", &[("dir", "DIR")]),
+                execution("run", &[("build", "BUILD"), ("dir", "DIR")]),
+            ],
+        );
+        assert_eq!(validate(&procedure), vec![]);
+    }
+
+    #[test]
+    fn reports_each_problem() {
+        let mut missing_script = execution("missing_script", &[]);
+        missing_script.script_name = "nowhere".to_string();
+        let procedure = procedure(
+            &["unused"],
+            vec![
+                missing_script,
+                execution("a", &[("b", "B")]),
+                execution("b", &[("a", "A")]),
+                execution("envvars", &[("script", "X"), ("script", "X")]),
+                execution("reserved", &[("script", "TRAOJUDGE_EXEC_OUTPUT")]),
+                execution("downstream", &[("missing_script", "M")]),
+            ],
+        );
+        let diagnostics = validate(&procedure);
+        for expected in [
+            SchemaDiagnostic::MissingScript {
+                execution: "missing_script".to_string(),
+                script_name: "nowhere".to_string(),
+            },
+            SchemaDiagnostic::DuplicateEnvvar {
+                execution: "envvars".to_string(),
+                envvar_name: "X".to_string(),
+            },
+            SchemaDiagnostic::ReservedEnvvar {
+                execution: "reserved".to_string(),
+                envvar_name: "TRAOJUDGE_EXEC_OUTPUT".to_string(),
+            },
+            SchemaDiagnostic::Cycle,
+            SchemaDiagnostic::UnreachableExecution {
+                execution: "a".to_string(),
+            },
+            SchemaDiagnostic::UnreachableExecution {
+                execution: "downstream".to_string(),
+            },
+            SchemaDiagnostic::UnusedResource {
+                name: "unused".to_string(),
+            },
+        ] {
+            assert!(diagnostics.contains(&expected), "{expected} not reported");
+        }
+        assert_eq!(diagnostics.len(), 8);
+    }
+
+    #[test]
+    fn condition_only_on_execution() {
+        let guarded = |ref_to: &str| {
+            let mut guarded = execution("guarded", &[(ref_to, "DEP")]);
+            guarded.dependencies[0].condition = Some(Condition::StatusIn(vec![JudgeStatus::AC]));
+            guarded
+        };
+        let valid = procedure(
+            &["dir"],
+            vec![execution("build", &[("dir", "DIR")]), guarded("build")],
+        );
+        assert_eq!(validate(&valid), vec![]);
+
+        let invalid = procedure(
+            &["dir"],
+            vec![execution("build", &[("dir", "DIR")]), guarded("dir")],
+        );
+        assert_eq!(
+            validate(&invalid),
+            vec![SchemaDiagnostic::ConditionOnResource {
+                execution: "guarded".to_string(),
+                ref_to: "dir".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    constant::env_var_exec,
+    model::{
+        dep_name_repository::DepNameRepository,
+        identifiers::{DepId, ResourceId},
+        problem_registry::{ProblemRegistryClient, ResourceFetchError},
+        procedure::{job_metadata::JobMetadata, registered, writer_schema::*},
+    },
+};
+use futures::future::join_all;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Turns a registered procedure back into the writer schema it was registered from,
+/// fetching every resource from the registry.
+pub async fn export<
+    PRClient: ProblemRegistryClient,
+    DNRepo: DepNameRepository<IdType>,
+    IdType: Clone,
+>(
+    procedure: registered::Procedure,
+    pr_client: PRClient,
+    dn_repo: DNRepo,
+    problem_id: IdType,
+) -> Result<Procedure, ExportError> {
+    let dep_names = dn_repo
+        .get_many_by_problem_id(problem_id.clone())
+        .await
+        .map_err(|e| ExportError::InternalError(e.to_string()))?;
+    let dep_metadata = dn_repo
+        .get_metadata_by_problem_id(problem_id)
+        .await
+        .map_err(|e| ExportError::InternalError(e.to_string()))?;
+    let resource_ids: HashSet<ResourceId> = procedure
+        .texts
+        .iter()
+        .map(|text| text.resource_id)
+        .chain(procedure.binaries.iter().map(|binary| binary.resource_id))
+        .chain(
+            procedure
+                .directories
+                .iter()
+                .map(|directory| directory.resource_id),
+        )
+        .collect();
+    let futures = resource_ids.into_iter().map(|resource_id| {
+        let pr_client = pr_client.clone();
+        async move {
+            let content = pr_client.fetch(resource_id).await?;
+            Ok::<_, ResourceFetchError>((resource_id, content))
+        }
+    });
+    let contents = join_all(futures)
+        .await
+        .into_iter()
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    restore(procedure, &dep_names, &dep_metadata, &contents)
+}
+
+/// Inverse of the registerer's transpilation: every `SCRIPT_PATH` dependency is turned back into a script.
+pub fn restore(
+    procedure: registered::Procedure,
+    dep_names: &HashMap<DepId, String>,
+    dep_metadata: &HashMap<DepId, JobMetadata>,
+    contents: &HashMap<ResourceId, Vec<u8>>,
+) -> Result<Procedure, ExportError> {
+    let name_of = |dep_id: &DepId| {
+        dep_names
+            .get(dep_id)
+            .cloned()
+            .ok_or(ExportError::MissingDepName(*dep_id))
+    };
+    let content_of = |resource_id: &ResourceId| {
+        contents
+            .get(resource_id)
+            .cloned()
+            .ok_or(ExportError::MissingContent(*resource_id))
+    };
+    let text_of = |name: &str, resource_id: &ResourceId| {
+        String::from_utf8(content_of(resource_id)?)
+            .map_err(|_| ExportError::NotText(name.to_string()))
+    };
+
+    let script_ids: HashSet<DepId> = procedure
+        .executions
+        .iter()
+        .flat_map(|execution| execution.dependencies.iter())
+        .filter(|dependency| dependency.envvar_name == env_var_exec::SCRIPT_PATH)
+        .map(|dependency| dependency.dep_id)
+        .collect();
+
+    let mut resources = Vec::new();
+    let mut scripts = Vec::new();
+    for runtime_text in procedure.runtime_texts.iter() {
+        resources.push(ResourceKind::RuntimeTextFile(RuntimeText {
+            name: name_of(&runtime_text.dep_id)?,
+            label: runtime_text.label.clone(),
+        }));
+    }
+    for text in procedure.texts.iter() {
+        let name = name_of(&text.dep_id)?;
+        let restored = Text {
+            content: text_of(&name, &text.resource_id)?,
+            name,
+        };
+        if script_ids.contains(&text.dep_id) {
+            scripts.push(restored);
+        } else {
+            resources.push(ResourceKind::TextFile(restored));
+        }
+    }
+    for binary in procedure.binaries.iter() {
+        resources.push(ResourceKind::BinaryFile(Binary {
+            name: name_of(&binary.dep_id)?,
+            content: content_of(&binary.resource_id)?,
+        }));
+    }
+    for directory in procedure.directories.iter() {
+        resources.push(ResourceKind::Directory(Directory {
+            name: name_of(&directory.dep_id)?,
+            archive: content_of(&directory.resource_id)?,
+        }));
+    }
+    for empty_directory in procedure.empty_directories.iter() {
+        resources.push(ResourceKind::EmptyDirectory(EmptyDirectory {
+            name: name_of(&empty_directory.dep_id)?,
+        }));
+    }
+
+    let mut executions = Vec::new();
+    for execution in procedure.executions.iter() {
+        let mut script_name = None;
+        let mut dependencies = Vec::new();
+        for dependency in execution.dependencies.iter() {
+            if dependency.envvar_name == env_var_exec::SCRIPT_PATH {
+                script_name = Some(name_of(&dependency.dep_id)?);
+            } else {
+                dependencies.push(Dependency {
+                    ref_to: name_of(&dependency.dep_id)?,
+                    envvar_name: dependency.envvar_name.clone(),
+                    condition: dependency.condition.clone(),
+                });
+            }
+        }
+        let name = name_of(&execution.dep_id)?;
+        executions.push(Execution {
+            script_name: script_name.ok_or_else(|| ExportError::MissingScript(name.clone()))?,
+            name,
+            dependencies,
+            time_reserved_ms: execution.time_reserved_ms,
+            no_cache: execution.no_cache,
+        });
+    }
+
+    let metadata = dep_metadata
+        .iter()
+        .map(|(dep_id, metadata)| Ok((name_of(dep_id)?, metadata.clone())))
+        .collect::<Result<BTreeMap<_, _>, ExportError>>()?;
+
+    Ok(Procedure {
+        version: SCHEMA_VERSION,
+        resources,
+        executions,
+        scripts,
+        metadata,
+    })
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExportError {
+    #[error("No name is recorded for dep {0}")]
+    MissingDepName(DepId),
+    #[error("Content of resource {0} is missing")]
+    MissingContent(ResourceId),
+    #[error("{0} is not UTF-8 text")]
+    NotText(String),
+    #[error("Execution {0} has no script")]
+    MissingScript(String),
+    #[error(transparent)]
+    Fetch(#[from] ResourceFetchError),
+    #[error("Internal error while exporting a procedure: {0}")]
+    InternalError(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::restore;
+    use crate::logic::{
+        problem_presets::normal_judge::{NormalJudgeTestcase, create_normal_judge_procedure},
+        writer_schema_registerer::{metadata_by_dep_id, transpile_inner},
+    };
+    use crate::model::procedure::writer_schema::*;
+
+    fn normalized(mut procedure: Procedure) -> serde_json::Value {
+        procedure.resources.sort_by(|a, b| a.name().cmp(b.name()));
+        procedure.executions.sort_by(|a, b| a.name.cmp(&b.name));
+        procedure.scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        serde_json::to_value(procedure).unwrap()
+    }
+
+    #[test]
+    fn restores_registered_procedure() {
+        let original = create_normal_judge_procedure(vec![NormalJudgeTestcase {
+            name: "sample".to_string(),
+            input: "1 2\n".to_string(),
+            expected_output: "3\n".to_string(),
+            group: None,
+        }])
+        .unwrap();
+        let (registered, contents, dep_names) = transpile_inner(original.clone()).unwrap();
+        let dep_metadata = metadata_by_dep_id(&original.metadata, &dep_names);
+        let restored = restore(registered, &dep_names, &dep_metadata, &contents).unwrap();
+        assert_eq!(normalized(restored), normalized(original));
+    }
+}
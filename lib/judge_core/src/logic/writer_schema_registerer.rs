@@ -2,34 +2,51 @@
 
 use crate::{
     constant::env_var_exec,
+    logic::validation,
     model::{
         dep_name_repository::*,
         problem_registry::*,
-        procedure::{writer_schema::*, *},
+        procedure::{job_metadata::JobMetadata, writer_schema::*, *},
         *,
     },
 };
 use futures::future::join_all;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 pub async fn register<
     PRServer: ProblemRegistryServer,
     DNRepo: DepNameRepository<IdType>,
-    IdType,
+    IdType: Clone,
 >(
     problem: writer_schema::Procedure,
     pr_server: PRServer,
     dn_repo: DNRepo,
     problem_id: IdType,
 ) -> Result<registered::Procedure, RegistrationError> {
+    let diagnostics = validation::writer_schema::validate(&problem);
+    if !diagnostics.is_empty() {
+        return Err(RegistrationError::InvalidSchema(diagnostics));
+    }
+    let metadata = problem.metadata.clone();
     let (procedure, content_to_id, name_to_id) = transpile_inner(problem)?;
+    let dep_id_to_metadata = metadata_by_dep_id(&metadata, &name_to_id);
+    dn_repo
+        .insert_many(problem_id.clone(), name_to_id)
+        .await
+        .map_err(|e| RegistrationError::InternalError(e.to_string()))?;
     dn_repo
-        .insert_many(problem_id, name_to_id)
+        .insert_metadata_many(problem_id, dep_id_to_metadata)
         .await
         .map_err(|e| RegistrationError::InternalError(e.to_string()))?;
     let mut futures = Vec::new();
-    for (content_id, content) in content_to_id.iter() {
-        futures.push(pr_server.register(content_id.clone(), content.clone()));
+    for (content_id, content) in content_to_id.into_iter() {
+        let pr_server = pr_server.clone();
+        futures.push(async move {
+            if pr_server.exists(content_id).await? {
+                return Ok(());
+            }
+            pr_server.register(content_id, content).await
+        });
     }
     join_all(futures)
         .await
@@ -39,25 +56,31 @@ pub async fn register<
     Ok(procedure)
 }
 
-fn transpile_inner(
+/// Re-keys the metadata of a writer schema by the dep ids its names were given
+pub(crate) fn metadata_by_dep_id(
+    metadata: &BTreeMap<String, JobMetadata>,
+    dep_names: &HashMap<identifiers::DepId, String>,
+) -> HashMap<identifiers::DepId, JobMetadata> {
+    dep_names
+        .iter()
+        .filter_map(|(dep_id, name)| Some((*dep_id, metadata.get(name)?.clone())))
+        .collect()
+}
+
+pub(crate) fn transpile_inner(
     problem: writer_schema::Procedure,
 ) -> Result<
     (
         registered::Procedure,
-        HashMap<identifiers::ResourceId, String>,
+        HashMap<identifiers::ResourceId, Vec<u8>>,
         HashMap<identifiers::DepId, String>,
     ),
     RegistrationError,
 > {
     let mut name_to_id = HashMap::new();
     for resource in problem.resources.iter() {
-        let name = match resource {
-            ResourceKind::TextFile(content) => content.name.clone(),
-            ResourceKind::EmptyDirectory(empty_dir) => empty_dir.name.clone(),
-            ResourceKind::RuntimeTextFile(runtime_text) => runtime_text.name.clone(),
-        };
         let id = identifiers::DepId::new();
-        name_to_id.insert(name.clone(), id);
+        name_to_id.insert(resource.name().to_string(), id);
     }
     for script in problem.scripts.iter() {
         let name = script.name.clone();
@@ -71,31 +94,38 @@ fn transpile_inner(
     }
     let mut content_to_id = HashMap::new();
     for resource in problem.resources.iter() {
-        if let ResourceKind::TextFile(content) = resource {
-            let id = identifiers::ResourceId::new();
-            content_to_id.insert(content.content.clone(), id);
-        }
+        let content = match resource {
+            ResourceKind::TextFile(text) => text.content.clone().into_bytes(),
+            ResourceKind::BinaryFile(binary) => binary.content.clone(),
+            ResourceKind::Directory(directory) => directory.archive.clone(),
+            _ => continue,
+        };
+        let id = identifiers::ResourceId::from_content(&content);
+        content_to_id.insert(content, id);
     }
     for script in problem.scripts.iter() {
-        let id = identifiers::ResourceId::new();
-        content_to_id.insert(script.content.clone(), id);
+        let content = script.content.clone().into_bytes();
+        let id = identifiers::ResourceId::from_content(&content);
+        content_to_id.insert(content, id);
     }
     let mut runtime_texts = Vec::new();
     let mut texts = Vec::new();
+    let mut binaries = Vec::new();
+    let mut directories = Vec::new();
     let mut empty_directories = Vec::new();
     for resource in problem.resources.iter() {
         match resource {
             ResourceKind::TextFile(content) => {
                 let dep_id = name_to_id
                     .get(&content.name)
-                    .ok_or(RegistrationError::InvalidSchema(
+                    .ok_or(RegistrationError::InternalError(
                         "TextFile name not found".to_string(),
                     ))?
                     .clone();
                 let text = registered::Text {
                     resource_id: content_to_id
-                        .get(&content.content)
-                        .ok_or(RegistrationError::InvalidSchema(
+                        .get(content.content.as_bytes())
+                        .ok_or(RegistrationError::InternalError(
                             "TextFile content not found".to_string(),
                         ))?
                         .clone(),
@@ -103,10 +133,36 @@ fn transpile_inner(
                 };
                 texts.push(text);
             }
+            ResourceKind::BinaryFile(content) => {
+                let dep_id = name_to_id.get(&content.name).copied().ok_or(
+                    RegistrationError::InternalError("BinaryFile name not found".to_string()),
+                )?;
+                let binary = registered::Binary {
+                    resource_id: content_to_id.get(&content.content).copied().ok_or(
+                        RegistrationError::InternalError(
+                            "BinaryFile content not found".to_string(),
+                        ),
+                    )?,
+                    dep_id,
+                };
+                binaries.push(binary);
+            }
+            ResourceKind::Directory(content) => {
+                let dep_id = name_to_id.get(&content.name).copied().ok_or(
+                    RegistrationError::InternalError("Directory name not found".to_string()),
+                )?;
+                let directory = registered::Directory {
+                    resource_id: content_to_id.get(&content.archive).copied().ok_or(
+                        RegistrationError::InternalError("Directory archive not found".to_string()),
+                    )?,
+                    dep_id,
+                };
+                directories.push(directory);
+            }
             ResourceKind::EmptyDirectory(empty_dir) => {
                 let dep_id = name_to_id
                     .get(&empty_dir.name)
-                    .ok_or(RegistrationError::InvalidSchema(
+                    .ok_or(RegistrationError::InternalError(
                         "EmptyDirectory name not found".to_string(),
                     ))?
                     .clone();
@@ -118,7 +174,7 @@ fn transpile_inner(
             ResourceKind::RuntimeTextFile(runtime_text) => {
                 let dep_id = name_to_id
                     .get(&runtime_text.name)
-                    .ok_or(RegistrationError::InvalidSchema(
+                    .ok_or(RegistrationError::InternalError(
                         "RuntimeText name not found".to_string(),
                     ))?
                     .clone();
@@ -134,7 +190,7 @@ fn transpile_inner(
     for execution in problem.executions.iter() {
         let script_id = name_to_id
             .get(&execution.script_name)
-            .ok_or(RegistrationError::InvalidSchema(
+            .ok_or(RegistrationError::InternalError(
                 "Execution script name not found".to_string(),
             ))?
             .clone();
@@ -142,23 +198,25 @@ fn transpile_inner(
         for dep in execution.dependencies.iter() {
             let dep_id = name_to_id
                 .get(&dep.ref_to)
-                .ok_or(RegistrationError::InvalidSchema(
+                .ok_or(RegistrationError::InternalError(
                     "Dependency name not found".to_string(),
                 ))?
                 .clone();
             let dependency = registered::Dependency {
                 dep_id: dep_id.clone(),
                 envvar_name: dep.envvar_name.clone(),
+                condition: dep.condition.clone(),
             };
             dependencies.push(dependency);
         }
         dependencies.push(registered::Dependency {
             dep_id: script_id.clone(),
             envvar_name: env_var_exec::SCRIPT_PATH.to_string(),
+            condition: None,
         });
         let dep_id = name_to_id
             .get(&execution.name)
-            .ok_or(RegistrationError::InvalidSchema(
+            .ok_or(RegistrationError::InternalError(
                 "Execution name not found".to_string(),
             ))?
             .clone();
@@ -166,6 +224,7 @@ fn transpile_inner(
             dependencies,
             dep_id: dep_id,
             time_reserved_ms: execution.time_reserved_ms,
+            no_cache: execution.no_cache,
         };
         executions.push(execution);
     }
@@ -173,13 +232,13 @@ fn transpile_inner(
         let name = script.name.clone();
         let dep_id = name_to_id
             .get(&name)
-            .ok_or(RegistrationError::InvalidSchema(
+            .ok_or(RegistrationError::InternalError(
                 "Script name not found".to_string(),
             ))?
             .clone();
         let resource_id = content_to_id
-            .get(&script.content)
-            .ok_or(RegistrationError::InvalidSchema(
+            .get(script.content.as_bytes())
+            .ok_or(RegistrationError::InternalError(
                 "Script content not found".to_string(),
             ))?
             .clone();
@@ -192,6 +251,8 @@ fn transpile_inner(
     let procedure = registered::Procedure {
         runtime_texts,
         texts,
+        binaries,
+        directories,
         empty_directories,
         executions,
     };
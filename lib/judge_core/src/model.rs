@@ -1,7 +1,10 @@
+pub mod builtin_checker;
+pub mod cancellation;
 pub mod dep_name_repository;
 pub mod identifiers;
 pub mod job;
 pub mod judge;
 pub mod judge_output;
+pub mod judge_trace;
 pub mod problem_registry;
 pub mod procedure;
@@ -0,0 +1,92 @@
+/// Checkers shipped with traopy_util, identified by a spec string such as `float:1e-6:1e-6`
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuiltinChecker {
+    /// Token-wise equality after whitespace normalization
+    Exact,
+    /// Real numbers match within either the absolute or the relative error
+    Float { abs_eps: f64, rel_eps: f64 },
+    /// Token-wise equality ignoring ASCII case
+    CaseInsensitive,
+    /// The same lines in any order
+    UnorderedLines,
+    /// Like `Exact`, but `yes`/`no` tokens are compared ignoring case
+    YesNo,
+}
+
+impl std::fmt::Display for BuiltinChecker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuiltinChecker::Exact => write!(f, "exact"),
+            BuiltinChecker::Float { abs_eps, rel_eps } => {
+                write!(f, "float:{}:{}", abs_eps, rel_eps)
+            }
+            BuiltinChecker::CaseInsensitive => write!(f, "caseInsensitive"),
+            BuiltinChecker::UnorderedLines => write!(f, "unorderedLines"),
+            BuiltinChecker::YesNo => write!(f, "yesNo"),
+        }
+    }
+}
+
+impl std::str::FromStr for BuiltinChecker {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let name = parts.next().unwrap_or_default();
+        let checker = match name {
+            "exact" => BuiltinChecker::Exact,
+            "float" => {
+                let abs_eps = parts
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing absolute error in {}", s))?
+                    .parse::<f64>()?;
+                let rel_eps = parts
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing relative error in {}", s))?
+                    .parse::<f64>()?;
+                if !(abs_eps >= 0.0 && rel_eps >= 0.0) {
+                    return Err(anyhow::anyhow!("Errors must be non-negative in {}", s));
+                }
+                BuiltinChecker::Float { abs_eps, rel_eps }
+            }
+            "caseInsensitive" => BuiltinChecker::CaseInsensitive,
+            "unorderedLines" => BuiltinChecker::UnorderedLines,
+            "yesNo" => BuiltinChecker::YesNo,
+            _ => return Err(anyhow::anyhow!("Unknown checker: {}", s)),
+        };
+        if parts.next().is_some() {
+            return Err(anyhow::anyhow!("Too many parameters in {}", s));
+        }
+        Ok(checker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BuiltinChecker;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(BuiltinChecker::Exact)]
+    #[case(BuiltinChecker::Float { abs_eps: 1e-6, rel_eps: 0.0 })]
+    #[case(BuiltinChecker::CaseInsensitive)]
+    #[case(BuiltinChecker::UnorderedLines)]
+    #[case(BuiltinChecker::YesNo)]
+    fn test_roundtrip(#[case] checker: BuiltinChecker) {
+        assert_eq!(
+            checker.to_string().parse::<BuiltinChecker>().unwrap(),
+            checker
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("float")]
+    #[case("float:1e-6")]
+    #[case("float:-1:0")]
+    #[case("exact:1")]
+    #[case("unknown")]
+    fn test_invalid(#[case] spec: &str) {
+        assert!(spec.parse::<BuiltinChecker>().is_err());
+    }
+}
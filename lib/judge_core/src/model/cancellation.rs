@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Shared flag to stop a running judge. Clones observe the same state.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once `cancel` has been called on this token or any of its clones.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender is owned by `self`, so the channel is never closed here
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+
+    /// Whether both tokens were cloned from the same original
+    pub fn same_as(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.sender, &other.sender)
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
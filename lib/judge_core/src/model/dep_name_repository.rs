@@ -1,7 +1,10 @@
 use super::identifiers;
+use super::procedure::job_metadata::JobMetadata;
 use anyhow::Result;
 use std::collections::HashMap;
 
+/// Names and metadata of the deps of registered procedures.
+/// Removing a dep removes both.
 #[axum::async_trait]
 pub trait DepNameRepository<IdType>: Clone + Send + Sync {
     async fn insert_many(
@@ -14,8 +17,24 @@ pub trait DepNameRepository<IdType>: Clone + Send + Sync {
         dep_ids: Vec<identifiers::DepId>,
     ) -> Result<HashMap<identifiers::DepId, Option<String>>>;
     async fn remove_many(&self, problem_id: IdType) -> Result<()>;
+    async fn remove_by_dep_ids(
+        &self,
+        problem_id: IdType,
+        dep_ids: Vec<identifiers::DepId>,
+    ) -> Result<()>;
     async fn get_many_by_problem_id(
         &self,
         problem_id: IdType,
     ) -> Result<HashMap<identifiers::DepId, String>>;
+    /// Metadata already recorded for a dep is replaced
+    async fn insert_metadata_many(
+        &self,
+        problem_id: IdType,
+        dep_id_to_metadata: HashMap<identifiers::DepId, JobMetadata>,
+    ) -> Result<()>;
+    /// Deps registered without metadata are left out
+    async fn get_metadata_by_problem_id(
+        &self,
+        problem_id: IdType,
+    ) -> Result<HashMap<identifiers::DepId, JobMetadata>>;
 }
@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct DepId {
     id: Uuid,
 }
@@ -67,6 +70,16 @@ impl ResourceId {
     pub fn new() -> Self {
         Self { id: Uuid::new_v4() }
     }
+
+    /// Derives the id from the SHA-256 of `content`, so the same content always gets the same id
+    pub fn from_content(content: &[u8]) -> Self {
+        let hash = Sha256::digest(content);
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        Self {
+            id: uuid::Builder::from_custom_bytes(bytes).into_uuid(),
+        }
+    }
 }
 
 impl std::fmt::Display for ResourceId {
@@ -86,3 +99,38 @@ impl From<Uuid> for ResourceId {
         Self { id }
     }
 }
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct JudgeId {
+    id: Uuid,
+}
+
+impl JudgeId {
+    pub fn new() -> Self {
+        Self { id: Uuid::new_v4() }
+    }
+}
+
+impl Default for JudgeId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for JudgeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl From<JudgeId> for Uuid {
+    fn from(judge_id: JudgeId) -> Self {
+        judge_id.id
+    }
+}
+
+impl From<Uuid> for JudgeId {
+    fn from(id: Uuid) -> Self {
+        Self { id }
+    }
+}
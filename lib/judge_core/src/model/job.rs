@@ -1,4 +1,5 @@
 use super::identifiers::ResourceId;
+use serde::{Deserialize, Serialize};
 use std::process::Output;
 
 #[axum::async_trait]
@@ -14,11 +15,44 @@ pub trait JobService<
 
     async fn place_file(&self, file_conf: FileConf) -> Result<OutcomeToken, FilePlacementError>;
 
+    /// The execution is stopped with `ExecutionError::Timeout` once it has run
+    /// for `time_reserved_ms` of wall-clock time.
+    /// Unless `no_cache` is set, implementations may return the result of an earlier
+    /// execution with the same dependencies instead of running it again.
     async fn execute(
         &self,
         reservation: ReservationToken,
         dependencies: Vec<Dependency<OutcomeToken>>,
-    ) -> Result<(OutcomeToken, Output), ExecutionError>;
+        time_reserved_ms: u64,
+        no_cache: bool,
+    ) -> Result<(OutcomeToken, Output, ExecutionDetails), ExecutionError>;
+
+    /// Copies the content of `outcome` so that it can be placed again later,
+    /// possibly by another `JobService`, with `FileConf::Snapshot`.
+    async fn snapshot(&self, outcome: &OutcomeToken) -> Result<OutcomeSnapshot, SnapshotError>;
+}
+
+/// How a `JobService` ran an execution
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionDetails {
+    /// The instance or host which ran the execution
+    pub host: Option<String>,
+    /// Attempts which failed before the one that produced the result
+    pub retry_count: u32,
+    /// When the execution started to run on `host`, see `unix_time_ms`
+    pub started_at_ms: Option<u64>,
+    /// Total size of the files in the outcome
+    pub outcome_size_bytes: Option<u64>,
+    /// Whether the result was reused from an earlier execution
+    pub cached: bool,
+}
+
+/// Milliseconds since the UNIX epoch
+pub fn unix_time_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -41,13 +75,33 @@ pub enum ExecutionError {
     InternalError(String),
     #[error("Judge process failed with error: {0}")]
     JudgeFailed(String),
+    #[error("Execution exceeded the reserved time of {0} ms")]
+    Timeout(u64),
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("Failed to snapshot outcome with error: {0}")]
+    SnapshotFailed(String),
 }
 
 #[derive(Debug, Clone)]
 pub enum FileConf {
     EmptyDirectory,
     Text(ResourceId),
+    Binary(ResourceId),
+    /// A tar archive placed as a directory tree
+    Directory(ResourceId),
     RuntimeText(String),
+    Snapshot(OutcomeSnapshot),
+}
+
+/// Content of an outcome, detached from the `JobService` which produced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutcomeSnapshot {
+    File(Vec<u8>),
+    /// A tar archive of the directory tree
+    Directory(Vec<u8>),
 }
 
 #[derive(Debug, Clone)]
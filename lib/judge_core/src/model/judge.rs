@@ -1,18 +1,51 @@
 use crate::model::{procedure::*, *};
 
 use anyhow::Result;
+use futures::StreamExt;
 use std::collections::HashMap;
 
 #[axum::async_trait]
 pub trait JudgeService: Clone + Send + Sync + 'static {
     async fn judge(&self, request: JudgeRequest) -> JudgeResponse;
+
+    /// Like `judge`, but yields each result as soon as its execution finishes.
+    /// The default implementation yields everything at once after `judge` returns.
+    fn judge_stream(&self, request: JudgeRequest) -> JudgeResponseStream {
+        let this = self.clone();
+        futures::stream::once(async move { this.judge(request).await })
+            .flat_map(|response| {
+                let items = match response {
+                    Ok(results) => results.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+                futures::stream::iter(items)
+            })
+            .boxed()
+    }
+
+    /// Stop the judge started with `judge_id`.
+    /// Executions which have not finished are reported as `ExecutionJobResult::Cancelled`.
+    /// Does nothing if no such judge is running.
+    async fn cancel(&self, judge_id: identifiers::JudgeId) -> Result<()>;
+
+    /// The trace bundle recorded for `judge_id`, see `judge_trace::JudgeTrace::to_bundle`.
+    /// `None` if no trace was recorded.
+    async fn trace(&self, judge_id: identifiers::JudgeId) -> Result<Option<Vec<u8>>>;
 }
 
 #[derive(Debug, Clone)]
 pub struct JudgeRequest {
+    /// Chosen by the caller, used to cancel the judge later
+    pub judge_id: identifiers::JudgeId,
     pub procedure: registered::Procedure,
     pub runtime_texts: HashMap<String, String>,
 }
 
 pub type JudgeResponse =
-    anyhow::Result<HashMap<identifiers::DepId, judge_output::ExecutionJobResult>>;
+    anyhow::Result<HashMap<identifiers::DepId, judge_output::ExecutionJobReport>>;
+
+/// An `Err` item means the judge failed; no more items follow it.
+pub type JudgeResponseStream = futures::stream::BoxStream<
+    'static,
+    anyhow::Result<(identifiers::DepId, judge_output::ExecutionJobReport)>,
+>;
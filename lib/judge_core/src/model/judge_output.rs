@@ -9,7 +9,11 @@ pub enum JudgeStatus {
     OLE,
     RE,
     CE,
-    WE, // writer error
+    WE,   // writer error
+    IE,   // internal error
+    SKIP, // not run because of an earlier stop
+    PE,   // presentation error
+    PA,   // partially accepted
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,14 +22,44 @@ pub enum ContinueStatus {
     Stop,
 }
 
+/// Longest excerpt of a compile log or stderr kept in a result, in bytes
+pub const EXCERPT_MAX_BYTES: usize = 4096;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplayableExecutionResult {
     pub status: JudgeStatus,
+    /// Time used for judging, usually the CPU time
     pub time: f64,
     pub memory: f64,
     pub score: i64,
+    /// Message from the checker
     pub message: Option<String>,
     pub continue_status: ContinueStatus,
+    /// Head of the compiler output, see `truncate_excerpt`
+    #[serde(default)]
+    pub compile_log: Option<String>,
+    /// Head of the stderr of the contestant's program, see `truncate_excerpt`
+    #[serde(default)]
+    pub stderr: Option<String>,
+    /// Signal which terminated the contestant's program
+    #[serde(default)]
+    pub signal: Option<i32>,
+    #[serde(default)]
+    pub cpu_time: Option<f64>,
+    #[serde(default)]
+    pub wall_time: Option<f64>,
+}
+
+/// Cuts `text` down to at most `EXCERPT_MAX_BYTES` bytes on a char boundary
+pub fn truncate_excerpt(text: &str) -> String {
+    if text.len() <= EXCERPT_MAX_BYTES {
+        return text.to_string();
+    }
+    let mut end = EXCERPT_MAX_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +67,21 @@ pub struct HiddenExecutionResult {
     pub continue_status: ContinueStatus,
 }
 
+/// Result of one of the testcases judged by a batched execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubExecutionResult {
+    /// Name of the testcase
+    pub sub_name: String,
+    pub result: DisplayableExecutionResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchedExecutionResult {
+    /// In the order the testcases were run
+    pub results: Vec<SubExecutionResult>,
+    pub continue_status: ContinueStatus,
+}
+
 /// ExecutionResult will be returned from exec container as stdout
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExecutionResult {
@@ -40,6 +89,18 @@ pub enum ExecutionResult {
     Displayable(DisplayableExecutionResult),
     /// Not displayed to frontend (e.g. for validation)
     Hidden(HiddenExecutionResult),
+    /// Frontend-displayable results of several testcases run by one execution
+    Batched(BatchedExecutionResult),
+}
+
+impl ExecutionResult {
+    pub fn continue_status(&self) -> &ContinueStatus {
+        match self {
+            ExecutionResult::Displayable(result) => &result.continue_status,
+            ExecutionResult::Hidden(result) => &result.continue_status,
+            ExecutionResult::Batched(result) => &result.continue_status,
+        }
+    }
 }
 
 /// ExecutionJobResult is the return value of judge-control
@@ -49,6 +110,51 @@ pub enum ExecutionJobResult {
     ExecutionResult(ExecutionResult),
     /// Early exit
     EarlyExit,
+    /// The judge was cancelled before this execution finished
+    Cancelled,
+    /// The execution itself failed (e.g. the job crashed or printed no valid result)
+    Failed { reason: String },
+    /// Not executed because an execution it depends on failed
+    Skipped,
+    /// Not executed because the guard on one of its dependencies did not hold
+    ConditionUnmet,
+}
+
+/// When and where an execution ran. Times are milliseconds since the UNIX epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionMetadata {
+    /// When all its dependencies became available
+    pub ready_at_ms: u64,
+    /// `None` if it never started to run
+    pub started_at_ms: Option<u64>,
+    pub finished_at_ms: u64,
+    pub host: Option<String>,
+    pub retry_count: u32,
+    pub outcome_size_bytes: Option<u64>,
+    pub cached: bool,
+}
+
+impl ExecutionMetadata {
+    /// Time spent waiting for an instance
+    pub fn queue_ms(&self) -> u64 {
+        let started_at_ms = self.started_at_ms.unwrap_or(self.finished_at_ms);
+        started_at_ms.saturating_sub(self.ready_at_ms)
+    }
+
+    /// Time spent running
+    pub fn wall_ms(&self) -> u64 {
+        self.started_at_ms
+            .map(|started_at_ms| self.finished_at_ms.saturating_sub(started_at_ms))
+            .unwrap_or_default()
+    }
+}
+
+/// An `ExecutionJobResult` together with how the execution ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionJobReport {
+    pub result: ExecutionJobResult,
+    /// `None` if the execution was never scheduled (e.g. `Skipped`)
+    pub metadata: Option<ExecutionMetadata>,
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -58,3 +164,17 @@ pub enum ExecutionOutputParseError {
     #[error("Non-zero exit code: {0}")]
     NonZeroExitCode(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_excerpt_keeps_char_boundary() {
+        assert_eq!(truncate_excerpt("error"), "error");
+        let long = "あ".repeat(EXCERPT_MAX_BYTES);
+        let truncated = truncate_excerpt(&long);
+        assert!(truncated.len() <= EXCERPT_MAX_BYTES);
+        assert!(long.starts_with(&truncated));
+    }
+}
@@ -0,0 +1,187 @@
+use super::{identifiers::DepId, job::OutcomeSnapshot, judge_output::ExecutionJobResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::os::unix::process::ExitStatusExt;
+
+const TRACE_FILE_NAME: &str = "trace.json";
+const OUTCOMES_DIR_NAME: &str = "outcomes";
+const DIRECTORY_SUFFIX: &str = ".tar";
+
+/// Everything needed to re-run the executions of a judge one by one.
+/// Stored as a tar bundle, see `to_bundle`.
+#[derive(Debug, Clone, Default)]
+pub struct JudgeTrace {
+    pub executions: Vec<TracedExecution>,
+    /// Contents of the outcomes which some execution depended on
+    pub outcomes: HashMap<DepId, OutcomeSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracedExecution {
+    pub dep_id: DepId,
+    pub dependencies: Vec<TracedDependency>,
+    pub time_reserved_ms: u64,
+    pub no_cache: bool,
+    /// `None` if the execution did not finish
+    pub output: Option<TracedOutput>,
+    /// `None` if the judge failed before reporting a result
+    pub result: Option<ExecutionJobResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracedDependency {
+    pub dep_id: DepId,
+    pub envvar_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracedOutput {
+    /// Raw wait status, as in `ExitStatusExt::into_raw`
+    pub status: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl From<&std::process::Output> for TracedOutput {
+    fn from(output: &std::process::Output) -> Self {
+        Self {
+            status: output.status.into_raw(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+}
+
+impl From<&TracedOutput> for std::process::Output {
+    fn from(output: &TracedOutput) -> Self {
+        Self {
+            status: std::process::ExitStatus::from_raw(output.status),
+            stdout: output.stdout.clone().into_bytes(),
+            stderr: output.stderr.clone().into_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TraceBundleError {
+    #[error("Failed to read trace bundle: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid {TRACE_FILE_NAME}: {0}")]
+    InvalidTrace(#[from] serde_json::Error),
+    #[error("{TRACE_FILE_NAME} is missing")]
+    MissingTrace,
+    #[error("Unexpected entry {0}")]
+    UnexpectedEntry(String),
+}
+
+impl JudgeTrace {
+    pub fn execution(&self, dep_id: DepId) -> Option<&TracedExecution> {
+        self.executions
+            .iter()
+            .find(|execution| execution.dep_id == dep_id)
+    }
+
+    /// A tar archive holding `trace.json` and one `outcomes/<dep_id>` entry per outcome.
+    /// Directory outcomes are nested archives named `outcomes/<dep_id>.tar`.
+    pub fn to_bundle(&self) -> std::io::Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let trace = serde_json::to_vec_pretty(&self.executions)?;
+        append_file(&mut builder, TRACE_FILE_NAME, &trace)?;
+        for (dep_id, snapshot) in self.outcomes.iter() {
+            match snapshot {
+                OutcomeSnapshot::File(content) => {
+                    let path = format!("{OUTCOMES_DIR_NAME}/{dep_id}");
+                    append_file(&mut builder, &path, content)?;
+                }
+                OutcomeSnapshot::Directory(archive) => {
+                    let path = format!("{OUTCOMES_DIR_NAME}/{dep_id}{DIRECTORY_SUFFIX}");
+                    append_file(&mut builder, &path, archive)?;
+                }
+            }
+        }
+        builder.into_inner()
+    }
+
+    pub fn from_bundle(bundle: &[u8]) -> Result<Self, TraceBundleError> {
+        let mut executions = None;
+        let mut outcomes = HashMap::new();
+        let mut archive = tar::Archive::new(bundle);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            if path == TRACE_FILE_NAME {
+                executions = Some(serde_json::from_slice(&content)?);
+                continue;
+            }
+            let name = path
+                .strip_prefix(OUTCOMES_DIR_NAME)
+                .and_then(|name| name.strip_prefix('/'))
+                .ok_or_else(|| TraceBundleError::UnexpectedEntry(path.clone()))?;
+            let (name, snapshot) = match name.strip_suffix(DIRECTORY_SUFFIX) {
+                Some(name) => (name, OutcomeSnapshot::Directory(content)),
+                None => (name, OutcomeSnapshot::File(content)),
+            };
+            let dep_id = uuid::Uuid::parse_str(name)
+                .map_err(|_| TraceBundleError::UnexpectedEntry(path.clone()))?;
+            outcomes.insert(DepId::from(dep_id), snapshot);
+        }
+        Ok(Self {
+            executions: executions.ok_or(TraceBundleError::MissingTrace)?,
+            outcomes,
+        })
+    }
+}
+
+fn append_file(
+    builder: &mut tar::Builder<Vec<u8>>,
+    path: &str,
+    content: &[u8],
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::judge_output::ExecutionJobResult;
+
+    #[test]
+    fn bundle_round_trip() {
+        let script = DepId::new();
+        let directory = DepId::new();
+        let execution = DepId::new();
+        let trace = JudgeTrace {
+            executions: vec![TracedExecution {
+                dep_id: execution,
+                dependencies: vec![TracedDependency {
+                    dep_id: script,
+                    envvar_name: "SCRIPT".to_string(),
+                }],
+                time_reserved_ms: 1000,
+                no_cache: true,
+                output: Some(TracedOutput {
+                    status: 0,
+                    stdout: "{}".to_string(),
+                    stderr: String::new(),
+                }),
+                result: Some(ExecutionJobResult::Skipped),
+            }],
+            outcomes: HashMap::from([
+                (script, OutcomeSnapshot::File(b"echo hi".to_vec())),
+                (directory, OutcomeSnapshot::Directory(vec![0; 1024])),
+            ]),
+        };
+        let restored = JudgeTrace::from_bundle(&trace.to_bundle().unwrap()).unwrap();
+        assert_eq!(restored.outcomes, trace.outcomes);
+        let restored_execution = restored.execution(execution).unwrap();
+        assert_eq!(restored_execution.dependencies[0].dep_id, script);
+        assert_eq!(restored_execution.output.as_ref().unwrap().stdout, "{}");
+    }
+}
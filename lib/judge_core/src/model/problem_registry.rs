@@ -7,16 +7,27 @@ pub trait ProblemRegistryServer: Clone + Send + Sync {
     async fn register(
         &self,
         resource_id: ResourceId,
-        content: String,
+        content: Vec<u8>,
     ) -> Result<(), RegistrationError>;
 
+    /// Whether the content of `resource_id` is already uploaded.
+    /// Resource ids are content hashes, so an existing resource is not uploaded again;
+    /// content that garbage collection may still remove must be reported as missing.
+    async fn exists(&self, resource_id: ResourceId) -> Result<bool, RegistrationError>;
+
     async fn remove(&self, resource_id: ResourceId) -> Result<(), RemovalError>;
 }
 
 /// ProblemRegistryClient fetches contents of problems from the registry in judge server.
 #[axum::async_trait]
 pub trait ProblemRegistryClient: Clone + Send + Sync + 'static {
-    async fn fetch(&self, resource_id: ResourceId) -> Result<String, ResourceFetchError>;
+    async fn fetch(&self, resource_id: ResourceId) -> Result<Vec<u8>, ResourceFetchError>;
+
+    /// Fetches a resource that is known to be UTF-8 text, such as a testcase
+    async fn fetch_text(&self, resource_id: ResourceId) -> Result<String, ResourceFetchError> {
+        let content = self.fetch(resource_id).await?;
+        String::from_utf8(content).map_err(|_| ResourceFetchError::NotText(resource_id))
+    }
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -25,14 +36,62 @@ pub enum ResourceFetchError {
     FetchFailed(String),
     #[error("Resource {0} not found")]
     NotFound(ResourceId),
+    #[error("Resource {0} is not UTF-8 text")]
+    NotText(ResourceId),
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum RegistrationError {
     #[error("Internal error while registering a problem: {0}")]
     InternalError(String),
-    #[error("Invalid problem procedure schema: {0}")]
-    InvalidSchema(String),
+    #[error("Invalid problem procedure schema: {}", format_diagnostics(.0))]
+    InvalidSchema(Vec<SchemaDiagnostic>),
+}
+
+/// A single problem found while validating a writer-schema procedure.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaDiagnostic {
+    #[error("Schema version {found} is newer than the latest supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("Dependencies between executions form a cycle")]
+    Cycle,
+    #[error("Failed to check dependencies between executions for cycles: {reason}")]
+    CycleCheckFailed { reason: String },
+    #[error("Execution {execution} refers to {script_name}, which is not a script")]
+    MissingScript {
+        execution: String,
+        script_name: String,
+    },
+    #[error("Execution {execution} depends on unknown name {ref_to}")]
+    UnknownDependency { execution: String, ref_to: String },
+    #[error("Execution {execution} guards its dependency on {ref_to}, which is not an execution")]
+    ConditionOnResource { execution: String, ref_to: String },
+    #[error("Execution {execution} binds envvar {envvar_name} more than once")]
+    DuplicateEnvvar {
+        execution: String,
+        envvar_name: String,
+    },
+    #[error("Execution {execution} binds reserved envvar {envvar_name}")]
+    ReservedEnvvar {
+        execution: String,
+        envvar_name: String,
+    },
+    #[error("Directory {name} is not a valid tar archive: {reason}")]
+    InvalidArchive { name: String, reason: String },
+    #[error("Resource {name} is not consumed by any execution")]
+    UnusedResource { name: String },
+    #[error("Execution {execution} can never be reached")]
+    UnreachableExecution { execution: String },
+    #[error("Metadata is given for unknown name {name}")]
+    MetadataForUnknownJob { name: String },
+}
+
+fn format_diagnostics(diagnostics: &[SchemaDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -1,3 +1,5 @@
+pub mod condition;
+pub mod job_metadata;
 pub mod registered;
 pub mod runtime;
 pub mod writer_schema;
@@ -0,0 +1,35 @@
+use crate::model::judge_output::{ExecutionJobResult, ExecutionResult, JudgeStatus};
+use serde::{Deserialize, Serialize};
+
+/// Guard on a dependency, evaluated on the result of the execution it refers to.
+/// An execution runs only if the guards on all of its dependencies hold.
+/// A batched result satisfies a guard only if every testcase in it does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    /// The result is displayable and its status is one of these
+    StatusIn(Vec<JudgeStatus>),
+    /// The result is displayable and its status is none of these
+    StatusNotIn(Vec<JudgeStatus>),
+}
+
+impl Condition {
+    pub fn holds(&self, result: &ExecutionJobResult) -> bool {
+        match result {
+            ExecutionJobResult::ExecutionResult(ExecutionResult::Displayable(result)) => {
+                self.holds_for(&result.status)
+            }
+            ExecutionJobResult::ExecutionResult(ExecutionResult::Batched(result)) => result
+                .results
+                .iter()
+                .all(|sub| self.holds_for(&sub.result.status)),
+            _ => false,
+        }
+    }
+
+    fn holds_for(&self, status: &JudgeStatus) -> bool {
+        match self {
+            Condition::StatusIn(statuses) => statuses.contains(status),
+            Condition::StatusNotIn(statuses) => !statuses.contains(status),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::constant::job_name::{self, v0_features};
+use crate::model::identifiers::DepId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What a job of a procedure is for, so that its results can be mapped without its name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobRole {
+    /// Compiles the submission
+    Compile,
+    /// Compiles a checker or interactor shipped with the problem
+    ToolCompile,
+    /// Judges the testcases listed in the metadata
+    Test,
+    /// Aggregates the results of a testcase group
+    GroupSummary,
+    /// Gives the verdict of the whole submission
+    Summary,
+    TestcaseInput,
+    TestcaseExpected,
+    /// Working directory of a test execution
+    TestcaseTempdir,
+    #[default]
+    Custom,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobMetadata {
+    pub role: JobRole,
+    /// Names of the testcases the job judges or belongs to, several for a batched execution
+    #[serde(default)]
+    pub testcases: Vec<String>,
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Position among the results shown to users, smaller first
+    #[serde(default)]
+    pub display_order: Option<u32>,
+}
+
+impl JobMetadata {
+    pub fn new(role: JobRole) -> Self {
+        JobMetadata {
+            role,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_testcases(mut self, testcases: Vec<String>) -> Self {
+        self.testcases = testcases;
+        self
+    }
+
+    pub fn with_group(mut self, group: Option<String>) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn with_display_order(mut self, display_order: u32) -> Self {
+        self.display_order = Some(display_order);
+        self
+    }
+
+    /// Guesses the metadata of a job registered before metadata was recorded, from the preset naming conventions
+    pub fn from_legacy_name(name: &str) -> Self {
+        let testcase = |core_name: &str| vec![core_name.to_string()];
+        if name == job_name::COMPILE_PHASE {
+            JobMetadata::new(JobRole::Compile)
+        } else if name == job_name::CHECKER_COMPILE_PHASE
+            || name == job_name::INTERACTOR_COMPILE_PHASE
+        {
+            JobMetadata::new(JobRole::ToolCompile)
+        } else if name == job_name::SUMMARY_PHASE {
+            JobMetadata::new(JobRole::Summary)
+        } else if let Some(core_name) = name.strip_prefix(job_name::TEST_PHASE_PREFIX) {
+            JobMetadata::new(JobRole::Test).with_testcases(testcase(core_name))
+        } else if name.starts_with(job_name::TEST_BATCH_PREFIX) {
+            JobMetadata::new(JobRole::Test)
+        } else if let Some(group) = name.strip_prefix(job_name::GROUP_PHASE_PREFIX) {
+            JobMetadata::new(JobRole::GroupSummary).with_group(Some(group.to_string()))
+        } else if let Some(core_name) = name.strip_prefix(job_name::TESTCASE_TEMPDIR_PREFIX) {
+            JobMetadata::new(JobRole::TestcaseTempdir).with_testcases(testcase(core_name))
+        } else if let Some(core_name) = v0_features::get_core_name_from_testcase_input_name(name) {
+            JobMetadata::new(JobRole::TestcaseInput).with_testcases(testcase(core_name))
+        } else if let Some(core_name) = v0_features::get_core_name_from_testcase_expected_name(name)
+        {
+            JobMetadata::new(JobRole::TestcaseExpected).with_testcases(testcase(core_name))
+        } else {
+            JobMetadata::default()
+        }
+    }
+}
+
+/// Metadata of every dep in `dep_names`, guessed from the name where none is recorded
+pub fn with_legacy_fallback(
+    dep_names: &HashMap<DepId, String>,
+    mut recorded: HashMap<DepId, JobMetadata>,
+) -> HashMap<DepId, JobMetadata> {
+    dep_names
+        .iter()
+        .map(|(dep_id, name)| {
+            let metadata = recorded
+                .remove(dep_id)
+                .unwrap_or_else(|| JobMetadata::from_legacy_name(name));
+            (*dep_id, metadata)
+        })
+        .collect()
+}
@@ -1,9 +1,12 @@
 use super::super::identifiers::{DepId, ResourceId};
+use super::condition::Condition;
 
 #[derive(Debug, Clone, Default)]
 pub struct Procedure {
     pub runtime_texts: Vec<RuntimeText>,
     pub texts: Vec<Text>,
+    pub binaries: Vec<Binary>,
+    pub directories: Vec<Directory>,
     pub empty_directories: Vec<EmptyDirectory>,
     pub executions: Vec<Execution>,
 }
@@ -20,6 +23,18 @@ pub struct Text {
     pub dep_id: DepId,
 }
 
+#[derive(Debug, Clone)]
+pub struct Binary {
+    pub resource_id: ResourceId,
+    pub dep_id: DepId,
+}
+
+#[derive(Debug, Clone)]
+pub struct Directory {
+    pub resource_id: ResourceId,
+    pub dep_id: DepId,
+}
+
 #[derive(Debug, Clone)]
 pub struct EmptyDirectory {
     pub dep_id: DepId,
@@ -30,10 +45,12 @@ pub struct Execution {
     pub dependencies: Vec<Dependency>,
     pub dep_id: DepId,
     pub time_reserved_ms: u64,
+    pub no_cache: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct Dependency {
     pub dep_id: DepId,
     pub envvar_name: String,
+    pub condition: Option<Condition>,
 }
@@ -1,9 +1,12 @@
 use super::super::identifiers::{ResourceId, RuntimeId};
+use super::condition::Condition;
 
 #[derive(Debug, Clone)]
 pub struct Procedure {
     pub runtime_texts: Vec<RuntimeText>,
     pub texts: Vec<Text>,
+    pub binaries: Vec<Binary>,
+    pub directories: Vec<Directory>,
     pub empty_directories: Vec<EmptyDirectory>,
     pub executions: Vec<Execution>,
 }
@@ -20,6 +23,18 @@ pub struct Text {
     pub runtime_id: RuntimeId,
 }
 
+#[derive(Debug, Clone)]
+pub struct Binary {
+    pub resource_id: ResourceId,
+    pub runtime_id: RuntimeId,
+}
+
+#[derive(Debug, Clone)]
+pub struct Directory {
+    pub resource_id: ResourceId,
+    pub runtime_id: RuntimeId,
+}
+
 #[derive(Debug, Clone)]
 pub struct EmptyDirectory {
     pub runtime_id: RuntimeId,
@@ -30,10 +45,12 @@ pub struct Execution {
     pub dependencies: Vec<Dependency>,
     pub runtime_id: RuntimeId,
     pub time_reserved_ms: u64,
+    pub no_cache: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct Dependency {
     pub runtime_id: RuntimeId,
     pub envvar_name: String,
+    pub condition: Option<Condition>,
 }
@@ -1,16 +1,39 @@
+use super::condition::Condition;
+use super::job_metadata::JobMetadata;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version written by this crate; older documents are upgraded by `logic::schema_migration`
+pub const SCHEMA_VERSION: u32 = 6;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Procedure {
+    pub version: u32,
     pub resources: Vec<ResourceKind>,
     pub executions: Vec<Execution>,
     pub scripts: Vec<Text>,
+    /// Metadata of executions and resources, keyed by their names
+    #[serde(default)]
+    pub metadata: BTreeMap<String, JobMetadata>,
+}
+
+impl Procedure {
+    /// Metadata of a job, guessed from its name when none is recorded
+    pub fn metadata_of(&self, name: &str) -> JobMetadata {
+        self.metadata
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| JobMetadata::from_legacy_name(name))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dependency {
     pub ref_to: String,
     pub envvar_name: String,
+    /// Only an execution can be guarded; without a guard the dependency always holds
+    #[serde(default)]
+    pub condition: Option<Condition>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +47,10 @@ pub struct Execution {
     pub script_name: String,
     pub dependencies: Vec<Dependency>,
     pub time_reserved_ms: u64,
+    /// Always run the script instead of reusing the outcome of an identical execution,
+    /// for nondeterministic or timing-sensitive jobs
+    #[serde(default)]
+    pub no_cache: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +58,20 @@ pub enum ResourceKind {
     EmptyDirectory(EmptyDirectory),
     RuntimeTextFile(RuntimeText),
     TextFile(Text),
+    BinaryFile(Binary),
+    Directory(Directory),
+}
+
+impl ResourceKind {
+    pub fn name(&self) -> &str {
+        match self {
+            ResourceKind::EmptyDirectory(empty_directory) => &empty_directory.name,
+            ResourceKind::RuntimeTextFile(runtime_text) => &runtime_text.name,
+            ResourceKind::TextFile(text) => &text.name,
+            ResourceKind::BinaryFile(binary) => &binary.name,
+            ResourceKind::Directory(directory) => &directory.name,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,3 +85,36 @@ pub struct Text {
     pub name: String,
     pub content: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Binary {
+    pub name: String,
+    /// Serialized as a base64 string
+    #[serde(with = "base64_content")]
+    pub content: Vec<u8>,
+}
+
+/// A directory tree given as a tar archive whose paths are relative to the directory itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Directory {
+    pub name: String,
+    /// Serialized as a base64 string
+    #[serde(with = "base64_content")]
+    pub archive: Vec<u8>,
+}
+
+mod base64_content {
+    use base64::{Engine, prelude::BASE64_STANDARD};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(content: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64_STANDARD.encode(content))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        BASE64_STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use judge_core::model::*;
+use judge_core::model::{procedure::job_metadata::JobMetadata, *};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -7,6 +7,7 @@ use tokio::sync::Mutex;
 #[derive(Debug, Clone)]
 pub struct DepNameRepository {
     dep_names: Arc<Mutex<HashMap<identifiers::DepId, String>>>,
+    metadata: Arc<Mutex<HashMap<identifiers::DepId, JobMetadata>>>,
     problem_id_to_dep_ids: Arc<Mutex<HashMap<i64, Vec<identifiers::DepId>>>>,
 }
 
@@ -14,6 +15,7 @@ impl DepNameRepository {
     pub fn new() -> Self {
         Self {
             dep_names: Arc::new(Mutex::new(HashMap::new())),
+            metadata: Arc::new(Mutex::new(HashMap::new())),
             problem_id_to_dep_ids: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -38,7 +40,10 @@ impl dep_name_repository::DepNameRepository<i64> for DepNameRepository {
         };
         {
             let mut problem_id_to_dep_ids = self.problem_id_to_dep_ids.lock().await;
-            problem_id_to_dep_ids.insert(_problem_id, dep_ids);
+            problem_id_to_dep_ids
+                .entry(_problem_id)
+                .or_default()
+                .extend(dep_ids);
             std::mem::drop(problem_id_to_dep_ids);
         }
         Ok(())
@@ -74,9 +79,37 @@ impl dep_name_repository::DepNameRepository<i64> for DepNameRepository {
         };
         {
             let mut dep_names = self.dep_names.lock().await;
+            let mut metadata = self.metadata.lock().await;
             for dep_id in dep_ids {
                 dep_names.remove(&dep_id);
+                metadata.remove(&dep_id);
             }
+            std::mem::drop(metadata);
+            std::mem::drop(dep_names);
+        }
+        Ok(())
+    }
+
+    async fn remove_by_dep_ids(
+        &self,
+        problem_id: i64,
+        dep_ids: Vec<identifiers::DepId>,
+    ) -> Result<()> {
+        {
+            let mut problem_id_to_dep_ids = self.problem_id_to_dep_ids.lock().await;
+            if let Some(problem_dep_ids) = problem_id_to_dep_ids.get_mut(&problem_id) {
+                problem_dep_ids.retain(|dep_id| !dep_ids.contains(dep_id));
+            }
+            std::mem::drop(problem_id_to_dep_ids);
+        }
+        {
+            let mut dep_names = self.dep_names.lock().await;
+            let mut metadata = self.metadata.lock().await;
+            for dep_id in dep_ids {
+                dep_names.remove(&dep_id);
+                metadata.remove(&dep_id);
+            }
+            std::mem::drop(metadata);
             std::mem::drop(dep_names);
         }
         Ok(())
@@ -109,4 +142,41 @@ impl dep_name_repository::DepNameRepository<i64> for DepNameRepository {
         };
         Ok(dep_to_name)
     }
+
+    async fn insert_metadata_many(
+        &self,
+        _problem_id: i64,
+        dep_id_to_metadata: HashMap<identifiers::DepId, JobMetadata>,
+    ) -> Result<()> {
+        let mut metadata = self.metadata.lock().await;
+        metadata.extend(dep_id_to_metadata);
+        std::mem::drop(metadata);
+        Ok(())
+    }
+
+    async fn get_metadata_by_problem_id(
+        &self,
+        problem_id: i64,
+    ) -> Result<HashMap<identifiers::DepId, JobMetadata>> {
+        let dep_ids = {
+            let problem_id_to_dep_ids = self.problem_id_to_dep_ids.lock().await;
+            if let Some(dep_ids) = problem_id_to_dep_ids.get(&problem_id) {
+                dep_ids.clone()
+            } else {
+                return Err(anyhow::anyhow!("Problem ID {} not found", problem_id));
+            }
+        };
+        let dep_to_metadata = {
+            let metadata = self.metadata.lock().await;
+            let mut dep_to_metadata = HashMap::new();
+            for dep_id in dep_ids {
+                if let Some(job_metadata) = metadata.get(&dep_id) {
+                    dep_to_metadata.insert(dep_id, job_metadata.clone());
+                }
+            }
+            std::mem::drop(metadata);
+            dep_to_metadata
+        };
+        Ok(dep_to_metadata)
+    }
 }
@@ -2,7 +2,7 @@ use super::tokens::{OutcomeToken, RegistrationToken};
 use bollard::container::StartContainerOptions;
 use bollard::{
     Docker,
-    container::{Config, CreateContainerOptions, WaitContainerOptions},
+    container::{Config, CreateContainerOptions, RemoveContainerOptions, WaitContainerOptions},
     service::{HostConfig, Mount},
 };
 use futures::StreamExt;
@@ -58,7 +58,9 @@ impl<ProblemRegistryClient: problem_registry::ProblemRegistryClient>
         &self,
         _: RegistrationToken,
         dependencies: Vec<Dependency<OutcomeToken>>,
-    ) -> Result<(OutcomeToken, Output), ExecutionError> {
+        time_reserved_ms: u64,
+        _no_cache: bool,
+    ) -> Result<(OutcomeToken, Output, ExecutionDetails), ExecutionError> {
         let this = self
             .place_file(FileConf::EmptyDirectory)
             .await
@@ -126,14 +128,35 @@ impl<ProblemRegistryClient: problem_registry::ProblemRegistryClient>
             )
             .await
             .map_err(|e| ExecutionError::InternalError(e.to_string()))?;
+        let started_at_ms = job::unix_time_ms();
         docker
             .start_container(&container.id, None::<StartContainerOptions<String>>)
             .await
             .map_err(|e| ExecutionError::InternalError(e.to_string()))?;
-        let wait = docker
-            .wait_container(&container_name, None::<WaitContainerOptions<String>>)
-            .next()
-            .await
+        let wait = tokio::time::timeout(
+            std::time::Duration::from_millis(time_reserved_ms),
+            docker
+                .wait_container(&container_name, None::<WaitContainerOptions<String>>)
+                .next(),
+        )
+        .await;
+        let wait = match wait {
+            Ok(wait) => wait,
+            Err(_) => {
+                docker
+                    .remove_container(
+                        &container_name,
+                        Some(RemoveContainerOptions {
+                            force: true,
+                            ..Default::default()
+                        }),
+                    )
+                    .await
+                    .map_err(|e| ExecutionError::InternalError(e.to_string()))?;
+                return Err(ExecutionError::Timeout(time_reserved_ms));
+            }
+        };
+        let wait = wait
             .ok_or(ExecutionError::InternalError(
                 "Failed to wait for container".to_string(),
             ))?
@@ -151,7 +174,14 @@ impl<ProblemRegistryClient: problem_registry::ProblemRegistryClient>
             stdout,
             stderr,
         };
-        Ok((this, output))
+        let details = ExecutionDetails {
+            host: Some(container_name),
+            retry_count: 0,
+            started_at_ms: Some(started_at_ms),
+            outcome_size_bytes: directory_size(&self.host_temp_dir.join(this.path())).ok(),
+            cached: false,
+        };
+        Ok((this, output, details))
     }
 
     async fn place_file(&self, file_conf: FileConf) -> Result<OutcomeToken, FilePlacementError> {
@@ -164,7 +194,7 @@ impl<ProblemRegistryClient: problem_registry::ProblemRegistryClient>
                 std::fs::create_dir(&path)
                     .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
             }
-            FileConf::Text(resource_id) => {
+            FileConf::Text(resource_id) | FileConf::Binary(resource_id) => {
                 let content = self
                     .problem_registry_client
                     .fetch(resource_id)
@@ -178,11 +208,69 @@ impl<ProblemRegistryClient: problem_registry::ProblemRegistryClient>
                 std::fs::write(&path, content)
                     .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
             }
+            FileConf::Directory(resource_id) => {
+                let archive = self
+                    .problem_registry_client
+                    .fetch(resource_id)
+                    .await
+                    .map_err(|e| match e {
+                        problem_registry::ResourceFetchError::NotFound(id) => {
+                            FilePlacementError::InvalidResourceId(id)
+                        }
+                        _ => FilePlacementError::PlaceFailed(e.to_string()),
+                    })?;
+                std::fs::create_dir(&path)
+                    .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
+                tar::Archive::new(archive.as_slice())
+                    .unpack(&path)
+                    .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
+            }
             FileConf::RuntimeText(content) => {
                 std::fs::write(&path, content)
                     .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
             }
+            FileConf::Snapshot(OutcomeSnapshot::File(content)) => {
+                std::fs::write(&path, content)
+                    .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
+            }
+            FileConf::Snapshot(OutcomeSnapshot::Directory(archive)) => {
+                std::fs::create_dir(&path)
+                    .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
+                tar::Archive::new(archive.as_slice())
+                    .unpack(&path)
+                    .map_err(|e| FilePlacementError::PlaceFailed(e.to_string()))?;
+            }
         }
         Ok(outcome)
     }
+
+    async fn snapshot(&self, outcome: &OutcomeToken) -> Result<OutcomeSnapshot, SnapshotError> {
+        let path = self.host_temp_dir.join(outcome.path());
+        if path.is_dir() {
+            let mut builder = tar::Builder::new(Vec::new());
+            builder
+                .append_dir_all(".", &path)
+                .and_then(|_| builder.into_inner())
+                .map(OutcomeSnapshot::Directory)
+                .map_err(|e| SnapshotError::SnapshotFailed(e.to_string()))
+        } else {
+            std::fs::read(&path)
+                .map(OutcomeSnapshot::File)
+                .map_err(|e| SnapshotError::SnapshotFailed(e.to_string()))
+        }
+    }
+}
+
+fn directory_size(path: &std::path::Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            directory_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
 }
@@ -16,10 +16,10 @@ impl problem_registry::ProblemRegistryClient for RegistryClient {
     async fn fetch(
         &self,
         resource_id: identifiers::ResourceId,
-    ) -> Result<String, problem_registry::ResourceFetchError> {
+    ) -> Result<Vec<u8>, problem_registry::ResourceFetchError> {
         let uuid: uuid::Uuid = resource_id.into();
         let path = self.cache_dir.join(uuid.to_string());
-        match tokio::fs::read_to_string(path).await {
+        match tokio::fs::read(path).await {
             Ok(contents) => Ok(contents),
             Err(_) => Err(problem_registry::ResourceFetchError::NotFound(resource_id)),
         }
@@ -23,15 +23,13 @@ impl ProblemRegistryServer for RegistryServer {
     async fn register(
         &self,
         resource_id: identifiers::ResourceId,
-        content: String,
+        content: Vec<u8>,
     ) -> Result<(), RegistrationError> {
         let uuid: uuid::Uuid = resource_id.into();
         let path = self.cache_dir.join(uuid.to_string());
-        if tokio::fs::try_exists(&path).await.unwrap_or(true) {
-            return Err(RegistrationError::InternalError(format!(
-                "Resource {} already exists",
-                resource_id
-            )));
+        // ResourceId は内容のハッシュなので、既にあれば同じ内容が書かれている
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(());
         }
         tokio::fs::write(path, content).await.map_err(|e| {
             RegistrationError::InternalError(format!("Failed to write to cache: {}", e))
@@ -40,6 +38,17 @@ impl ProblemRegistryServer for RegistryServer {
         Ok(())
     }
 
+    async fn exists(
+        &self,
+        resource_id: identifiers::ResourceId,
+    ) -> Result<bool, RegistrationError> {
+        let uuid: uuid::Uuid = resource_id.into();
+        let path = self.cache_dir.join(uuid.to_string());
+        tokio::fs::try_exists(path).await.map_err(|e| {
+            RegistrationError::InternalError(format!("Failed to check the cache: {}", e))
+        })
+    }
+
     async fn remove(&self, resource_id: identifiers::ResourceId) -> Result<(), RemovalError> {
         let uuid: uuid::Uuid = resource_id.into();
         let path = self.cache_dir.join(uuid.to_string());
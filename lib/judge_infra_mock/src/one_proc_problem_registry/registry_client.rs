@@ -5,7 +5,7 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct RegistryClient {
-    pub(crate) registry: Arc<Mutex<HashMap<identifiers::ResourceId, String>>>,
+    pub(crate) registry: Arc<Mutex<HashMap<identifiers::ResourceId, Vec<u8>>>>,
 }
 
 #[axum::async_trait]
@@ -13,7 +13,7 @@ impl problem_registry::ProblemRegistryClient for RegistryClient {
     async fn fetch(
         &self,
         resource_id: identifiers::ResourceId,
-    ) -> Result<String, problem_registry::ResourceFetchError> {
+    ) -> Result<Vec<u8>, problem_registry::ResourceFetchError> {
         let registry = self.registry.lock().await;
         match registry.get(&resource_id) {
             Some(contents) => Ok(contents.clone()),
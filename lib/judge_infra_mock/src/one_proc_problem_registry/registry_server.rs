@@ -5,7 +5,7 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct RegistryServer {
-    pub(crate) registry: Arc<Mutex<HashMap<identifiers::ResourceId, String>>>,
+    pub(crate) registry: Arc<Mutex<HashMap<identifiers::ResourceId, Vec<u8>>>>,
 }
 
 #[axum::async_trait]
@@ -13,7 +13,7 @@ impl ProblemRegistryServer for RegistryServer {
     async fn register(
         &self,
         resource_id: identifiers::ResourceId,
-        content: String,
+        content: Vec<u8>,
     ) -> Result<(), RegistrationError> {
         {
             let mut registry = self.registry.lock().await;
@@ -23,6 +23,14 @@ impl ProblemRegistryServer for RegistryServer {
         Ok(())
     }
 
+    async fn exists(
+        &self,
+        resource_id: identifiers::ResourceId,
+    ) -> Result<bool, RegistrationError> {
+        let registry = self.registry.lock().await;
+        Ok(registry.contains_key(&resource_id))
+    }
+
     async fn remove(&self, resource_id: identifiers::ResourceId) -> Result<(), RemovalError> {
         let mut registry = self.registry.lock().await;
         registry
@@ -26,7 +26,7 @@ impl problem_registry::ProblemRegistryClient for ProblemRegistryClient {
     async fn fetch(
         &self,
         resource_id: judge_core::model::identifiers::ResourceId,
-    ) -> Result<String, problem_registry::ResourceFetchError> {
+    ) -> Result<Vec<u8>, problem_registry::ResourceFetchError> {
         // checked in lib/job_service/src/aws.rs
         // FIXME: do not hard code!
         let judge_bucket_name = env::var("JUDGE_BUCKET_NAME").unwrap();
@@ -61,12 +61,6 @@ impl problem_registry::ProblemRegistryClient for ProblemRegistryClient {
                 problem_registry::ResourceFetchError::FetchFailed(format!("Fetch Failed: {e}"))
             })?
             .to_vec();
-        let content = String::from_utf8(bytes);
-        match content {
-            Ok(content) => Ok(content),
-            Err(_) => Err(problem_registry::ResourceFetchError::FetchFailed(
-                "Failed to parse content".to_string(),
-            )),
-        }
+        Ok(bytes)
     }
 }
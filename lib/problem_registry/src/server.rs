@@ -24,7 +24,7 @@ impl problem_registry::ProblemRegistryServer for ProblemRegistryServer {
     async fn register(
         &self,
         resource_id: identifiers::ResourceId,
-        content: String,
+        content: Vec<u8>,
     ) -> Result<(), problem_registry::RegistrationError> {
         // FIXME: do not hard code!
         let judge_bucket_name = std::env::var("JUDGE_BUCKET_NAME").unwrap();
@@ -36,7 +36,7 @@ impl problem_registry::ProblemRegistryServer for ProblemRegistryServer {
             .put_object()
             .bucket(judge_bucket_name)
             .key(resource_id.to_string())
-            .body(ByteStream::from(content.into_bytes()))
+            .body(ByteStream::from(content))
             .send()
             .await
             .map_err(|e| {
@@ -48,6 +48,29 @@ impl problem_registry::ProblemRegistryServer for ProblemRegistryServer {
         Ok(())
     }
 
+    async fn exists(
+        &self,
+        resource_id: identifiers::ResourceId,
+    ) -> Result<bool, problem_registry::RegistrationError> {
+        // FIXME: do not hard code!
+        let judge_bucket_name = std::env::var("JUDGE_BUCKET_NAME").unwrap();
+
+        let s3_response = self
+            .s3_client
+            .head_object()
+            .bucket(judge_bucket_name)
+            .key(resource_id.to_string())
+            .send()
+            .await;
+        match s3_response {
+            Ok(_) => Ok(true),
+            Err(SdkError::ServiceError(err)) if err.err().is_not_found() => Ok(false),
+            Err(e) => Err(problem_registry::RegistrationError::InternalError(format!(
+                "existence check failed: {e}"
+            ))),
+        }
+    }
+
     async fn remove(
         &self,
         resource_id: identifiers::ResourceId,
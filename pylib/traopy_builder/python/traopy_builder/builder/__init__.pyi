@@ -11,9 +11,25 @@ class Builder:
     def new(self) -> Builder:
         ...
 
+    @staticmethod
+    def from_json(json:builtins.str) -> Builder:
+        r"""
+        Loads a procedure exported by `jsonify`, upgrading documents of older schema versions
+        """
+        ...
+
     def add_static_text(self, name:builtins.str, content:builtins.str) -> Outcome:
         ...
 
+    def add_binary(self, name:builtins.str, content:builtins.bytes) -> Outcome:
+        ...
+
+    def add_directory(self, name:builtins.str, path:builtins.str | os.PathLike | pathlib.Path) -> Outcome:
+        r"""
+        Registers the whole tree under `path` as one directory resource
+        """
+        ...
+
     def add_runtime_text(self, name:builtins.str, label:builtins.str) -> Outcome:
         ...
 
@@ -23,7 +39,22 @@ class Builder:
     def add_script(self, name:builtins.str, content:builtins.str) -> Outcome:
         ...
 
-    def add_execution(self, name:builtins.str, script:Outcome, dependencies:typing.Sequence[Dependency], time_reserved_ms:builtins.int) -> Outcome:
+    def add_execution(self, name:builtins.str, script:Outcome, dependencies:typing.Sequence[Dependency], time_reserved_ms:builtins.int, no_cache:builtins.bool=False) -> Outcome:
+        r"""
+        Set `no_cache` for executions whose outcome must not be reused, such as timed runs
+        """
+        ...
+
+    def set_metadata(self, outcome:Outcome, role:builtins.str, testcases:typing.Optional[typing.Sequence[builtins.str]]=None, group:typing.Optional[builtins.str]=None, display_order:typing.Optional[builtins.int]=None) -> None:
+        r"""
+        Tells the backend what `outcome` is for, e.g. role "Test" with the testcases it judges
+        """
+        ...
+
+    def validate(self) -> builtins.list[builtins.str]:
+        r"""
+        Returns the problems that would make the procedure rejected at registration
+        """
         ...
 
     def jsonify(self) -> builtins.str:
@@ -34,7 +65,10 @@ class Builder:
 
 
 class Dependency:
-    def new(self, outcome:Outcome, envvar_name:builtins.str) -> Dependency:
+    def new(self, outcome:Outcome, envvar_name:builtins.str, status_in:typing.Optional[typing.Sequence[builtins.str]]=None, status_not_in:typing.Optional[typing.Sequence[builtins.str]]=None) -> Dependency:
+        r"""
+        `status_in` / `status_not_in` guard the dependency on the status (e.g. "AC") of the execution it refers to
+        """
         ...
 
 
@@ -1,10 +1,16 @@
 use judge_core::logic::{
     self, judge_service_impl::JudgeServiceImpl, procedure_builder::ProcedureBuilder,
+    schema_migration, validation,
 };
 use judge_core::model::{
     dep_name_repository::DepNameRepository as _,
     judge::{JudgeRequest, JudgeService as _},
-    procedure::writer_schema::{self, *},
+    judge_output::JudgeStatus,
+    procedure::{
+        condition::Condition,
+        job_metadata::{JobMetadata, JobRole},
+        writer_schema::{self, *},
+    },
 };
 use judge_infra_mock::{
     dep_name_repository::DepNameRepository, job_service::job_service::JobService,
@@ -29,20 +35,53 @@ pub struct Outcome {
 pub struct Dependency {
     pub(crate) id: Uuid,
     pub(crate) envvar_name: String,
+    pub(crate) condition: Option<Condition>,
 }
 
 #[pymethods]
 #[gen_stub_pymethods]
 impl Dependency {
+    /// `status_in` / `status_not_in` guard the dependency on the status (e.g. "AC") of the execution it refers to
     #[new]
-    fn new(outcome: &Outcome, envvar_name: String) -> Self {
-        Dependency {
+    #[pyo3(signature = (outcome, envvar_name, status_in=None, status_not_in=None))]
+    fn new(
+        outcome: &Outcome,
+        envvar_name: String,
+        status_in: Option<Vec<String>>,
+        status_not_in: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let condition = match (status_in, status_not_in) {
+            (None, None) => None,
+            (Some(statuses), None) => Some(Condition::StatusIn(parse_statuses(statuses)?)),
+            (None, Some(statuses)) => Some(Condition::StatusNotIn(parse_statuses(statuses)?)),
+            (Some(_), Some(_)) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Only one of status_in and status_not_in can be given",
+                ));
+            }
+        };
+        Ok(Dependency {
             id: outcome.id,
             envvar_name,
-        }
+            condition,
+        })
     }
 }
 
+fn parse_statuses(statuses: Vec<String>) -> PyResult<Vec<JudgeStatus>> {
+    statuses
+        .into_iter()
+        .map(|status| {
+            serde_json::from_value(serde_json::Value::String(status.clone())).map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown judge status {}",
+                    status
+                ))
+            })
+        })
+        .collect()
+}
+
 #[pyclass(module = "traopy_builder.builder")]
 #[gen_stub_pyclass]
 pub struct Builder {
@@ -61,6 +100,21 @@ impl Builder {
         }
     }
 
+    /// Loads a procedure exported by `jsonify`, upgrading documents of older schema versions
+    #[staticmethod]
+    fn from_json(json: String) -> PyResult<Self> {
+        let procedure = schema_migration::load_writer_schema(&json).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Failed to load procedure: {}",
+                e
+            ))
+        })?;
+        Ok(Builder {
+            inner: ProcedureBuilder::from(procedure),
+            id_to_name: HashMap::new(),
+        })
+    }
+
     fn add_static_text(&mut self, name: String, content: String) -> PyResult<Outcome> {
         let text = Text {
             name: name.clone(),
@@ -78,6 +132,56 @@ impl Builder {
         Ok(Outcome { id })
     }
 
+    fn add_binary(&mut self, name: String, content: &[u8]) -> PyResult<Outcome> {
+        let binary = Binary {
+            name: name.clone(),
+            content: content.to_vec(),
+        };
+        let resource = ResourceKind::BinaryFile(binary);
+        let id = uuid::Uuid::new_v4();
+        self.inner.add_resource(resource).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Resource {} already exists",
+                name
+            ))
+        })?;
+        self.id_to_name.insert(id, name);
+        Ok(Outcome { id })
+    }
+
+    /// Registers the whole tree under `path` as one directory resource
+    fn add_directory(&mut self, name: String, path: PathBuf) -> PyResult<Outcome> {
+        let mut archive = tar::Builder::new(Vec::new());
+        archive.append_dir_all(".", &path).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Failed to archive {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let archive = archive.into_inner().map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Failed to archive {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let directory = Directory {
+            name: name.clone(),
+            archive,
+        };
+        let resource = ResourceKind::Directory(directory);
+        let id = uuid::Uuid::new_v4();
+        self.inner.add_resource(resource).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Resource {} already exists",
+                name
+            ))
+        })?;
+        self.id_to_name.insert(id, name);
+        Ok(Outcome { id })
+    }
+
     fn add_runtime_text(&mut self, name: String, label: String) -> PyResult<Outcome> {
         let runtime_text = RuntimeText {
             name: name.clone(),
@@ -125,12 +229,15 @@ impl Builder {
         Ok(Outcome { id })
     }
 
+    /// Set `no_cache` for executions whose outcome must not be reused, such as timed runs
+    #[pyo3(signature = (name, script, dependencies, time_reserved_ms, no_cache=false))]
     fn add_execution(
         &mut self,
         name: String,
         script: Outcome,
         dependencies: Vec<Dependency>,
         time_reserved_ms: u64,
+        no_cache: bool,
     ) -> PyResult<Outcome> {
         let mut inner_dependencies = Vec::new();
         for dep in dependencies {
@@ -143,6 +250,7 @@ impl Builder {
             let dependency = writer_schema::Dependency {
                 ref_to: dep_name.clone(),
                 envvar_name: dep.envvar_name,
+                condition: dep.condition,
             };
             inner_dependencies.push(dependency);
         }
@@ -161,6 +269,7 @@ impl Builder {
             script_name: script_name.clone(),
             dependencies: inner_dependencies,
             time_reserved_ms,
+            no_cache,
         };
         let id = uuid::Uuid::new_v4();
         self.inner.add_execution(execution).map_err(|_| {
@@ -173,8 +282,61 @@ impl Builder {
         Ok(Outcome { id })
     }
 
+    /// Tells the backend what `outcome` is for, e.g. role "Test" with the testcases it judges
+    #[pyo3(signature = (outcome, role, testcases=None, group=None, display_order=None))]
+    fn set_metadata(
+        &mut self,
+        outcome: Outcome,
+        role: String,
+        testcases: Option<Vec<String>>,
+        group: Option<String>,
+        display_order: Option<u32>,
+    ) -> PyResult<()> {
+        let name = self.id_to_name.get(&outcome.id).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Outcome {} not found",
+                outcome.id
+            ))
+        })?;
+        let role: JobRole = serde_json::from_value(serde_json::Value::String(role.clone()))
+            .map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown job role {}",
+                    role
+                ))
+            })?;
+        let metadata = JobMetadata {
+            role,
+            testcases: testcases.unwrap_or_default(),
+            group,
+            display_order,
+        };
+        self.inner.set_metadata(name, metadata).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{} not found", name))
+        })
+    }
+
+    /// Returns the problems that would make the procedure rejected at registration
+    fn validate(&self) -> Vec<String> {
+        validation::writer_schema::validate(&self.inner.get_procedure())
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
     fn jsonify(&self) -> PyResult<String> {
         let procedure = self.inner.get_procedure();
+        let diagnostics = validation::writer_schema::validate(&procedure);
+        if !diagnostics.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid procedure: {}",
+                diagnostics
+                    .iter()
+                    .map(|diagnostic| diagnostic.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )));
+        }
         let json = serde_json::to_string(&procedure).map_err(|_| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>("Failed to serialize procedure")
         })?;
@@ -239,11 +401,15 @@ impl Builder {
             0 as i64,
         )
         .await
-        .map_err(|_| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>("Failed to register procedure")
+        .map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Failed to register procedure: {}",
+                e
+            ))
         })?;
         let judge_service = JudgeServiceImpl::new(job_service);
         let judge_req = JudgeRequest {
+            judge_id: Uuid::new_v4().into(),
             procedure: regi_procedure,
             runtime_texts: label_to_content,
         };
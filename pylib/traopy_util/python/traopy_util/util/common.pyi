@@ -2,6 +2,26 @@
 # ruff: noqa: E501, F401
 
 import builtins
+import typing
+
+class CheckerVerdict:
+    r"""
+    Result of a built-in checker with a diagnostic message on rejection
+    """
+    accepted: builtins.bool
+    message: typing.Optional[builtins.str]
+
+def case_insensitive_checker(expected:builtins.str, actual:builtins.str) -> CheckerVerdict:
+    r"""
+    Token-wise comparison ignoring ASCII case
+    """
+    ...
+
+def float_checker(expected:builtins.str, actual:builtins.str, abs_eps:builtins.float, rel_eps:builtins.float) -> CheckerVerdict:
+    r"""
+    Token-wise comparison of real numbers within an absolute or relative error
+    """
+    ...
 
 def normal_judge_checker(expected:builtins.str, actual:builtins.str) -> builtins.bool:
     ...
@@ -12,3 +32,21 @@ def parse_whitespace_and_newline(s:builtins.str) -> builtins.str:
 def read_file_with_envvar(envvar:builtins.str) -> builtins.str:
     ...
 
+def run_checker(spec:builtins.str, expected:builtins.str, actual:builtins.str) -> CheckerVerdict:
+    r"""
+    Run the built-in checker described by `spec`, e.g. `exact` or `float:1e-6:1e-6`
+    """
+    ...
+
+def unordered_lines_checker(expected:builtins.str, actual:builtins.str) -> CheckerVerdict:
+    r"""
+    Line-wise comparison ignoring the order of lines
+    """
+    ...
+
+def yes_no_checker(expected:builtins.str, actual:builtins.str) -> CheckerVerdict:
+    r"""
+    Token-wise comparison where `yes`/`no` are compared ignoring case
+    """
+    ...
+
@@ -7,15 +7,36 @@ from enum import Enum, auto
 
 class ExecStats:
     time_ms: builtins.int
+    wall_time_ms: builtins.int
     memory_kib: builtins.int
     exit_code: builtins.int
+    signal: typing.Optional[builtins.int]
+    stderr: builtins.str
 
 class ExecutionResult:
     status: JudgeStatus
     time: builtins.float
     memory: builtins.float
     score: builtins.int
-    def __new__(cls,status:JudgeStatus, time:builtins.float, memory:builtins.float, score:builtins.int): ...
+    message: typing.Optional[builtins.str]
+    compile_log: typing.Optional[builtins.str]
+    stderr: typing.Optional[builtins.str]
+    signal: typing.Optional[builtins.int]
+    cpu_time: typing.Optional[builtins.float]
+    wall_time: typing.Optional[builtins.float]
+    def __new__(cls,status:JudgeStatus, time:builtins.float, memory:builtins.float, score:builtins.int, message:typing.Optional[builtins.str]=None, compile_log:typing.Optional[builtins.str]=None, stderr:typing.Optional[builtins.str]=None, signal:typing.Optional[builtins.int]=None, cpu_time:typing.Optional[builtins.float]=None, wall_time:typing.Optional[builtins.float]=None): ...
+
+class InteractiveExecStats:
+    r"""
+    Resource usage of the contestant process and the exit code of the interactor
+    """
+    time_ms: builtins.int
+    wall_time_ms: builtins.int
+    memory_kib: builtins.int
+    exit_code: builtins.int
+    signal: typing.Optional[builtins.int]
+    stderr: builtins.str
+    interactor_exit_code: builtins.int
 
 class Language:
     compile: builtins.str
@@ -33,6 +54,10 @@ class JudgeStatus(Enum):
     RE = auto()
     CE = auto()
     WE = auto()
+    IE = auto()
+    SKIP = auto()
+    PE = auto()
+    PA = auto()
 
 def build_output_envvar() -> builtins.str:
     ...
@@ -43,22 +68,45 @@ def build_source_envvar() -> builtins.str:
 def build_tempdir_envvar() -> builtins.str:
     ...
 
+def dejsonify_batched_output(json:builtins.str) -> typing.Optional[builtins.list[tuple[builtins.str, ExecutionResult]]]:
+    r"""
+    Pairs of a testcase name and its result, `None` unless `json` is a batched output
+    """
+    ...
+
 def dejsonify_output(json:builtins.str) -> typing.Optional[ExecutionResult]:
     ...
 
+async def exec_interactive_with_stats(cmd:builtins.str, envs:typing.Mapping[builtins.str, builtins.str], interactor_cmd:builtins.str, interactor_envs:typing.Mapping[builtins.str, builtins.str], time_limit_ms:builtins.int, interactor_time_limit_ms:builtins.int) -> typing.Optional[InteractiveExecStats]:
+    r"""
+    Execute a command with its stdin/stdout connected to an interactor process.
+    Time, memory and the stderr excerpt are taken from `cmd` only.
+    """
+    ...
+
 def exec_job_outcome_path_envvar() -> builtins.str:
     ...
 
 async def exec_with_stats(cmd:builtins.str, envs:typing.Mapping[builtins.str, builtins.str], time_limit_ms:builtins.int) -> typing.Optional[ExecStats]:
     r"""
-    Execute a command with environment variables and a time limit
+    Execute a command with environment variables and a time limit.
+    Only the head of stderr is kept, see `judge_output::EXCERPT_MAX_BYTES`.
     """
     ...
 
 def get_language_info(language_tag:builtins.str) -> Language:
     ...
 
-def jsonify_displayable_output(status:JudgeStatus, time_ms:builtins.float, memory_kib:builtins.float, score:builtins.int, continue_next:builtins.bool, message:typing.Optional[builtins.str]) -> builtins.str:
+def jsonify_batched_output(results:typing.Sequence[tuple[builtins.str, builtins.str]], continue_next:builtins.bool) -> builtins.str:
+    r"""
+    `results` are pairs of a testcase name and the output of `jsonify_displayable_output`
+    """
+    ...
+
+def jsonify_displayable_output(status:JudgeStatus, time_ms:builtins.float, memory_kib:builtins.float, score:builtins.int, continue_next:builtins.bool, message:typing.Optional[builtins.str]=None, compile_log:typing.Optional[builtins.str]=None, stderr:typing.Optional[builtins.str]=None, signal:typing.Optional[builtins.int]=None, cpu_time_ms:typing.Optional[builtins.float]=None, wall_time_ms:typing.Optional[builtins.float]=None) -> builtins.str:
+    r"""
+    `compile_log` and `stderr` are truncated to `judge_output::EXCERPT_MAX_BYTES`
+    """
     ...
 
 def jsonify_hidden_output(continue_next:builtins.bool) -> builtins.str:
@@ -19,6 +19,18 @@ pub fn common_module(parent: &Bound<'_, PyModule>) -> PyResult<()> {
         checker::parse_whitespace_and_newline,
         &sub_mod
     )?)?;
+    sub_mod.add_class::<checker::CheckerVerdict>()?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(checker::float_checker, &sub_mod)?)?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(
+        checker::case_insensitive_checker,
+        &sub_mod
+    )?)?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(
+        checker::unordered_lines_checker,
+        &sub_mod
+    )?)?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(checker::yes_no_checker, &sub_mod)?)?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(checker::run_checker, &sub_mod)?)?;
     parent.add_submodule(&sub_mod)?;
     Ok(())
 }
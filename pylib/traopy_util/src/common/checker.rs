@@ -1,3 +1,4 @@
+use judge_core::model::builtin_checker::BuiltinChecker;
 use pyo3::prelude::*;
 use pyo3_stub_gen::derive::*;
 
@@ -19,3 +20,229 @@ pub fn parse_whitespace_and_newline(s: &str) -> String {
         .collect::<Vec<&str>>()
         .join(" ")
 }
+
+#[gen_stub_pyclass]
+#[pyclass(module = "traopy_util.util.common")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Result of a built-in checker with a diagnostic message on rejection
+pub struct CheckerVerdict {
+    accepted: bool,
+    message: Option<String>,
+}
+
+impl CheckerVerdict {
+    fn accept() -> Self {
+        CheckerVerdict {
+            accepted: true,
+            message: None,
+        }
+    }
+
+    fn reject(message: String) -> Self {
+        CheckerVerdict {
+            accepted: false,
+            message: Some(message),
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl CheckerVerdict {
+    #[getter]
+    fn accepted(&self) -> bool {
+        self.accepted
+    }
+
+    #[getter]
+    fn message(&self) -> Option<String> {
+        self.message.clone()
+    }
+}
+
+/// Compare whitespace-separated tokens one by one with `eq`
+fn check_tokens(expected: &str, actual: &str, eq: impl Fn(&str, &str) -> bool) -> CheckerVerdict {
+    let expected = expected.split_whitespace().collect::<Vec<_>>();
+    let actual = actual.split_whitespace().collect::<Vec<_>>();
+    for (i, (e, a)) in expected.iter().zip(actual.iter()).enumerate() {
+        if !eq(e, a) {
+            return CheckerVerdict::reject(format!("token {}: expected {} got {}", i + 1, e, a));
+        }
+    }
+    if expected.len() != actual.len() {
+        return CheckerVerdict::reject(format!(
+            "expected {} tokens got {}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+    CheckerVerdict::accept()
+}
+
+fn float_eq(expected: &str, actual: &str, abs_eps: f64, rel_eps: f64) -> bool {
+    match (expected.parse::<f64>(), actual.parse::<f64>()) {
+        (Ok(e), Ok(a)) if e.is_finite() && a.is_finite() => {
+            let diff = (e - a).abs();
+            diff <= abs_eps || diff <= rel_eps * e.abs()
+        }
+        _ => expected == actual,
+    }
+}
+
+fn yes_no_eq(expected: &str, actual: &str) -> bool {
+    let is_yes_no = |s: &str| s.eq_ignore_ascii_case("yes") || s.eq_ignore_ascii_case("no");
+    if is_yes_no(expected) {
+        expected.eq_ignore_ascii_case(actual)
+    } else {
+        expected == actual
+    }
+}
+
+fn check_unordered_lines(expected: &str, actual: &str) -> CheckerVerdict {
+    let normalize = |s: &str| {
+        let mut lines = s
+            .lines()
+            .map(parse_whitespace_and_newline)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        lines.sort();
+        lines
+    };
+    let expected = normalize(expected);
+    let actual = normalize(actual);
+    if expected.len() != actual.len() {
+        return CheckerVerdict::reject(format!(
+            "expected {} lines got {}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+    for (e, a) in expected.iter().zip(actual.iter()) {
+        if e != a {
+            // The smaller one of the first mismatching pair is missing on the other side
+            return if e < a {
+                CheckerVerdict::reject(format!("missing line: {}", e))
+            } else {
+                CheckerVerdict::reject(format!("unexpected line: {}", a))
+            };
+        }
+    }
+    CheckerVerdict::accept()
+}
+
+fn check(checker: &BuiltinChecker, expected: &str, actual: &str) -> CheckerVerdict {
+    match checker {
+        BuiltinChecker::Exact => check_tokens(expected, actual, |e, a| e == a),
+        BuiltinChecker::Float { abs_eps, rel_eps } => {
+            check_tokens(expected, actual, |e, a| float_eq(e, a, *abs_eps, *rel_eps))
+        }
+        BuiltinChecker::CaseInsensitive => {
+            check_tokens(expected, actual, |e, a| e.eq_ignore_ascii_case(a))
+        }
+        BuiltinChecker::UnorderedLines => check_unordered_lines(expected, actual),
+        BuiltinChecker::YesNo => check_tokens(expected, actual, yes_no_eq),
+    }
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.common")]
+/// Token-wise comparison of real numbers within an absolute or relative error
+pub fn float_checker(
+    expected: String,
+    actual: String,
+    abs_eps: f64,
+    rel_eps: f64,
+) -> CheckerVerdict {
+    check(
+        &BuiltinChecker::Float { abs_eps, rel_eps },
+        &expected,
+        &actual,
+    )
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.common")]
+/// Token-wise comparison ignoring ASCII case
+pub fn case_insensitive_checker(expected: String, actual: String) -> CheckerVerdict {
+    check(&BuiltinChecker::CaseInsensitive, &expected, &actual)
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.common")]
+/// Line-wise comparison ignoring the order of lines
+pub fn unordered_lines_checker(expected: String, actual: String) -> CheckerVerdict {
+    check(&BuiltinChecker::UnorderedLines, &expected, &actual)
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.common")]
+/// Token-wise comparison where `yes`/`no` are compared ignoring case
+pub fn yes_no_checker(expected: String, actual: String) -> CheckerVerdict {
+    check(&BuiltinChecker::YesNo, &expected, &actual)
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.common")]
+/// Run the built-in checker described by `spec`, e.g. `exact` or `float:1e-6:1e-6`
+pub fn run_checker(spec: String, expected: String, actual: String) -> PyResult<CheckerVerdict> {
+    let checker = spec.parse::<BuiltinChecker>().map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid checker: {}", e))
+    })?;
+    Ok(check(&checker, &expected, &actual))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_float() {
+        let checker = BuiltinChecker::Float {
+            abs_eps: 1e-6,
+            rel_eps: 1e-6,
+        };
+        assert!(check(&checker, "1.5 2", "1.5000001 2.0").accepted);
+        assert!(check(&checker, "1e9", "1000000001").accepted);
+        assert_eq!(
+            check(&checker, "0 1 1.5", "0 1 1.7").message.as_deref(),
+            Some("token 3: expected 1.5 got 1.7")
+        );
+        assert!(!check(&checker, "1.5", "nan").accepted);
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert!(
+            check(
+                &BuiltinChecker::CaseInsensitive,
+                "Hello World",
+                "hello WORLD"
+            )
+            .accepted
+        );
+        assert_eq!(
+            check(&BuiltinChecker::CaseInsensitive, "a b", "a")
+                .message
+                .as_deref(),
+            Some("expected 2 tokens got 1")
+        );
+    }
+
+    #[test]
+    fn test_unordered_lines() {
+        assert!(check(&BuiltinChecker::UnorderedLines, "1 2\n3 4\n", "3  4\n1 2").accepted);
+        assert_eq!(
+            check(&BuiltinChecker::UnorderedLines, "1\n2\n", "1\n3\n")
+                .message
+                .as_deref(),
+            Some("missing line: 2")
+        );
+    }
+
+    #[test]
+    fn test_yes_no() {
+        assert!(check(&BuiltinChecker::YesNo, "Yes\n3", "YES 3").accepted);
+        assert!(!check(&BuiltinChecker::YesNo, "Yes", "No").accepted);
+        assert!(!check(&BuiltinChecker::YesNo, "Alice", "alice").accepted);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod command;
 pub mod constant;
+pub mod exec_interactive;
 pub mod exec_with_stats;
 pub mod output;
+mod process;
 use pyo3::prelude::*;
 
 /// This module provides utilities for traOJudge v0 spec.
@@ -21,6 +23,11 @@ pub fn v0_module(parent: &Bound<'_, PyModule>) -> PyResult<()> {
         exec_with_stats::exec_with_stats,
         &sub_mod
     )?)?;
+    sub_mod.add_class::<exec_interactive::InteractiveExecStats>()?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(
+        exec_interactive::exec_interactive_with_stats,
+        &sub_mod
+    )?)?;
     // output support utilities
     sub_mod.add_class::<output::JudgeStatus>()?;
     sub_mod.add_class::<output::ExecutionResult>()?;
@@ -32,7 +39,15 @@ pub fn v0_module(parent: &Bound<'_, PyModule>) -> PyResult<()> {
         output::jsonify_hidden_output,
         &sub_mod
     )?)?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(
+        output::jsonify_batched_output,
+        &sub_mod
+    )?)?;
     sub_mod.add_function(pyo3::wrap_pyfunction!(output::dejsonify_output, &sub_mod)?)?;
+    sub_mod.add_function(pyo3::wrap_pyfunction!(
+        output::dejsonify_batched_output,
+        &sub_mod
+    )?)?;
     sub_mod.add_function(pyo3::wrap_pyfunction!(
         output::merge_judge_status,
         &sub_mod
@@ -0,0 +1,157 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use std::collections::HashMap;
+
+use super::process::{KILL_GRACE_MS, StderrExcerpt, mark_finished, spawn_killer, wait_with_usage};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug)]
+#[gen_stub_pyclass]
+#[pyclass(module = "traopy_util.util.v0")]
+/// Resource usage of the contestant process and the exit code of the interactor
+pub struct InteractiveExecStats {
+    time_ms: i64,
+    wall_time_ms: i64,
+    memory_kib: i64,
+    exit_code: i32,
+    signal: Option<i32>,
+    stderr: String,
+    interactor_exit_code: i32,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl InteractiveExecStats {
+    #[getter]
+    fn time_ms(&self) -> i64 {
+        self.time_ms
+    }
+
+    #[getter]
+    fn wall_time_ms(&self) -> i64 {
+        self.wall_time_ms
+    }
+
+    #[getter]
+    fn memory_kib(&self) -> i64 {
+        self.memory_kib
+    }
+
+    #[getter]
+    fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+
+    #[getter]
+    fn signal(&self) -> Option<i32> {
+        self.signal
+    }
+
+    #[getter]
+    fn stderr(&self) -> String {
+        self.stderr.clone()
+    }
+
+    #[getter]
+    fn interactor_exit_code(&self) -> i32 {
+        self.interactor_exit_code
+    }
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.v0")]
+/// Execute a command with its stdin/stdout connected to an interactor process.
+/// Time, memory and the stderr excerpt are taken from `cmd` only.
+pub async fn exec_interactive_with_stats(
+    cmd: String,
+    envs: HashMap<String, String>,
+    interactor_cmd: String,
+    interactor_envs: HashMap<String, String>,
+    time_limit_ms: i64,
+    interactor_time_limit_ms: i64,
+) -> PyResult<Option<InteractiveExecStats>> {
+    let started_at = std::time::Instant::now();
+    let (contestant_pid, interactor_pid, stderr_excerpt) = {
+        let mut contestant = Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .envs(envs)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to start child process: {}",
+                    e
+                ))
+            })?;
+        let contestant_stdin = contestant.stdin.take().unwrap();
+        let contestant_stdout = contestant.stdout.take().unwrap();
+        let stderr_excerpt = StderrExcerpt::spawn(contestant.stderr.take());
+        // The pipe ends are moved into the interactor, so that each side sees EOF
+        // as soon as the other one exits.
+        let interactor = Command::new("sh")
+            .arg("-c")
+            .arg(interactor_cmd)
+            .envs(interactor_envs)
+            .stdin(Stdio::from(contestant_stdout))
+            .stdout(Stdio::from(contestant_stdin))
+            .spawn();
+        let interactor = match interactor {
+            Ok(interactor) => interactor,
+            Err(e) => {
+                let _ = contestant.kill();
+                let _ = contestant.wait();
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to start interactor process: {}",
+                    e
+                )));
+            }
+        };
+        (
+            contestant.id() as i32,
+            interactor.id() as i32,
+            stderr_excerpt,
+        )
+    };
+
+    let contestant_finished = Arc::new(Mutex::new(false));
+    let interactor_finished = Arc::new(Mutex::new(false));
+    spawn_killer(
+        contestant_pid,
+        time_limit_ms,
+        Arc::clone(&contestant_finished),
+    );
+    spawn_killer(
+        interactor_pid,
+        time_limit_ms.max(interactor_time_limit_ms),
+        Arc::clone(&interactor_finished),
+    );
+
+    let contestant_result = wait_with_usage(contestant_pid);
+    let wall_time_ms = started_at.elapsed().as_millis() as i64;
+    let contestant_killed = mark_finished(&contestant_finished);
+    let interactor_result = wait_with_usage(interactor_pid);
+    let interactor_killed = mark_finished(&interactor_finished);
+    let stderr = stderr_excerpt.finish();
+
+    Ok(Some(InteractiveExecStats {
+        time_ms: if contestant_killed {
+            time_limit_ms + KILL_GRACE_MS
+        } else {
+            contestant_result.time_ms
+        },
+        wall_time_ms,
+        memory_kib: contestant_result.memory_kib,
+        exit_code: contestant_result.exit_code,
+        signal: contestant_result.signal,
+        stderr,
+        interactor_exit_code: if interactor_killed {
+            -1
+        } else {
+            interactor_result.exit_code
+        },
+    }))
+}
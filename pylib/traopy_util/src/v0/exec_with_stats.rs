@@ -2,22 +2,19 @@ use pyo3::prelude::*;
 use pyo3_stub_gen::derive::*;
 use std::collections::HashMap;
 
-use nix::libc::{SIGKILL, WEXITSTATUS, WIFEXITED, c_int, kill, rusage, wait4};
-use nix::unistd::Pid;
-use std::mem::MaybeUninit;
+use super::process::{KILL_GRACE_MS, StderrExcerpt, mark_finished, spawn_killer, wait_with_usage};
 use std::sync::{Arc, Mutex};
-#[cfg(not(target_os = "macos"))]
-const RUSAGE_MAXRSS_IS_KIB: bool = true;
-#[cfg(target_os = "macos")]
-const RUSAGE_MAXRSS_IS_KIB: bool = false;
 
 #[derive(Clone, Debug)]
 #[gen_stub_pyclass]
 #[pyclass(module = "traopy_util.util.v0")]
 pub struct ExecStats {
     time_ms: i64,
+    wall_time_ms: i64,
     memory_kib: i64,
     exit_code: i32,
+    signal: Option<i32>,
+    stderr: String,
 }
 
 #[gen_stub_pymethods]
@@ -28,6 +25,11 @@ impl ExecStats {
         self.time_ms
     }
 
+    #[getter]
+    fn wall_time_ms(&self) -> i64 {
+        self.wall_time_ms
+    }
+
     #[getter]
     fn memory_kib(&self) -> i64 {
         self.memory_kib
@@ -37,80 +39,54 @@ impl ExecStats {
     fn exit_code(&self) -> i32 {
         self.exit_code
     }
+
+    #[getter]
+    fn signal(&self) -> Option<i32> {
+        self.signal
+    }
+
+    #[getter]
+    fn stderr(&self) -> String {
+        self.stderr.clone()
+    }
 }
 
 #[pyfunction]
 #[gen_stub_pyfunction(module = "traopy_util.util.v0")]
-/// Execute a command with environment variables and a time limit
+/// Execute a command with environment variables and a time limit.
+/// Only the head of stderr is kept, see `judge_output::EXCERPT_MAX_BYTES`.
 pub async fn exec_with_stats(
     cmd: String,
     envs: HashMap<String, String>,
     time_limit_ms: i64,
 ) -> PyResult<Option<ExecStats>> {
-    let child_proc = std::process::Command::new("sh")
+    let started_at = std::time::Instant::now();
+    let mut child_proc = std::process::Command::new("sh")
         .arg("-c")
         .arg(cmd)
         .envs(envs)
+        .stderr(std::process::Stdio::piped())
         .spawn()
         .expect("Failed to start child process");
-    let pid = Pid::from_raw(child_proc.id() as i32);
+    let pid = child_proc.id() as i32;
+    let stderr_excerpt = StderrExcerpt::spawn(child_proc.stderr.take());
 
     let finished = Arc::new(Mutex::new(false));
-    let finished_clone = Arc::clone(&finished);
-
-    std::thread::spawn(move || {
-        std::thread::sleep(std::time::Duration::from_millis(
-            (time_limit_ms + 200) as u64,
-        ));
-
-        // If finished flag is locked, this means the process has already completed
-        if let Ok(mut done) = finished_clone.lock() {
-            if *done {
-                return;
-            } else {
-                let _ = unsafe { kill(pid.as_raw(), SIGKILL) };
-                *done = true;
-            }
-        }
-    });
-    let pid = child_proc.id() as i32;
-    let mut usage = MaybeUninit::<rusage>::uninit();
-    let mut status = MaybeUninit::<c_int>::uninit();
-    unsafe { wait4(pid, status.as_mut_ptr(), 0, usage.as_mut_ptr()) };
-    // Set finished flag to true
-    // If the flag is already locked, this means the timeout thread has already executed
-    let mut killed_estimated_exec_time = None;
-    {
-        let mut done = finished.lock().unwrap();
-        if *done {
-            killed_estimated_exec_time = Some(time_limit_ms + 200);
+    spawn_killer(pid, time_limit_ms, Arc::clone(&finished));
+    let result = wait_with_usage(pid);
+    let wall_time_ms = started_at.elapsed().as_millis() as i64;
+    let killed = mark_finished(&finished);
+    let stderr = stderr_excerpt.finish();
+    Ok(Some(ExecStats {
+        time_ms: if killed {
+            time_limit_ms + KILL_GRACE_MS
         } else {
-            *done = true;
-        }
-    }
-    let status = unsafe { status.assume_init() };
-    let exit_code = if WIFEXITED(status) {
-        WEXITSTATUS(status)
-    } else {
-        -1
-    };
-    let (mut memory_used, cpu_time_elapsed) = unsafe {
-        let usage = usage.assume_init();
-        (
-            usage.ru_maxrss as i64,
-            usage.ru_utime.tv_sec as f64
-                + usage.ru_stime.tv_sec as f64
-                + (usage.ru_utime.tv_usec as f64 + usage.ru_stime.tv_usec as f64) * 1e-6,
-        )
-    };
-    if !RUSAGE_MAXRSS_IS_KIB {
-        memory_used /= 1024;
-    }
-    Ok({
-        Some(ExecStats {
-            time_ms: killed_estimated_exec_time.unwrap_or((cpu_time_elapsed * 1000.0) as i64),
-            memory_kib: memory_used,
-            exit_code,
-        })
-    })
+            result.time_ms
+        },
+        wall_time_ms,
+        memory_kib: result.memory_kib,
+        exit_code: result.exit_code,
+        signal: result.signal,
+        stderr,
+    }))
 }
@@ -14,6 +14,68 @@ pub enum JudgeStatus {
     RE,
     CE,
     WE,
+    IE,
+    SKIP,
+    PE,
+    PA,
+}
+
+impl From<JudgeStatus> for judge_output::JudgeStatus {
+    fn from(status: JudgeStatus) -> Self {
+        match status {
+            JudgeStatus::AC => judge_output::JudgeStatus::AC,
+            JudgeStatus::WA => judge_output::JudgeStatus::WA,
+            JudgeStatus::TLE => judge_output::JudgeStatus::TLE,
+            JudgeStatus::MLE => judge_output::JudgeStatus::MLE,
+            JudgeStatus::OLE => judge_output::JudgeStatus::OLE,
+            JudgeStatus::RE => judge_output::JudgeStatus::RE,
+            JudgeStatus::CE => judge_output::JudgeStatus::CE,
+            JudgeStatus::WE => judge_output::JudgeStatus::WE,
+            JudgeStatus::IE => judge_output::JudgeStatus::IE,
+            JudgeStatus::SKIP => judge_output::JudgeStatus::SKIP,
+            JudgeStatus::PE => judge_output::JudgeStatus::PE,
+            JudgeStatus::PA => judge_output::JudgeStatus::PA,
+        }
+    }
+}
+
+impl From<judge_output::JudgeStatus> for JudgeStatus {
+    fn from(status: judge_output::JudgeStatus) -> Self {
+        match status {
+            judge_output::JudgeStatus::AC => JudgeStatus::AC,
+            judge_output::JudgeStatus::WA => JudgeStatus::WA,
+            judge_output::JudgeStatus::TLE => JudgeStatus::TLE,
+            judge_output::JudgeStatus::MLE => JudgeStatus::MLE,
+            judge_output::JudgeStatus::OLE => JudgeStatus::OLE,
+            judge_output::JudgeStatus::RE => JudgeStatus::RE,
+            judge_output::JudgeStatus::CE => JudgeStatus::CE,
+            judge_output::JudgeStatus::WE => JudgeStatus::WE,
+            judge_output::JudgeStatus::IE => JudgeStatus::IE,
+            judge_output::JudgeStatus::SKIP => JudgeStatus::SKIP,
+            judge_output::JudgeStatus::PE => JudgeStatus::PE,
+            judge_output::JudgeStatus::PA => JudgeStatus::PA,
+        }
+    }
+}
+
+impl JudgeStatus {
+    /// Smaller is worse, see `merge_judge_status`
+    fn severity(&self) -> u8 {
+        match self {
+            JudgeStatus::WE => 0,
+            JudgeStatus::IE => 1,
+            JudgeStatus::CE => 2,
+            JudgeStatus::RE => 3,
+            JudgeStatus::OLE => 4,
+            JudgeStatus::WA => 5,
+            JudgeStatus::PE => 6,
+            JudgeStatus::TLE => 7,
+            JudgeStatus::MLE => 8,
+            JudgeStatus::PA => 9,
+            JudgeStatus::SKIP => 10,
+            JudgeStatus::AC => 11,
+        }
+    }
 }
 
 #[gen_stub_pyclass]
@@ -24,18 +86,43 @@ pub struct ExecutionResult {
     pub time: f64,
     pub memory: f64,
     pub score: i64,
+    pub message: Option<String>,
+    pub compile_log: Option<String>,
+    pub stderr: Option<String>,
+    pub signal: Option<i32>,
+    pub cpu_time: Option<f64>,
+    pub wall_time: Option<f64>,
 }
 
 #[gen_stub_pymethods]
 #[pymethods]
 impl ExecutionResult {
     #[new]
-    fn new(status: JudgeStatus, time: f64, memory: f64, score: i64) -> Self {
+    #[pyo3(signature = (status, time, memory, score, message=None, compile_log=None, stderr=None, signal=None, cpu_time=None, wall_time=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        status: JudgeStatus,
+        time: f64,
+        memory: f64,
+        score: i64,
+        message: Option<String>,
+        compile_log: Option<String>,
+        stderr: Option<String>,
+        signal: Option<i32>,
+        cpu_time: Option<f64>,
+        wall_time: Option<f64>,
+    ) -> Self {
         ExecutionResult {
             status,
             time,
             memory,
             score,
+            message,
+            compile_log,
+            stderr,
+            signal,
+            cpu_time,
+            wall_time,
         }
     }
     #[getter]
@@ -54,11 +141,37 @@ impl ExecutionResult {
     fn score(&self) -> i64 {
         self.score
     }
+    #[getter]
+    fn message(&self) -> Option<String> {
+        self.message.clone()
+    }
+    #[getter]
+    fn compile_log(&self) -> Option<String> {
+        self.compile_log.clone()
+    }
+    #[getter]
+    fn stderr(&self) -> Option<String> {
+        self.stderr.clone()
+    }
+    #[getter]
+    fn signal(&self) -> Option<i32> {
+        self.signal
+    }
+    #[getter]
+    fn cpu_time(&self) -> Option<f64> {
+        self.cpu_time
+    }
+    #[getter]
+    fn wall_time(&self) -> Option<f64> {
+        self.wall_time
+    }
 }
 
 #[pyfunction]
 #[gen_stub_pyfunction(module = "traopy_util.util.v0")]
-#[pyo3(signature = (status, time_ms, memory_kib, score, continue_next, message=None))]
+#[pyo3(signature = (status, time_ms, memory_kib, score, continue_next, message=None, compile_log=None, stderr=None, signal=None, cpu_time_ms=None, wall_time_ms=None))]
+#[allow(clippy::too_many_arguments)]
+/// `compile_log` and `stderr` are truncated to `judge_output::EXCERPT_MAX_BYTES`
 pub fn jsonify_displayable_output(
     status: JudgeStatus,
     time_ms: f64,
@@ -66,17 +179,13 @@ pub fn jsonify_displayable_output(
     score: i64,
     continue_next: bool,
     message: Option<String>,
+    compile_log: Option<String>,
+    stderr: Option<String>,
+    signal: Option<i32>,
+    cpu_time_ms: Option<f64>,
+    wall_time_ms: Option<f64>,
 ) -> PyResult<String> {
-    let inner_status = match status {
-        JudgeStatus::AC => judge_output::JudgeStatus::AC,
-        JudgeStatus::WA => judge_output::JudgeStatus::WA,
-        JudgeStatus::TLE => judge_output::JudgeStatus::TLE,
-        JudgeStatus::MLE => judge_output::JudgeStatus::MLE,
-        JudgeStatus::OLE => judge_output::JudgeStatus::OLE,
-        JudgeStatus::RE => judge_output::JudgeStatus::RE,
-        JudgeStatus::CE => judge_output::JudgeStatus::CE,
-        JudgeStatus::WE => judge_output::JudgeStatus::WE,
-    };
+    let inner_status = status.into();
     let continue_status = if continue_next {
         judge_output::ContinueStatus::Continue
     } else {
@@ -89,6 +198,11 @@ pub fn jsonify_displayable_output(
         score,
         message,
         continue_status,
+        compile_log: compile_log.map(|log| judge_output::truncate_excerpt(&log)),
+        stderr: stderr.map(|stderr| judge_output::truncate_excerpt(&stderr)),
+        signal,
+        cpu_time: cpu_time_ms,
+        wall_time: wall_time_ms,
     };
     let wrapped = judge_output::ExecutionResult::Displayable(result);
     let json = serde_json::to_string(&wrapped)
@@ -111,30 +225,68 @@ pub fn jsonify_hidden_output(continue_next: bool) -> PyResult<String> {
     Ok(json)
 }
 
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.v0")]
+/// `results` are pairs of a testcase name and the output of `jsonify_displayable_output`
+pub fn jsonify_batched_output(
+    results: Vec<(String, String)>,
+    continue_next: bool,
+) -> PyResult<String> {
+    let continue_status = if continue_next {
+        judge_output::ContinueStatus::Continue
+    } else {
+        judge_output::ContinueStatus::Stop
+    };
+    let results = results
+        .into_iter()
+        .map(|(sub_name, json)| {
+            let inner_output = serde_json::from_str::<judge_output::ExecutionResult>(&json)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?;
+            match inner_output {
+                judge_output::ExecutionResult::Displayable(result) => {
+                    Ok(judge_output::SubExecutionResult { sub_name, result })
+                }
+                _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "result of {} is not displayable",
+                    sub_name
+                ))),
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let result = judge_output::BatchedExecutionResult {
+        results,
+        continue_status,
+    };
+    let wrapped = judge_output::ExecutionResult::Batched(result);
+    let json = serde_json::to_string(&wrapped)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?;
+    Ok(json)
+}
+
+impl From<judge_output::DisplayableExecutionResult> for ExecutionResult {
+    fn from(result: judge_output::DisplayableExecutionResult) -> Self {
+        ExecutionResult {
+            status: result.status.into(),
+            time: result.time,
+            memory: result.memory,
+            score: result.score,
+            message: result.message,
+            compile_log: result.compile_log,
+            stderr: result.stderr,
+            signal: result.signal,
+            cpu_time: result.cpu_time,
+            wall_time: result.wall_time,
+        }
+    }
+}
+
 #[pyfunction]
 #[gen_stub_pyfunction(module = "traopy_util.util.v0")]
 pub fn dejsonify_output(json: String) -> PyResult<Option<ExecutionResult>> {
     let inner_output = serde_json::from_str::<judge_output::ExecutionResult>(&json)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?;
     let result = match inner_output {
-        judge_output::ExecutionResult::Displayable(result) => {
-            let status = match result.status {
-                judge_output::JudgeStatus::AC => JudgeStatus::AC,
-                judge_output::JudgeStatus::WA => JudgeStatus::WA,
-                judge_output::JudgeStatus::TLE => JudgeStatus::TLE,
-                judge_output::JudgeStatus::MLE => JudgeStatus::MLE,
-                judge_output::JudgeStatus::OLE => JudgeStatus::OLE,
-                judge_output::JudgeStatus::RE => JudgeStatus::RE,
-                judge_output::JudgeStatus::CE => JudgeStatus::CE,
-                judge_output::JudgeStatus::WE => JudgeStatus::WE,
-            };
-            Some(ExecutionResult {
-                status,
-                time: result.time,
-                memory: result.memory,
-                score: result.score,
-            })
-        }
+        judge_output::ExecutionResult::Displayable(result) => Some(result.into()),
         _ => None,
     };
     Ok(result)
@@ -142,34 +294,30 @@ pub fn dejsonify_output(json: String) -> PyResult<Option<ExecutionResult>> {
 
 #[pyfunction]
 #[gen_stub_pyfunction(module = "traopy_util.util.v0")]
-// WE > CE > RE > OLE > WA > TLE > MLE > AC
-pub fn merge_judge_status(statuses: Vec<JudgeStatus>) -> PyResult<JudgeStatus> {
-    let mut status_idx = 7;
-    for status in statuses {
-        let idx = match status {
-            JudgeStatus::WE => 0,
-            JudgeStatus::CE => 1,
-            JudgeStatus::RE => 2,
-            JudgeStatus::OLE => 3,
-            JudgeStatus::WA => 4,
-            JudgeStatus::TLE => 5,
-            JudgeStatus::MLE => 6,
-            JudgeStatus::AC => 7,
-        };
-        if idx < status_idx {
-            status_idx = idx;
-        }
-    }
-    let status = match status_idx {
-        0 => JudgeStatus::WE,
-        1 => JudgeStatus::CE,
-        2 => JudgeStatus::RE,
-        3 => JudgeStatus::OLE,
-        4 => JudgeStatus::WA,
-        5 => JudgeStatus::TLE,
-        6 => JudgeStatus::MLE,
-        7 => JudgeStatus::AC,
-        _ => unreachable!(),
+/// Pairs of a testcase name and its result, `None` unless `json` is a batched output
+pub fn dejsonify_batched_output(json: String) -> PyResult<Option<Vec<(String, ExecutionResult)>>> {
+    let inner_output = serde_json::from_str::<judge_output::ExecutionResult>(&json)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?;
+    let result = match inner_output {
+        judge_output::ExecutionResult::Batched(batched) => Some(
+            batched
+                .results
+                .into_iter()
+                .map(|sub| (sub.sub_name, sub.result.into()))
+                .collect(),
+        ),
+        _ => None,
     };
+    Ok(result)
+}
+
+#[pyfunction]
+#[gen_stub_pyfunction(module = "traopy_util.util.v0")]
+// WE > IE > CE > RE > OLE > WA > PE > TLE > MLE > PA > SKIP > AC
+pub fn merge_judge_status(statuses: Vec<JudgeStatus>) -> PyResult<JudgeStatus> {
+    let status = statuses
+        .into_iter()
+        .min_by_key(JudgeStatus::severity)
+        .unwrap_or(JudgeStatus::AC);
     Ok(status)
 }
@@ -0,0 +1,129 @@
+use judge_core::model::judge_output;
+use nix::libc::{
+    SIGKILL, WEXITSTATUS, WIFEXITED, WIFSIGNALED, WTERMSIG, c_int, kill, rusage, wait4,
+};
+use std::io::Read;
+use std::mem::MaybeUninit;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+#[cfg(not(target_os = "macos"))]
+const RUSAGE_MAXRSS_IS_KIB: bool = true;
+#[cfg(target_os = "macos")]
+const RUSAGE_MAXRSS_IS_KIB: bool = false;
+/// How long a process may run past its time limit before it is killed
+pub const KILL_GRACE_MS: i64 = 200;
+const STDERR_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(200);
+
+pub struct WaitResult {
+    pub exit_code: i32,
+    pub signal: Option<i32>,
+    pub time_ms: i64,
+    pub memory_kib: i64,
+}
+
+/// Wait for the process and collect its CPU time and peak memory.
+pub fn wait_with_usage(pid: i32) -> WaitResult {
+    let mut usage = MaybeUninit::<rusage>::uninit();
+    let mut status = MaybeUninit::<c_int>::uninit();
+    unsafe { wait4(pid, status.as_mut_ptr(), 0, usage.as_mut_ptr()) };
+    let status = unsafe { status.assume_init() };
+    let exit_code = if WIFEXITED(status) {
+        WEXITSTATUS(status)
+    } else {
+        -1
+    };
+    let signal = if WIFSIGNALED(status) {
+        Some(WTERMSIG(status))
+    } else {
+        None
+    };
+    let (mut memory_used, cpu_time_elapsed) = unsafe {
+        let usage = usage.assume_init();
+        (
+            usage.ru_maxrss as i64,
+            usage.ru_utime.tv_sec as f64
+                + usage.ru_stime.tv_sec as f64
+                + (usage.ru_utime.tv_usec as f64 + usage.ru_stime.tv_usec as f64) * 1e-6,
+        )
+    };
+    if !RUSAGE_MAXRSS_IS_KIB {
+        memory_used /= 1024;
+    }
+    WaitResult {
+        exit_code,
+        signal,
+        time_ms: (cpu_time_elapsed * 1000.0) as i64,
+        memory_kib: memory_used,
+    }
+}
+
+/// Kill the process after `time_limit_ms` unless it is marked as finished.
+pub fn spawn_killer(pid: i32, time_limit_ms: i64, finished: Arc<Mutex<bool>>) {
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(
+            (time_limit_ms + KILL_GRACE_MS) as u64,
+        ));
+
+        // If finished flag is locked, this means the process has already completed
+        if let Ok(mut done) = finished.lock() {
+            if !*done {
+                let _ = unsafe { kill(pid, SIGKILL) };
+                *done = true;
+            }
+        }
+    });
+}
+
+/// Returns true if the killer thread has already killed the process
+pub fn mark_finished(finished: &Arc<Mutex<bool>>) -> bool {
+    let mut done = finished.lock().unwrap();
+    if *done {
+        true
+    } else {
+        *done = true;
+        false
+    }
+}
+
+/// Keeps the head of a child's stderr, see `judge_output::EXCERPT_MAX_BYTES`
+pub struct StderrExcerpt {
+    excerpt: Arc<Mutex<Vec<u8>>>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl StderrExcerpt {
+    /// Reads `stderr` on its own thread so that the child never blocks on a full pipe
+    pub fn spawn(stderr: Option<impl Read + Send + 'static>) -> Self {
+        let excerpt = Arc::new(Mutex::new(Vec::new()));
+        let reader = stderr.map(|stderr| {
+            let excerpt = Arc::clone(&excerpt);
+            std::thread::spawn(move || read_excerpt(stderr, &excerpt))
+        });
+        Self { excerpt, reader }
+    }
+
+    /// Call once the child has exited
+    pub fn finish(self) -> String {
+        // A leftover descendant may still hold stderr open, so only wait a little for the reader
+        if let Some(reader) = self.reader {
+            let deadline = std::time::Instant::now() + STDERR_DRAIN_TIMEOUT;
+            while !reader.is_finished() && std::time::Instant::now() < deadline {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+        }
+        judge_output::truncate_excerpt(&String::from_utf8_lossy(&self.excerpt.lock().unwrap()))
+    }
+}
+
+/// Reads `reader` to the end so that the writer never blocks, keeping only the head
+fn read_excerpt(mut reader: impl Read, excerpt: &Mutex<Vec<u8>>) {
+    let mut buf = [0u8; 8192];
+    while let Ok(n) = reader.read(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        let mut excerpt = excerpt.lock().unwrap();
+        let rest = judge_output::EXCERPT_MAX_BYTES.saturating_sub(excerpt.len());
+        excerpt.extend_from_slice(&buf[..n.min(rest)]);
+    }
+}
@@ -22,3 +22,17 @@ def visualize(
         graph_width_inch=graph_width_inch,
         graph_height_inch=graph_height_inch,
     )
+
+def visualize_timeline(
+    resp: str,
+    title: str = "traOJudge Execution Timeline",
+    graph_width_inch: int = 12,
+    graph_height_inch: int = 8,
+) -> None:
+    entries = _parser.parse_timeline(resp)
+    _visualize.visualize_timeline(
+        entries,
+        title=title,
+        graph_width_inch=graph_width_inch,
+        graph_height_inch=graph_height_inch,
+    )
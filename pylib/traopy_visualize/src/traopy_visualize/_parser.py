@@ -11,8 +11,13 @@ class _JudgeStatus(enum.Enum):
     RE = enum.auto()
     CE = enum.auto()
     WE = enum.auto()
+    IE = enum.auto()
+    SKIP = enum.auto()
+    PE = enum.auto()
+    PA = enum.auto()
     Hidden = enum.auto()
     EarlyExit = enum.auto()
+    ConditionUnmet = enum.auto()
 
 class _Node(enum.Enum):
     STATIC_TEXT = enum.auto()
@@ -57,16 +62,19 @@ def parse(schema: str, resp: str | None) -> _Visualizable:
         nodes[execution['name']] = _Node.EXECUTION
         judge_status[execution['name']] = None
         for dep in execution['dependencies']:
-            edges.append(_Edge(src=dep['ref_to'], dst=execution['name'], label=dep['envvar_name']))
+            edges.append(_Edge(src=dep['ref_to'], dst=execution['name'], label=_dependency_label(dep)))
         edges.append(_Edge(src=execution['script_name'], dst=execution['name'], label='script'))
     for script in schema['scripts']:
         nodes[script['name']] = _Node.SCRIPT
     
     if resp is not None:
         resp = json.loads(resp)
-        for (name, result) in resp.items():
+        for (name, report) in resp.items():
+            result = report['result']
             if result == "EarlyExit":
                     status = _JudgeStatus.EarlyExit
+            elif result == "ConditionUnmet":
+                status = _JudgeStatus.ConditionUnmet
             elif 'Displayable' in result['ExecutionResult']:
                 if result['ExecutionResult']['Displayable']['status'] == 'AC':
                     status = _JudgeStatus.AC
@@ -84,6 +92,18 @@ def parse(schema: str, resp: str | None) -> _Visualizable:
                     status = _JudgeStatus.CE
                 elif result['ExecutionResult']['Displayable']['status'] == 'WE':
                     status = _JudgeStatus.WE
+                elif result['ExecutionResult']['Displayable']['status'] == 'IE':
+                    status = _JudgeStatus.IE
+                elif result['ExecutionResult']['Displayable']['status'] == 'SKIP':
+                    status = _JudgeStatus.SKIP
+                elif result['ExecutionResult']['Displayable']['status'] == 'PE':
+                    status = _JudgeStatus.PE
+                elif result['ExecutionResult']['Displayable']['status'] == 'PA':
+                    status = _JudgeStatus.PA
+            elif 'Batched' in result['ExecutionResult']:
+                # a batch shows the first testcase that was not accepted
+                statuses = [sub['result']['status'] for sub in result['ExecutionResult']['Batched']['results']]
+                status = _JudgeStatus[next((s for s in statuses if s != 'AC'), 'AC')]
             else:
                 status = _JudgeStatus.Hidden
             judge_status[name] = status
@@ -123,10 +143,66 @@ def parse(schema: str, resp: str | None) -> _Visualizable:
                 node_styles[node] = {'shape': 'o', 'color': 'orange', 'size': 1000.0}
             elif judge_status[node] == _JudgeStatus.WE:
                 node_styles[node] = {'shape': 'o', 'color': 'red', 'size': 1000.0}
+            elif judge_status[node] == _JudgeStatus.IE:
+                node_styles[node] = {'shape': 'o', 'color': 'red', 'size': 1000.0}
+            elif judge_status[node] == _JudgeStatus.SKIP:
+                node_styles[node] = {'shape': 'o', 'color': 'gray', 'size': 1000.0}
+            elif judge_status[node] == _JudgeStatus.PE:
+                node_styles[node] = {'shape': 'o', 'color': 'orange', 'size': 1000.0}
+            elif judge_status[node] == _JudgeStatus.PA:
+                node_styles[node] = {'shape': 'o', 'color': 'yellowgreen', 'size': 1000.0}
             elif judge_status[node] == _JudgeStatus.Hidden:
                 node_styles[node] = {'shape': 'o', 'color': 'gray', 'size': 1000.0}
             elif judge_status[node] == _JudgeStatus.EarlyExit:
                 node_styles[node] = {'shape': 'o', 'color': 'lightseagreen', 'size': 1000.0}
+            elif judge_status[node] == _JudgeStatus.ConditionUnmet:
+                node_styles[node] = {'shape': 'o', 'color': 'lightgray', 'size': 1000.0}
             else:
                 node_styles[node] = {'shape': 'o', 'color': 'olivedrab', 'size': 1000.0}
-    return _Visualizable(graph=graph, edge_labels=edge_labels, node_styles=node_styles)
\ No newline at end of file
+    return _Visualizable(graph=graph, edge_labels=edge_labels, node_styles=node_styles)
+
+def _dependency_label(dep) -> str:
+    # guarded edges show the statuses they are taken on, e.g. "RESULT [AC]" or "RESULT [not AC]"
+    condition = dep.get('condition')
+    if condition is None:
+        return dep['envvar_name']
+    if 'StatusIn' in condition:
+        return f"{dep['envvar_name']} [{','.join(condition['StatusIn'])}]"
+    return f"{dep['envvar_name']} [not {','.join(condition['StatusNotIn'])}]"
+
+class _TimelineEntry:
+    name: str
+    ready_at_ms: int
+    started_at_ms: int | None
+    finished_at_ms: int
+    host: str | None
+    retry_count: int
+    cached: bool
+
+    def __init__(self, name: str, ready_at_ms: int, started_at_ms: int | None, finished_at_ms: int, host: str | None, retry_count: int, cached: bool) -> None:
+        self.name = name
+        self.ready_at_ms = ready_at_ms
+        self.started_at_ms = started_at_ms
+        self.finished_at_ms = finished_at_ms
+        self.host = host
+        self.retry_count = retry_count
+        self.cached = cached
+
+def parse_timeline(resp: str) -> list[_TimelineEntry]:
+    resp = json.loads(resp)
+    entries = []
+    for (name, report) in resp.items():
+        metadata = report.get('metadata')
+        if metadata is None:
+            continue
+        entries.append(_TimelineEntry(
+            name=name,
+            ready_at_ms=metadata['ready_at_ms'],
+            started_at_ms=metadata['started_at_ms'],
+            finished_at_ms=metadata['finished_at_ms'],
+            host=metadata['host'],
+            retry_count=metadata['retry_count'],
+            cached=metadata['cached'],
+        ))
+    entries.sort(key=lambda entry: entry.ready_at_ms)
+    return entries
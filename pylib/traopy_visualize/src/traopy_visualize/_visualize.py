@@ -54,3 +54,33 @@ def visualize_dag(
     plt.title(title)
     plt.axis("off")
     plt.show()
+
+def visualize_timeline(
+    entries: list,
+    title: str,
+    graph_width_inch: int = 12,
+    graph_height_inch: int = 8,
+) -> None:
+    if len(entries) == 0:
+        return
+    origin = min(entry.ready_at_ms for entry in entries)
+    for (row, entry) in enumerate(entries):
+        ready = (entry.ready_at_ms - origin) / 1000.0
+        finished = (entry.finished_at_ms - origin) / 1000.0
+        if entry.started_at_ms is None:
+            started = finished
+        else:
+            started = (entry.started_at_ms - origin) / 1000.0
+        # 待ち時間と実行時間を分けて描く
+        plt.barh(row, started - ready, left=ready, color="lightgray")
+        plt.barh(row, finished - started, left=started, color="lightseagreen" if entry.cached else "steelblue")
+        label = entry.host or ""
+        if entry.retry_count > 0:
+            label += f" (retry {entry.retry_count})"
+        plt.text(finished, row, " " + label, va="center", fontsize=6.0)
+    plt.yticks(range(len(entries)), [entry.name for entry in entries], fontsize=6.0)
+    plt.gca().invert_yaxis()
+    plt.xlabel("seconds")
+    plt.gcf().set_size_inches(graph_width_inch, graph_height_inch)
+    plt.title(title)
+    plt.show()